@@ -169,7 +169,7 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
         gen
     }
 
-    fn sqr_impl(a: proc_macro2::TokenStream, limbs: usize) -> proc_macro2::TokenStream {
+    fn sqr_impl(a: proc_macro2::TokenStream, mont_reduce: proc_macro2::TokenStream, limbs: usize) -> proc_macro2::TokenStream {
         let mut gen = proc_macro2::TokenStream::new();
 
         for i in 0..(limbs - 1) {
@@ -245,7 +245,7 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
         );
 
         gen.extend(quote!{
-            self.mont_reduce(modulus, mont_inv, #mont_calling);
+            self.#mont_reduce(modulus, mont_inv, #mont_calling);
         });
 
         gen
@@ -254,6 +254,7 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
     fn mul_impl(
         a: proc_macro2::TokenStream,
         b: proc_macro2::TokenStream,
+        mont_reduce: proc_macro2::TokenStream,
         limbs: usize,
     ) -> proc_macro2::TokenStream {
         let mut gen = proc_macro2::TokenStream::new();
@@ -291,14 +292,16 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
         );
 
         gen.extend(quote!{
-            self.mont_reduce(modulus, mont_inv, #mont_calling);
+            self.#mont_reduce(modulus, mont_inv, #mont_calling);
         });
 
         gen
     }
 
-    let squaring_impl = sqr_impl(quote!{self}, limbs);
-    let multiply_impl = mul_impl(quote!{self}, quote!{other}, limbs);
+    let squaring_impl = sqr_impl(quote!{self}, quote!{mont_reduce}, limbs);
+    let multiply_impl = mul_impl(quote!{self}, quote!{other}, quote!{mont_reduce}, limbs);
+    let squaring_ct_impl = sqr_impl(quote!{self}, quote!{mont_reduce_ct}, limbs);
+    let multiply_ct_impl = mul_impl(quote!{self}, quote!{other}, quote!{mont_reduce_ct}, limbs);
     let montgomery_impl = mont_impl(limbs);
 
     quote! {
@@ -326,6 +329,19 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
                 self.reduce(&modulus);
             }
 
+            #[inline(always)]
+            fn mont_reduce_ct(
+                &mut self,
+                modulus: &#repr,
+                mont_inv: u64,
+                #mont_paramlist
+            )
+            {
+                #montgomery_impl
+
+                self.reduce_ct(&modulus);
+            }
+
             #[inline(always)]
             fn reduce(
                 &mut self,
@@ -336,6 +352,26 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
                     self.sub_noborrow(&modulus);
                 }
             }
+
+            #[inline(always)]
+            fn reduce_ct(
+                &mut self,
+                modulus: &#repr
+            )
+            {
+                // always subtract and then select, so there is no branch on the value
+                let mut reduced = *self;
+                let mut borrow = 0;
+                for (a, b) in reduced.0.iter_mut().zip(modulus.0.iter()) {
+                    *a = crate::arithmetics::sbb(*a, *b, &mut borrow);
+                }
+
+                // borrow means that the value is already less than the modulus
+                let mask = ::std::hint::black_box(0u64.wrapping_sub(borrow));
+                for (a, b) in self.0.iter_mut().zip(reduced.0.iter()) {
+                    *a = (*a & mask) | (*b & !mask);
+                }
+            }
         }
 
         impl ::std::fmt::Debug for #repr
@@ -578,6 +614,18 @@ fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenS
                 self.reduce(modulus);
             }
 
+            #[inline]
+            fn mont_mul_assign_ct(&mut self, other: &#repr, modulus: &#repr, mont_inv: u64)
+            {
+                #multiply_ct_impl
+            }
+
+            #[inline]
+            fn mont_square_ct(&mut self, modulus: &#repr, mont_inv: u64)
+            {
+                #squaring_ct_impl
+            }
+
             #[inline(always)]
             fn into_normal_repr(&self, modulus: &#repr, mont_inv: u64) -> #repr {
                 let mut r = *self;
@@ -15,63 +15,26 @@
 /// Assumptions:
 /// - one byte for length encoding
 
-use crate::weierstrass::Group;
+use crate::weierstrass::{Group, CurveType};
 use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::projective::ProjectivePoint;
+use crate::context::CurveContext;
 use crate::field::{SizedPrimeField, field_from_modulus};
+use crate::field::*;
 use crate::fp::Fp;
-use crate::field::{U256Repr, U320Repr};
+use crate::traits::FieldElement;
 use crate::representation::ElementRepr;
+use crate::cache::ContextCache;
+#[cfg(feature = "op_counter")]
+use crate::op_counter::{self, OperationCounts};
 
 use num_bigint::BigUint;
 use num_traits::{Zero};
 
-const BYTES_FOR_LENGTH_ENCODING: usize = 1;
-
-#[macro_use]
-macro_rules! create_field {
-    ($bytes:expr, $repr:tt) => {
-        {
-            let ((modulus, modulus_len), rest) = get_field_params($bytes)?;
-            let field = field_from_modulus::<$repr>(modulus)?;
-            if rest.len() < modulus_len {
-                return Err(());
-            }
-
-            (field, modulus_len, rest)
-        }
-    }
-}
-
-macro_rules! get_ab {
-    ($rest:expr, $field:expr, $modulus_len: expr) => {
-        {
-            if $rest.len() < $modulus_len {
-                return Err(());
-            }
-            let (a_encoding, rest) = $rest.split_at($modulus_len);
-            let a = Fp::from_be_bytes(&$field, a_encoding, true).map_err(|_| ())?;
-            if rest.len() < $modulus_len {
-                return Err(());
-            }
-            let (b_encoding, rest) = rest.split_at($modulus_len);
-            let b = Fp::from_be_bytes(&$field, b_encoding, true).map_err(|_| ())?;
-            
-            (a, b, rest)
-        }
-    }
-}
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
 
-macro_rules! create_group {
-    ($bytes:expr, $repr:tt) => {
-        {
-            let ((order, order_len), rest) = get_curve_params($bytes)?;
-            let order = BigUint::from_bytes_be(&order);
-            let group = field_from_modulus::<$repr>(order)?;
-
-            (group, order_len, rest)
-        }
-    }
-}
+const BYTES_FOR_LENGTH_ENCODING: usize = 1;
 
 pub trait PrecompileAPI {
     // fn decompress_point(bytes: &[u8]) -> Vec<u8>;
@@ -85,43 +48,66 @@ pub struct ApiImplementation<FE: ElementRepr, GE: ElementRepr> {
     _marker_ge: std::marker::PhantomData<GE>
 }
 
-impl<FE: ElementRepr, GE: ElementRepr> PrecompileAPI for ApiImplementation<FE, GE> {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ()> {
-        let (field, modulus_len, rest) = create_field!(bytes, FE);
-        let (a, b, rest) = get_ab!(rest, field, modulus_len);
-        let (group, order_len, rest) = create_group!(rest, GE);
+impl<FE: ElementRepr, GE: ElementRepr> ApiImplementation<FE, GE> {
+    pub(crate) fn create_context(bytes: &[u8]) -> Result<CurveContext<FE, GE>, ()> {
+        let (modulus, _, a_encoding, b_encoding, order, _, _) = parse_encodings(bytes)?;
+        let field = field_from_modulus::<FE>(modulus)?;
+        let group = field_from_modulus::<GE>(order)?;
+        let a = Fp::from_be_bytes(&field, a_encoding, true).map_err(|_| ())?.repr;
+        let b = Fp::from_be_bytes(&field, b_encoding, true).map_err(|_| ())?.repr;
+
+        Ok(CurveContext {
+            base_field: field,
+            scalar_field: group,
+            a,
+            b,
+            glv: OnceLock::new(),
+            two_torsion: OnceLock::new()
+        })
+    }
 
-        let curve = WeierstrassCurve::new(&group, a, b);
+    pub(crate) fn cached_context(bytes: &[u8], cache: &ContextCache) -> Result<Arc<CurveContext<FE, GE>>, ()> {
+        let header = split_header(bytes)?;
 
-        let (mut p_0, rest) = decode_point_from_xy(rest, modulus_len, &curve)?;
-        let (p_1, _rest) = decode_point_from_xy(rest, modulus_len, &curve)?;
+        cache.get_or_try_insert_with(header, || Self::create_context(bytes))
+    }
 
-        p_0.add_assign(&p_1);
+    pub(crate) fn add_points_in_context(context: &CurveContext<FE, GE>, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        let (_, modulus_len, _, _, _, _, rest) = parse_encodings(bytes)?;
 
-        serialize_point(modulus_len, &p_0)   
-    }
+        context.with_curve(|curve| {
+            let (p_0, rest) = decode_point_from_xy(rest, modulus_len, curve)?;
+            let (p_1, _rest) = decode_point_from_xy(rest, modulus_len, curve)?;
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ()> {
-        let (field, modulus_len, rest) = create_field!(bytes, FE);
-        let (a, b, rest) = get_ab!(rest, field, modulus_len);
-        let (group, order_len, rest) = create_group!(rest, GE);
+            let p = if curve.has_points_of_order_two() {
+                CurvePoint::add_points(&p_0, &p_1)
+            } else {
+                ProjectivePoint::add_points(&p_0, &p_1)
+            };
 
-        let curve = WeierstrassCurve::new(&group, a, b);
+            serialize_point(modulus_len, &p)
+        })
+    }
 
-        let (p_0, rest) = decode_point_from_xy(rest, modulus_len, &curve)?;
-        let (scalar, _rest) = decode_scalar_representation(rest, order_len, &group)?;
+    pub(crate) fn mul_point_in_context(context: &CurveContext<FE, GE>, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        let (_, modulus_len, _, _, _, order_len, rest) = parse_encodings(bytes)?;
 
-        let p = p_0.mul(&scalar);
+        context.with_curve(|curve| {
+            let (p_0, rest) = decode_point_from_xy(rest, modulus_len, curve)?;
+            let (scalar, _rest) = decode_scalar_representation(rest, order_len, curve.scalar_field)?;
 
-        serialize_point(modulus_len, &p)   
-    }
+            let p = if curve.has_points_of_order_two() {
+                CurvePoint::mul_point(&p_0, &scalar)
+            } else {
+                ProjectivePoint::mul_point(&p_0, &scalar)
+            };
 
-    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ()> {
-        let (field, modulus_len, rest) = create_field!(bytes, FE);
-        let (a, b, rest) = get_ab!(rest, field, modulus_len);
-        let (group, order_len, rest) = create_group!(rest, GE);
+            serialize_point(modulus_len, &p)
+        })
+    }
 
-        let curve = WeierstrassCurve::new(&group, a, b);
+    pub(crate) fn multiexp_in_context(context: &CurveContext<FE, GE>, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        let (_, modulus_len, _, _, _, order_len, rest) = parse_encodings(bytes)?;
 
         let expected_pair_len = 2*modulus_len + order_len;
         if rest.len() % expected_pair_len != 0 {
@@ -133,19 +119,142 @@ impl<FE: ElementRepr, GE: ElementRepr> PrecompileAPI for ApiImplementation<FE, G
             return Err(());
         }
 
-        let mut acc = CurvePoint::zero(&curve);
+        context.with_curve(|curve| {
+            let mut points = Vec::with_capacity(expected_pairs);
+            let mut scalars = Vec::with_capacity(expected_pairs);
+
+            let mut global_rest = rest;
+
+            for _ in 0..expected_pairs {
+                let (p, local_rest) = decode_point_from_xy(global_rest, modulus_len, curve)?;
+                let (scalar, local_rest) = decode_scalar_representation(local_rest, order_len, curve.scalar_field)?;
+
+                points.push(p);
+                scalars.push(scalar);
+                global_rest = local_rest;
+            }
+
+            let acc = if curve.has_points_of_order_two() {
+                CurvePoint::multiexp(curve, &points, &scalars)
+            } else {
+                ProjectivePoint::multiexp(curve, &points, &scalars)
+            };
+
+            serialize_point(modulus_len, &acc)
+        })
+    }
+
+    pub(crate) fn run(call: ApiCall, bytes: &[u8], cache: Option<&ContextCache>) -> Result<Vec<u8>, ()> {
+        let context = match cache {
+            Some(cache) => Self::cached_context(bytes, cache)?,
+            None => Arc::new(Self::create_context(bytes)?)
+        };
+
+        match call {
+            ApiCall::Prepare => Ok(vec![]),
+            ApiCall::Execute(OperationType::AddPoints) => Self::add_points_in_context(&context, bytes),
+            ApiCall::Execute(OperationType::MulPoint) => Self::mul_point_in_context(&context, bytes),
+            ApiCall::Execute(OperationType::Multiexp) => Self::multiexp_in_context(&context, bytes),
+        }
+    }
+}
+
+/// Coordinates for the arithmetic of a call. Points are decoded into Jacobian
+/// coordinates, that handle P + P, P + (-P) and the point at infinity with branches.
+/// Curves without points of order two use complete projective formulas instead
+trait Representation<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    GE: ElementRepr,
+    G: SizedPrimeField<Repr = GE>
+    >: Group + Clone
+{
+    fn zero(curve: &'a WeierstrassCurve<'a, FE, F, GE, G>) -> Self;
+    fn from_jacobian(point: &CurvePoint<'a, FE, F, GE, G>) -> Self;
+    fn into_jacobian(self) -> CurvePoint<'a, FE, F, GE, G>;
+
+    fn add_points(p_0: &CurvePoint<'a, FE, F, GE, G>, p_1: &CurvePoint<'a, FE, F, GE, G>) -> CurvePoint<'a, FE, F, GE, G> {
+        let mut p = Self::from_jacobian(p_0);
+        p.add_assign(&Self::from_jacobian(p_1));
 
-        let mut global_rest = rest;
+        p.into_jacobian()
+    }
 
-        for _ in 0..expected_pairs {
-            let (p, local_rest) = decode_point_from_xy(global_rest, modulus_len, &curve)?;
-            let (scalar, local_rest) = decode_scalar_representation(local_rest, order_len, &group)?;
+    fn mul_point(p: &CurvePoint<'a, FE, F, GE, G>, scalar: &GE) -> CurvePoint<'a, FE, F, GE, G> {
+        Self::from_jacobian(p).mul(scalar).into_jacobian()
+    }
 
-            acc.add_assign(&p.mul(&scalar));
-            global_rest = local_rest;
+    fn multiexp(
+        curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+        points: &[CurvePoint<'a, FE, F, GE, G>],
+        scalars: &[GE]
+    ) -> CurvePoint<'a, FE, F, GE, G> {
+        let mut acc = Self::zero(curve);
+        for (p, scalar) in points.iter().zip(scalars.iter()) {
+            acc.add_assign(&Self::from_jacobian(p).mul(scalar));
         }
 
-        serialize_point(modulus_len, &acc)   
+        acc.into_jacobian()
+    }
+}
+
+impl<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    GE: ElementRepr,
+    G: SizedPrimeField<Repr = GE>
+    > Representation<'a, FE, F, GE, G> for CurvePoint<'a, FE, F, GE, G>
+{
+    fn zero(curve: &'a WeierstrassCurve<'a, FE, F, GE, G>) -> Self {
+        CurvePoint::zero(curve)
+    }
+
+    fn from_jacobian(point: &CurvePoint<'a, FE, F, GE, G>) -> Self {
+        point.clone()
+    }
+
+    fn into_jacobian(self) -> CurvePoint<'a, FE, F, GE, G> {
+        self
+    }
+}
+
+impl<
+    'a,
+    FE: ElementRepr,
+    F: SizedPrimeField<Repr = FE>,
+    GE: ElementRepr,
+    G: SizedPrimeField<Repr = GE>
+    > Representation<'a, FE, F, GE, G> for ProjectivePoint<'a, FE, F, GE, G>
+{
+    fn zero(curve: &'a WeierstrassCurve<'a, FE, F, GE, G>) -> Self {
+        ProjectivePoint::zero(curve)
+    }
+
+    fn from_jacobian(point: &CurvePoint<'a, FE, F, GE, G>) -> Self {
+        ProjectivePoint::from_jacobian(point)
+    }
+
+    fn into_jacobian(self) -> CurvePoint<'a, FE, F, GE, G> {
+        self.to_jacobian()
+    }
+}
+
+impl<FE: ElementRepr, GE: ElementRepr> PrecompileAPI for ApiImplementation<FE, GE> {
+    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        let context = Self::create_context(bytes)?;
+        Self::add_points_in_context(&context, bytes)
+    }
+
+    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        let context = Self::create_context(bytes)?;
+        Self::mul_point_in_context(&context, bytes)
+    }
+
+    fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        let context = Self::create_context(bytes)?;
+        Self::multiexp_in_context(&context, bytes)
     }
 }
 
@@ -196,40 +305,6 @@ fn serialize_point<
     Ok(result)
 }
 
-fn get_field_params(bytes: &[u8]) -> Result<((BigUint, usize), &[u8]), ()> {
-    if bytes.len() < BYTES_FOR_LENGTH_ENCODING {
-        return Err(());
-    }
-    let (modulus_len, rest) = bytes.split_at(BYTES_FOR_LENGTH_ENCODING);
-    let modulus_len = modulus_len[0] as usize;
-
-    if rest.len() < modulus_len {
-        return Err(());
-    }
-    let (modulus_encoding, rest) = rest.split_at(modulus_len);
-    let modulus = BigUint::from_bytes_be(&modulus_encoding);
-    if modulus.is_zero() {
-        return Err(());
-    }
-
-    Ok(((modulus, modulus_len), rest))
-}
-
-fn get_curve_params(bytes: &[u8]) -> Result<((&[u8], usize), &[u8]), ()> {
-    if bytes.len() < BYTES_FOR_LENGTH_ENCODING {
-        return Err(());
-    }
-
-    let (order_len, rest) = bytes.split_at(BYTES_FOR_LENGTH_ENCODING);
-    let order_len = order_len[0] as usize;
-    if rest.len() < order_len {
-        return Err(());
-    }
-    let (order_encoding, rest) = rest.split_at(order_len);
-
-    Ok(((order_encoding, order_len), rest))
-}
-
 fn decode_point_from_xy<
     'a,
     FE: ElementRepr,
@@ -253,8 +328,21 @@ fn decode_point_from_xy<
     }
     let (y_encoding, rest) = rest.split_at(field_byte_len);
     let y = Fp::from_be_bytes(curve.base_field, y_encoding, true).map_err(|_| ())?;
+
+    // (0, 0) encodes the point at infinity, but for b = 0 it's a point of order
+    // two that is on the curve, so it's rejected as an input
+    if x.is_zero() && y.is_zero() {
+        if curve.curve_type == CurveType::BIsZero {
+            return Err(());
+        }
+
+        return Ok((CurvePoint::zero(curve), rest));
+    }
     
     let p: CurvePoint<'a, FE, F, GE, G> = CurvePoint::point_from_xy(&curve, x, y);
+    if !p.check_on_curve() {
+        return Err(());
+    }
     
     Ok((p, rest))
 }
@@ -356,75 +444,220 @@ fn parse_encodings<'a>(bytes: &'a [u8]) -> Result<(
     )
 }
 
-pub struct API;
+/// Returns the part of the call that encodes the curve parameters
+fn split_header(bytes: &[u8]) -> Result<&[u8], ()> {
+    let (_, _, _, _, _, _, rest) = parse_encodings(bytes)?;
 
-impl PrecompileAPI for API {
-    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ()> {
-        let (modulus, _, _, _, order, _, _) = parse_encodings(&bytes)?;
-        let modulus_limbs = (modulus.bits() / 64) + 1;
-        let order_limbs = (order.bits() / 64) + 1;
-
-        let result: Result<Vec<u8>, ()> = match (modulus_limbs, order_limbs) {
-            (4, 4) => {
-                ApiImplementation::<U256Repr, U256Repr>::add_points(&bytes)
-            },
-            (5, 4) => {
-                ApiImplementation::<U320Repr, U256Repr>::add_points(&bytes)
-            },
-            (5, 5) => {
-                ApiImplementation::<U320Repr, U320Repr>::add_points(&bytes)
-            },
+    Ok(&bytes[..(bytes.len() - rest.len())])
+}
+
+/// Generates a match over the pairs of (modulus limbs, order limbs)
+/// that calls the implementation with corresponding representations
+macro_rules! dispatch_on_limbs {
+    (
+        $modulus_limbs:expr, $order_limbs:expr, $call:expr, $bytes:expr, $cache:expr;
+        $( ($m:tt, $fe:ty) => [ $( ($o:tt, $ge:ty) ),* ] );*
+    ) => {
+        match ($modulus_limbs, $order_limbs) {
+            $(
+                $(
+                    ($m, $o) => {
+                        ApiImplementation::<$fe, $ge>::run($call, $bytes, $cache)
+                    },
+                )*
+            )*
             _ => {
-                unimplemented!();
+                Err(())
             }
-        };
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ApiCall {
+    /// Only construct the context (into the cache)
+    Prepare,
+    Execute(OperationType)
+}
+
+/// Group order is at most one limb larger than the modulus only if the modulus
+/// is just below the limb boundary, so only orders that fit into the
+/// number of limbs of the modulus are supported
+fn dispatch(call: ApiCall, bytes: &[u8], cache: Option<&ContextCache>) -> Result<Vec<u8>, ()> {
+    let (modulus_limbs, order_limbs) = limbs_for_header(bytes)?;
+
+    dispatch_on_limbs!(
+        modulus_limbs, order_limbs, call, bytes, cache;
+        (4, U256Repr) => [(4, U256Repr)];
+        (5, U320Repr) => [(4, U256Repr), (5, U320Repr)];
+        (6, U384Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr)];
+        (7, U448Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr)];
+        (8, U512Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr)];
+        (9, U576Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr)];
+        (10, U640Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr)];
+        (11, U704Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr), (11, U704Repr)];
+        (12, U768Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr), (11, U704Repr), (12, U768Repr)];
+        (13, U832Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr), (11, U704Repr), (12, U768Repr), (13, U832Repr)];
+        (14, U896Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr), (11, U704Repr), (12, U768Repr), (13, U832Repr), (14, U896Repr)];
+        (15, U960Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr), (11, U704Repr), (12, U768Repr), (13, U832Repr), (14, U896Repr), (15, U960Repr)];
+        (16, U1024Repr) => [(4, U256Repr), (5, U320Repr), (6, U384Repr), (7, U448Repr), (8, U512Repr), (9, U576Repr), (10, U640Repr), (11, U704Repr), (12, U768Repr), (13, U832Repr), (14, U896Repr), (15, U960Repr), (16, U1024Repr)]
+    )
+}
+
+fn limbs_for_header(bytes: &[u8]) -> Result<(usize, usize), ()> {
+    let (modulus, _, _, _, order, _, _) = parse_encodings(bytes)?;
+    let modulus_limbs = (modulus.bits() / 64) + 1;
+    let order_limbs = (order.bits() / 64) + 1;
+
+    Ok((modulus_limbs, order_limbs))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationType {
+    AddPoints,
+    MulPoint,
+    Multiexp
+}
+
+pub struct API;
+
+impl API {
+    pub fn execute(operation: OperationType, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        match operation {
+            OperationType::AddPoints => Self::add_points(bytes),
+            OperationType::MulPoint => Self::mul_point(bytes),
+            OperationType::Multiexp => Self::multiexp(bytes)
+        }
+    }
 
-        result
+    /// Executes the call and returns the number of base field operations it took.
+    /// Counts are exact and do not depend on the machine, so can be used to fit
+    /// the gas schedule
+    #[cfg(feature = "op_counter")]
+    pub fn execute_with_op_counts(operation: OperationType, bytes: &[u8]) -> (Result<Vec<u8>, ()>, OperationCounts) {
+        op_counter::reset_counts();
+        let result = Self::execute(operation, bytes);
+        let counts = op_counter::current_counts();
+
+        (result, counts)
     }
 
-    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ()> {
-        let (modulus, _, _, _, order, _, _) = parse_encodings(&bytes)?;
-        let modulus_limbs = (modulus.bits() / 64) + 1;
-        let order_limbs = (order.bits() / 64) + 1;
-        
-        let result: Result<Vec<u8>, ()> = match (modulus_limbs, order_limbs) {
-            (4, 4) => {
-                ApiImplementation::<U256Repr, U256Repr>::mul_point(&bytes)
-            },
-            (5, 4) => {
-                ApiImplementation::<U320Repr, U256Repr>::mul_point(&bytes)
-            },
-            (5, 5) => {
-                ApiImplementation::<U320Repr, U320Repr>::mul_point(&bytes)
-            },
-            _ => {
-                unimplemented!();
+    /// Executes independent calls, e.g. all the precompile calls of the block.
+    /// Field contexts are constructed once for every distinct curve header and
+    /// are shared by all the calls using it, and calls are split between
+    /// `num_threads` threads. Results are in the same order as calls and are
+    /// the same as if every call was executed separately.
+    pub fn execute_batch(calls: &[(OperationType, &[u8])], num_threads: usize) -> Vec<Result<Vec<u8>, ()>> {
+        let mut headers = HashSet::new();
+        for (_, bytes) in calls.iter() {
+            if let Ok(header) = split_header(bytes) {
+                headers.insert(header);
             }
-        };
+        }
+
+        let api = CachedAPI::new(headers.len());
+        // construct every context before splitting the work, so threads only
+        // read from the cache. Malformed headers will fail again in the call itself
+        for (_, bytes) in calls.iter() {
+            let _ = api.prepare(bytes);
+        }
+
+        let num_threads = std::cmp::max(num_threads, 1);
+        if num_threads == 1 || calls.len() < 2 {
+            return calls.iter().map(|(operation, bytes)| api.execute(*operation, bytes)).collect();
+        }
+
+        let chunk_size = calls.len().div_ceil(num_threads);
+
+        std::thread::scope(|scope| {
+            let api = &api;
+            let handles: Vec<_> = calls.chunks(chunk_size).map(|chunk| {
+                scope.spawn(move || {
+                    chunk.iter().map(|(operation, bytes)| api.execute(*operation, bytes)).collect::<Vec<_>>()
+                })
+            }).collect();
 
-        result
+            handles.into_iter().flat_map(|handle| {
+                handle.join().unwrap_or_else(|err| std::panic::resume_unwind(err))
+            }).collect()
+        })
+    }
+}
+
+impl PrecompileAPI for API {
+    fn add_points(bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        dispatch(ApiCall::Execute(OperationType::AddPoints), bytes, None)
+    }
+
+    fn mul_point(bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        dispatch(ApiCall::Execute(OperationType::MulPoint), bytes, None)
     }
 
     fn multiexp(bytes: &[u8]) -> Result<Vec<u8>, ()> {
-        let (modulus, _, _, _, order, _, _) = parse_encodings(&bytes)?;
-        let modulus_limbs = (modulus.bits() / 64) + 1;
-        let order_limbs = (order.bits() / 64) + 1;
-
-        let result: Result<Vec<u8>, ()> = match (modulus_limbs, order_limbs) {
-            (4, 4) => {
-                ApiImplementation::<U256Repr, U256Repr>::multiexp(&bytes)
-            },
-            (5, 4) => {
-                ApiImplementation::<U320Repr, U256Repr>::multiexp(&bytes)
-            },
-            (5, 5) => {
-                ApiImplementation::<U320Repr, U320Repr>::multiexp(&bytes)
-            },
-            _ => {
-                unimplemented!();
-            }
-        };
+        dispatch(ApiCall::Execute(OperationType::Multiexp), bytes, None)
+    }
+}
+
+/// Same operations as `API`, but fields for recently used curves
+/// are kept in a bounded cache and are not recomputed on every call.
+/// Pairing engine contexts (extension towers with Frobenius coefficients and
+/// engine parameters) are kept in a separate cache of the same capacity
+pub struct CachedAPI {
+    cache: ContextCache,
+    engines: ContextCache
+}
+
+impl CachedAPI {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: ContextCache::new(capacity),
+            engines: ContextCache::new(capacity)
+        }
+    }
+
+    pub fn cached_contexts(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn cached_engines(&self) -> usize {
+        self.engines.len()
+    }
+
+    pub fn clear(&self) {
+        self.cache.clear();
+        self.engines.clear();
+    }
 
-        result
+    /// Returns a pairing engine context, such as `BnContext` or `Bls12Context`, for
+    /// the header of the engine parameters, constructing it with `f` on a miss. Use
+    /// the context with `with_engine`, so towers are not rebuilt on every call
+    pub fn engine_context<T, F>(&self, header: &[u8], f: F) -> Result<Arc<T>, ()>
+        where T: std::any::Any + Send + Sync, F: FnOnce() -> Result<T, ()>
+    {
+        self.engines.get_or_try_insert_with(header, f)
     }
-}
\ No newline at end of file
+
+    pub fn execute(&self, operation: OperationType, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        match operation {
+            OperationType::AddPoints => self.add_points(bytes),
+            OperationType::MulPoint => self.mul_point(bytes),
+            OperationType::Multiexp => self.multiexp(bytes)
+        }
+    }
+
+    fn prepare(&self, bytes: &[u8]) -> Result<(), ()> {
+        dispatch(ApiCall::Prepare, bytes, Some(&self.cache)).map(|_| ())
+    }
+
+    pub fn add_points(&self, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        dispatch(ApiCall::Execute(OperationType::AddPoints), bytes, Some(&self.cache))
+    }
+
+    pub fn mul_point(&self, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        dispatch(ApiCall::Execute(OperationType::MulPoint), bytes, Some(&self.cache))
+    }
+
+    pub fn multiexp(&self, bytes: &[u8]) -> Result<Vec<u8>, ()> {
+        dispatch(ApiCall::Execute(OperationType::Multiexp), bytes, Some(&self.cache))
+    }
+}
@@ -0,0 +1,157 @@
+//! Bounded cache for everything that can be precomputed from the curve parameters
+//! alone (Montgomery constants, Frobenius coefficients, etc.)
+//!
+//! Entries are keyed by the hash of the curve-parameter header of the call,
+//! but full header is also stored and compared on lookup, so hash collision
+//! can only result in a cache miss, never in a wrong context being used.
+//! Values are type-erased, so the same cache can hold contexts for
+//! different limb sizes, or extension tower and pairing engine contexts,
+//! as long as they own all their data.
+//!
+//! Eviction policy is "least recently used".
+
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+struct CacheEntry {
+    key: u64,
+    header: Vec<u8>,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+pub struct ContextCache {
+    capacity: usize,
+    // most recently used entries are at the back
+    entries: Mutex<VecDeque<CacheEntry>>,
+}
+
+impl ContextCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("cache lock is not poisoned").len()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().expect("cache lock is not poisoned").clear();
+    }
+
+    /// Returns a context for this header, constructing it with `f` if it's not cached yet.
+    /// Errors of `f` are passed through and are not cached. Lock is not held
+    /// while `f` runs, so two threads may construct the same context simultaneously,
+    /// and only one of them will be stored.
+    pub fn get_or_try_insert_with<T, F>(&self, header: &[u8], f: F) -> Result<Arc<T>, ()>
+        where T: Any + Send + Sync, F: FnOnce() -> Result<T, ()>
+    {
+        let key = hash_header(header);
+        if let Some(value) = self.get(key, header) {
+            return Ok(value);
+        }
+
+        let value = Arc::new(f()?);
+        if self.capacity == 0 {
+            return Ok(value);
+        }
+
+        let mut entries = self.entries.lock().expect("cache lock is not poisoned");
+        if let Some(position) = find_entry(&entries, key, header) {
+            entries.remove(position);
+        } else if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(CacheEntry {
+            key,
+            header: header.to_vec(),
+            value: value.clone(),
+        });
+
+        Ok(value)
+    }
+
+    fn get<T: Any + Send + Sync>(&self, key: u64, header: &[u8]) -> Option<Arc<T>> {
+        let mut entries = self.entries.lock().expect("cache lock is not poisoned");
+        let position = find_entry(&entries, key, header)?;
+        // same header can be used with a different representation type only
+        // if it's malformed for one of them, so just treat it as a miss
+        let value = entries[position].value.clone().downcast::<T>().ok()?;
+        let entry = entries.remove(position).expect("entry exists");
+        entries.push_back(entry);
+
+        Some(value)
+    }
+}
+
+fn find_entry(entries: &VecDeque<CacheEntry>, key: u64, header: &[u8]) -> Option<usize> {
+    entries.iter().position(|e| e.key == key && &e.header[..] == header)
+}
+
+fn hash_header(header: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    header.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContextCache;
+
+    #[test]
+    fn test_cache_hit_does_not_reconstruct() {
+        let cache = ContextCache::new(2);
+        let first = cache.get_or_try_insert_with(b"header", || Ok(1u64)).unwrap();
+        let second = cache.get_or_try_insert_with(b"header", || -> Result<u64, ()> { panic!("must be cached") }).unwrap();
+        assert_eq!(*first, 1u64);
+        assert_eq!(*second, 1u64);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let cache = ContextCache::new(2);
+        cache.get_or_try_insert_with(b"a", || Ok(1u64)).unwrap();
+        cache.get_or_try_insert_with(b"b", || Ok(2u64)).unwrap();
+        // touch "a", so "b" becomes the oldest one
+        cache.get_or_try_insert_with(b"a", || Ok(100u64)).unwrap();
+        cache.get_or_try_insert_with(b"c", || Ok(3u64)).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        let a = cache.get_or_try_insert_with(b"a", || Ok(100u64)).unwrap();
+        assert_eq!(*a, 1u64);
+        let b = cache.get_or_try_insert_with(b"b", || Ok(200u64)).unwrap();
+        assert_eq!(*b, 200u64);
+    }
+
+    #[test]
+    fn test_cache_does_not_store_errors() {
+        let cache = ContextCache::new(2);
+        let result: Result<std::sync::Arc<u64>, ()> = cache.get_or_try_insert_with(b"a", || Err(()));
+        assert!(result.is_err());
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_cache_type_mismatch_is_a_miss() {
+        let cache = ContextCache::new(2);
+        cache.get_or_try_insert_with(b"a", || Ok(1u64)).unwrap();
+        let value = cache.get_or_try_insert_with(b"a", || Ok(2u32)).unwrap();
+        assert_eq!(*value, 2u32);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_zero_capacity_cache() {
+        let cache = ContextCache::new(0);
+        let value = cache.get_or_try_insert_with(b"a", || Ok(1u64)).unwrap();
+        assert_eq!(*value, 1u64);
+        assert_eq!(cache.len(), 0);
+    }
+}
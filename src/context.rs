@@ -0,0 +1,330 @@
+//! Owned contexts for the curves and extension towers.
+//!
+//! All the arithmetic types borrow their field, so a curve can not be stored next
+//! to its points or returned from a constructor. Contexts hold the same data
+//! (prime fields and coefficients in Montgomery form) without any references,
+//! so they can be moved, stored in an `Arc` and shared between threads. Borrowed
+//! structures are reconstructed on the stack by the `with_*` functions, which
+//! only involves copying of precomputed values.
+
+use crate::field::{SizedPrimeField, PrimeField, field_from_modulus};
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::weierstrass::curve::WeierstrassCurve;
+use crate::weierstrass::glv::{GlvParameters, biguint_from_limbs, limbs_from_biguint};
+use crate::traits::FieldElement;
+use crate::pairings::{frobenius_calculator_fp2, frobenius_calculator_fp6_as_3_over_2, frobenius_calculator_fp12};
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
+use crate::extension_towers::fp12_as_2_over3_over_2::Extension2Over3Over2;
+
+use num_bigint::BigUint;
+use std::sync::OnceLock;
+
+#[inline(always)]
+pub(crate) fn fp_from_mont_repr<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    field: &'a F,
+    repr: FE
+) -> Fp<'a, FE, F> {
+    Fp {
+        field,
+        repr
+    }
+}
+
+#[inline(always)]
+pub(crate) fn fp2_from_mont_repr<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    extension: &'a Extension2<'a, FE, F>,
+    repr: [FE; 2]
+) -> Fp2<'a, FE, F> {
+    let mut el = Fp2::zero(extension);
+    el.c0 = fp_from_mont_repr(extension.field, repr[0]);
+    el.c1 = fp_from_mont_repr(extension.field, repr[1]);
+
+    el
+}
+
+#[inline(always)]
+pub(crate) fn fp2_into_mont_repr<FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    el: &Fp2<'_, FE, F>
+) -> [FE; 2] {
+    [el.c0.repr, el.c1.repr]
+}
+
+/// Base and scalar fields of the curve in short Weierstrass form and
+/// it's coefficients
+#[derive(Clone, Debug)]
+pub struct CurveContext<FE: ElementRepr, GE: ElementRepr> {
+    pub(crate) base_field: PrimeField<FE>,
+    pub(crate) scalar_field: PrimeField<GE>,
+    // in Montgomery form
+    pub(crate) a: FE,
+    pub(crate) b: FE,
+    // filled by the first multiplication on the curve, so contexts
+    // in the cache don't derive it again
+    pub(crate) glv: OnceLock<Option<GlvParameters<FE>>>,
+    // same for the choice of formulas
+    pub(crate) two_torsion: OnceLock<bool>,
+}
+
+impl<FE: ElementRepr, GE: ElementRepr> CurveContext<FE, GE> {
+    pub fn new(modulus: BigUint, order: BigUint, a: BigUint, b: BigUint) -> Result<Self, ()> {
+        let base_field = field_from_modulus::<FE>(modulus)?;
+        let scalar_field = field_from_modulus::<GE>(order)?;
+        let a = Fp::from_be_bytes(&base_field, &a.to_bytes_be(), true).map_err(|_| ())?.repr;
+        let b = Fp::from_be_bytes(&base_field, &b.to_bytes_be(), true).map_err(|_| ())?.repr;
+
+        Ok(Self {
+            base_field,
+            scalar_field,
+            a,
+            b,
+            glv: OnceLock::new(),
+            two_torsion: OnceLock::new()
+        })
+    }
+
+    pub fn from_curve(curve: &WeierstrassCurve<'_, FE, PrimeField<FE>, GE, PrimeField<GE>>) -> Self {
+        Self {
+            base_field: curve.base_field.clone(),
+            scalar_field: curve.scalar_field.clone(),
+            a: curve.a.repr,
+            b: curve.b.repr,
+            glv: curve.glv.clone(),
+            two_torsion: curve.two_torsion.clone()
+        }
+    }
+
+    pub fn base_field(&self) -> &PrimeField<FE> {
+        &self.base_field
+    }
+
+    pub fn scalar_field(&self) -> &PrimeField<GE> {
+        &self.scalar_field
+    }
+
+    pub fn with_curve<R, F>(&self, f: F) -> R
+        where F: for<'a> FnOnce(&'a WeierstrassCurve<'a, FE, PrimeField<FE>, GE, PrimeField<GE>>) -> R
+    {
+        let a = fp_from_mont_repr(&self.base_field, self.a);
+        let b = fp_from_mont_repr(&self.base_field, self.b);
+        let curve = WeierstrassCurve::new(&self.scalar_field, a, b);
+        if let Some(glv) = self.glv.get() {
+            let _ = curve.glv.set(glv.clone());
+        }
+        if let Some(two_torsion) = self.two_torsion.get() {
+            let _ = curve.two_torsion.set(*two_torsion);
+        }
+
+        let result = f(&curve);
+        if let Some(glv) = curve.glv.into_inner() {
+            let _ = self.glv.set(glv);
+        }
+        if let Some(two_torsion) = curve.two_torsion.into_inner() {
+            let _ = self.two_torsion.set(two_torsion);
+        }
+
+        result
+    }
+}
+
+/// Coefficients of the Fp12 as 2 over 3 over 2 tower over some base field.
+/// Base field is not included, so it can be shared with the curve context
+#[derive(Clone, Debug)]
+pub(crate) struct Fp12TowerContext<FE: ElementRepr> {
+    fp2_non_residue: FE,
+    fp2_frobenius_coeffs_c1: [FE; 2],
+    fp6_non_residue: [FE; 2],
+    fp6_frobenius_coeffs_c1: [[FE; 2]; 6],
+    fp6_frobenius_coeffs_c2: [[FE; 2]; 6],
+    fp12_frobenius_coeffs_c1: [[FE; 2]; 12],
+}
+
+impl<FE: ElementRepr> Fp12TowerContext<FE> {
+    /// Tower Fp2 = Fp[u]/(u^2 - fp_non_residue), Fp6 = Fp2[v]/(v^3 - fp2_non_residue) and
+    /// Fp12 = Fp6[w]/(w^2 - v). Non-residues are plain numbers, `fp2_non_residue` is c0 and c1.
+    /// Returns an error unless the first one is not a square in Fp and the second one is neither
+    /// a square nor a cube in Fp2
+    pub(crate) fn new(base_field: &PrimeField<FE>, fp_non_residue: &BigUint, fp2_non_residue: (&BigUint, &BigUint)) -> Result<Self, ()> {
+        let modulus = biguint_from_limbs(base_field.modulus().as_ref());
+        let fp = |value: &BigUint| Fp::from_be_bytes(base_field, &value.to_bytes_be(), true).map_err(|_| ());
+
+        let mut extension_2 = Extension2 {
+            field: base_field,
+            non_residue: fp(fp_non_residue)?,
+            frobenius_coeffs_c1: [Fp::zero(base_field), Fp::zero(base_field)]
+        };
+        let mut minus_one = Fp::one(base_field);
+        minus_one.negate();
+        if extension_2.non_residue.pow(limbs_from_biguint(&(&modulus >> 1))) != minus_one {
+            return Err(());
+        }
+        extension_2.frobenius_coeffs_c1 = frobenius_calculator_fp2(&extension_2)?;
+
+        let mut non_residue = Fp2::zero(&extension_2);
+        non_residue.c0 = fp(fp2_non_residue.0)?;
+        non_residue.c1 = fp(fp2_non_residue.1)?;
+        let p_squared_minus_one = &modulus * &modulus - 1u64;
+        let one = Fp2::one(&extension_2);
+        for degree in [2u64, 3].iter() {
+            if non_residue.pow(limbs_from_biguint(&(&p_squared_minus_one / *degree))) == one {
+                return Err(());
+            }
+        }
+
+        let zero = Fp2::zero(&extension_2);
+        let mut extension_6 = Extension3Over2 {
+            non_residue,
+            field: &extension_2,
+            frobenius_coeffs_c1: [zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone()],
+            frobenius_coeffs_c2: [zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone()],
+        };
+        let (coeffs_c1, coeffs_c2) = frobenius_calculator_fp6_as_3_over_2(modulus.clone(), &extension_6)?;
+        extension_6.frobenius_coeffs_c1 = coeffs_c1;
+        extension_6.frobenius_coeffs_c2 = coeffs_c2;
+
+        let mut extension_12 = Extension2Over3Over2 {
+            non_residue: Fp6::zero(&extension_6),
+            field: &extension_6,
+            frobenius_coeffs_c1: [
+                zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone(),
+                zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero.clone(), zero
+            ],
+        };
+        extension_12.frobenius_coeffs_c1 = frobenius_calculator_fp12(modulus, &extension_12)?;
+
+        Ok(Self::from_extension(&extension_12))
+    }
+
+    pub(crate) fn from_extension(extension: &Extension2Over3Over2<'_, FE, PrimeField<FE>>) -> Self {
+        let fp6_extension = extension.field;
+        let fp2_extension = fp6_extension.field;
+
+        let mut fp6_frobenius_coeffs_c1 = [[FE::default(); 2]; 6];
+        let mut fp6_frobenius_coeffs_c2 = [[FE::default(); 2]; 6];
+        for i in 0..6 {
+            fp6_frobenius_coeffs_c1[i] = fp2_into_mont_repr(&fp6_extension.frobenius_coeffs_c1[i]);
+            fp6_frobenius_coeffs_c2[i] = fp2_into_mont_repr(&fp6_extension.frobenius_coeffs_c2[i]);
+        }
+
+        let mut fp12_frobenius_coeffs_c1 = [[FE::default(); 2]; 12];
+        for (c, el) in fp12_frobenius_coeffs_c1.iter_mut().zip(extension.frobenius_coeffs_c1.iter()) {
+            *c = fp2_into_mont_repr(el);
+        }
+
+        Self {
+            fp2_non_residue: fp2_extension.non_residue.repr,
+            fp2_frobenius_coeffs_c1: [fp2_extension.frobenius_coeffs_c1[0].repr, fp2_extension.frobenius_coeffs_c1[1].repr],
+            fp6_non_residue: fp2_into_mont_repr(&fp6_extension.non_residue),
+            fp6_frobenius_coeffs_c1,
+            fp6_frobenius_coeffs_c2,
+            fp12_frobenius_coeffs_c1
+        }
+    }
+
+    pub(crate) fn with_tower<R, F>(&self, base_field: &PrimeField<FE>, f: F) -> R
+        where F: for<'a> FnOnce(
+            &'a Extension2<'a, FE, PrimeField<FE>>,
+            &'a Extension3Over2<'a, FE, PrimeField<FE>>,
+            &'a Extension2Over3Over2<'a, FE, PrimeField<FE>>
+        ) -> R
+    {
+        let extension_2 = Extension2 {
+            field: base_field,
+            non_residue: fp_from_mont_repr(base_field, self.fp2_non_residue),
+            frobenius_coeffs_c1: [
+                fp_from_mont_repr(base_field, self.fp2_frobenius_coeffs_c1[0]),
+                fp_from_mont_repr(base_field, self.fp2_frobenius_coeffs_c1[1])
+            ]
+        };
+
+        let fp6_c1 = &self.fp6_frobenius_coeffs_c1;
+        let fp6_c2 = &self.fp6_frobenius_coeffs_c2;
+        let extension_6 = Extension3Over2 {
+            non_residue: fp2_from_mont_repr(&extension_2, self.fp6_non_residue),
+            field: &extension_2,
+            frobenius_coeffs_c1: [
+                fp2_from_mont_repr(&extension_2, fp6_c1[0]), fp2_from_mont_repr(&extension_2, fp6_c1[1]),
+                fp2_from_mont_repr(&extension_2, fp6_c1[2]), fp2_from_mont_repr(&extension_2, fp6_c1[3]),
+                fp2_from_mont_repr(&extension_2, fp6_c1[4]), fp2_from_mont_repr(&extension_2, fp6_c1[5])
+            ],
+            frobenius_coeffs_c2: [
+                fp2_from_mont_repr(&extension_2, fp6_c2[0]), fp2_from_mont_repr(&extension_2, fp6_c2[1]),
+                fp2_from_mont_repr(&extension_2, fp6_c2[2]), fp2_from_mont_repr(&extension_2, fp6_c2[3]),
+                fp2_from_mont_repr(&extension_2, fp6_c2[4]), fp2_from_mont_repr(&extension_2, fp6_c2[5])
+            ],
+        };
+
+        let fp12_c1 = &self.fp12_frobenius_coeffs_c1;
+        let extension_12 = Extension2Over3Over2 {
+            non_residue: Fp6::zero(&extension_6),
+            field: &extension_6,
+            frobenius_coeffs_c1: [
+                fp2_from_mont_repr(&extension_2, fp12_c1[0]), fp2_from_mont_repr(&extension_2, fp12_c1[1]),
+                fp2_from_mont_repr(&extension_2, fp12_c1[2]), fp2_from_mont_repr(&extension_2, fp12_c1[3]),
+                fp2_from_mont_repr(&extension_2, fp12_c1[4]), fp2_from_mont_repr(&extension_2, fp12_c1[5]),
+                fp2_from_mont_repr(&extension_2, fp12_c1[6]), fp2_from_mont_repr(&extension_2, fp12_c1[7]),
+                fp2_from_mont_repr(&extension_2, fp12_c1[8]), fp2_from_mont_repr(&extension_2, fp12_c1[9]),
+                fp2_from_mont_repr(&extension_2, fp12_c1[10]), fp2_from_mont_repr(&extension_2, fp12_c1[11])
+            ],
+        };
+
+        f(&extension_2, &extension_6, &extension_12)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CurveContext;
+    use crate::field::{U256Repr, SizedPrimeField};
+    use crate::fp::Fp;
+    use crate::weierstrass::Group;
+    use crate::weierstrass::curve::CurvePoint;
+    use num_bigint::BigUint;
+    use num_traits::Num;
+    use std::sync::Arc;
+
+    fn bn254_curve() -> CurveContext<U256Repr, U256Repr> {
+        let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let order = BigUint::from_str_radix("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+
+        CurveContext::new(modulus, order, BigUint::from(0u64), BigUint::from(3u64)).unwrap()
+    }
+
+    #[test]
+    fn test_curve_context_between_threads() {
+        let context = Arc::new(bn254_curve());
+
+        let handles: Vec<_> = (0..2).map(|_| {
+            let context = context.clone();
+            std::thread::spawn(move || {
+                context.with_curve(|curve| {
+                    let one = Fp::from_be_bytes(curve.base_field, &[1], true).unwrap();
+                    let two = Fp::from_be_bytes(curve.base_field, &[2], true).unwrap();
+                    let p = CurvePoint::point_from_xy(curve, one, two);
+                    assert!(p.check_on_curve());
+                    // r * G = 0
+                    let mut order = context.scalar_field().modulus();
+                    order.as_mut()[0] -= 1;
+                    let mut q = p.mul(order);
+                    q.add_assign(&p);
+
+                    q.is_zero()
+                })
+            })
+        }).collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_curve_context_from_curve() {
+        let context = bn254_curve();
+        let copy = context.with_curve(CurveContext::from_curve);
+        assert!(copy.a == context.a);
+        assert!(copy.b == context.b);
+        assert!(copy.base_field().modulus() == context.base_field().modulus());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::field::{SizedPrimeField};
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, FieldExtension};
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ConditionallySelectable};
 use super::fp6_as_3_over_2::{Fp6, Extension3Over2};
 use super::fp2::Fp2;
 
@@ -245,25 +245,398 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp12<'a, E, F> {
         self.c1.c2 = g5;
     }
 
+    /// Karabina's squaring (https://eprint.iacr.org/2010/542.pdf) of the element
+    /// of cyclotomic subgroup in compressed form. Only c0.c1, c0.c2, c1.c0 and c1.c2
+    /// are updated, two other coefficients have no meaning until `cyclotomic_decompress`
+    pub fn cyclotomic_square_compressed(&mut self) {
+        let z1 = self.c0.c1.clone();
+        let z2 = self.c0.c2.clone();
+        let z3 = self.c1.c0.clone();
+        let z5 = self.c1.c2.clone();
+
+        // z1^2
+        let mut t0 = z1.clone();
+        t0.square();
+
+        // z5^2
+        let mut t1 = z5.clone();
+        t1.square();
+
+        // 2*z1*z5
+        let mut z1_z5 = z1.clone();
+        z1_z5.add_assign(&z5);
+        z1_z5.square();
+        z1_z5.sub_assign(&t0);
+        z1_z5.sub_assign(&t1);
+
+        // z2^2
+        let mut t2 = z2.clone();
+        t2.square();
+
+        // z3^2
+        let mut t3 = z3.clone();
+        t3.square();
+
+        // 2*z2*z3
+        let mut z2_z3 = z2.clone();
+        z2_z3.add_assign(&z3);
+        z2_z3.square();
+        z2_z3.sub_assign(&t2);
+        z2_z3.sub_assign(&t3);
+
+        // c1.c0 = 6*xi*z1*z5 + 2*z3
+        let mut tmp = z1_z5;
+        tmp.mul_by_nonresidue(self.extension_field.field);
+        let mut g3 = tmp.clone();
+        g3.add_assign(&z3);
+        g3.double();
+        g3.add_assign(&tmp);
+        self.c1.c0 = g3;
+
+        // c0.c2 = 3*(xi*z5^2 + z1^2) - 2*z2
+        let mut tmp = t1;
+        tmp.mul_by_nonresidue(self.extension_field.field);
+        tmp.add_assign(&t0);
+        let mut g2 = tmp.clone();
+        g2.sub_assign(&z2);
+        g2.double();
+        g2.add_assign(&tmp);
+        self.c0.c2 = g2;
+
+        // c0.c1 = 3*(xi*z2^2 + z3^2) - 2*z1
+        let mut tmp = t2;
+        tmp.mul_by_nonresidue(self.extension_field.field);
+        tmp.add_assign(&t3);
+        let mut g1 = tmp.clone();
+        g1.sub_assign(&z1);
+        g1.double();
+        g1.add_assign(&tmp);
+        self.c0.c1 = g1;
+
+        // c1.c2 = 6*z2*z3 + 2*z5
+        let mut g5 = z2_z3.clone();
+        g5.add_assign(&z5);
+        g5.double();
+        g5.add_assign(&z2_z3);
+        self.c1.c2 = g5;
+    }
+
+    /// Recovers c0.c0 and c1.c1 of the element in compressed form. Returns `None`
+    /// if element can not be uniquely decompressed (c0.c2 and c1.c0 are both zero)
+    pub fn cyclotomic_decompress(&self) -> Option<Self> {
+        let (mut z4, den) = self.cyclotomic_decompression_fraction();
+        z4.mul_assign(&den.inverse()?);
+
+        Some(self.cyclotomic_decompress_with_c1_c1(z4))
+    }
+
+    /// Decompresses a set of elements using one shared inversion (Montgomery's trick).
+    /// Returns `None` if any of the elements can not be decompressed
+    pub fn cyclotomic_decompress_batch(elements: &[Self]) -> Option<Vec<Self>> {
+        if elements.is_empty() {
+            return Some(vec![]);
+        }
+
+        let fractions: Vec<_> = elements.iter().map(|el| el.cyclotomic_decompression_fraction()).collect();
+
+        // prefix products of denominators
+        let mut products = Vec::with_capacity(fractions.len());
+        let mut acc = Fp2::one(elements[0].extension_field.field.field);
+        for (_, den) in fractions.iter() {
+            acc.mul_assign(den);
+            products.push(acc.clone());
+        }
+
+        let mut acc_inv = acc.inverse()?;
+
+        let mut result = Vec::with_capacity(elements.len());
+        for (i, (el, (num, den))) in elements.iter().zip(fractions).enumerate().rev() {
+            // acc_inv is an inverse of the product of first i+1 denominators
+            let mut den_inv = acc_inv.clone();
+            if i != 0 {
+                den_inv.mul_assign(&products[i - 1]);
+            }
+            acc_inv.mul_assign(&den);
+
+            let mut z4 = num;
+            z4.mul_assign(&den_inv);
+            result.push(el.cyclotomic_decompress_with_c1_c1(z4));
+        }
+        result.reverse();
+
+        Some(result)
+    }
+
+    // c1.c1 of the compressed element as numerator and denominator
+    fn cyclotomic_decompression_fraction(&self) -> (Fp2<'a, E, F>, Fp2<'a, E, F>) {
+        let z1 = &self.c0.c1;
+        let z2 = &self.c0.c2;
+        let z3 = &self.c1.c0;
+        let z5 = &self.c1.c2;
+
+        if !z3.is_zero() {
+            // c1.c1 = (xi*z5^2 + 3*z1^2 - 2*z2) / (4*z3)
+            let mut z1_squared = z1.clone();
+            z1_squared.square();
+            let mut num = z1_squared.clone();
+            num.sub_assign(z2);
+            num.double();
+            num.add_assign(&z1_squared);
+            let mut t = z5.clone();
+            t.square();
+            t.mul_by_nonresidue(self.extension_field.field);
+            num.add_assign(&t);
+
+            let mut den = z3.clone();
+            den.double();
+            den.double();
+
+            (num, den)
+        } else {
+            // c1.c1 = (2*z1*z5) / z2
+            let mut num = z1.clone();
+            num.mul_assign(z5);
+            num.double();
+
+            (num, z2.clone())
+        }
+    }
+
+    fn cyclotomic_decompress_with_c1_c1(&self, z4: Fp2<'a, E, F>) -> Self {
+        let z1 = &self.c0.c1;
+        let z2 = &self.c0.c2;
+        let z3 = &self.c1.c0;
+        let z5 = &self.c1.c2;
+
+        // c0.c0 = xi*(2*z4^2 + z3*z5 - 3*z1*z2) + 1
+        let mut z1_z2 = z1.clone();
+        z1_z2.mul_assign(z2);
+        let mut z0 = z4.clone();
+        z0.square();
+        z0.sub_assign(&z1_z2);
+        z0.double();
+        z0.sub_assign(&z1_z2);
+        let mut t = z3.clone();
+        t.mul_assign(z5);
+        z0.add_assign(&t);
+        z0.mul_by_nonresidue(self.extension_field.field);
+        z0.add_assign(&Fp2::one(self.extension_field.field.field));
+
+        let mut res = self.clone();
+        res.c0.c0 = z0;
+        res.c1.c1 = z4;
+
+        res
+    }
+
+    /// Exponentiation of the element of cyclotomic subgroup, so inversion is
+    /// just a conjugation and signed digit representation of the exponent is used
     pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
-        let mut res = Self::one(&self.extension_field);
+        use crate::pairings::into_ternary_wnaf;
+        let naf = into_ternary_wnaf(exp.as_ref());
+        if naf.is_empty() {
+            return Self::one(self.extension_field);
+        }
 
-        let mut found_one = false;
+        // compressed squaring is cheaper, but every nonzero digit requires
+        // decompression that costs a few multiplications on top of one shared
+        // inversion, so it only pays off for sparse exponents like BLS12 x
+        let num_nonzero = naf.iter().filter(|d| **d != 0).count();
+        if num_nonzero * 4 <= naf.len() {
+            if let Some(res) = self.cyclotomic_exp_compressed(&naf) {
+                return res;
+            }
+        }
 
-        for i in BitIterator::new(exp) {
-            if found_one {
+        let mut res = Self::one(self.extension_field);
+        let mut self_inverse = self.clone();
+        self_inverse.conjugate();
+
+        let mut found_nonzero = false;
+
+        for &value in naf.iter().rev() {
+            if found_nonzero {
                 res.cyclotomic_square();
-            } else {
-                found_one = i;
             }
 
-            if i {
-                res.mul_assign(self);
+            if value != 0 {
+                found_nonzero = true;
+
+                if value > 0 {
+                    res.mul_assign(self);
+                } else {
+                    res.mul_assign(&self_inverse);
+                }
             }
         }
 
         res
     }
+
+    fn cyclotomic_exp_compressed(&self, naf: &[i64]) -> Option<Self> {
+        // self^(2^i) in compressed form for every nonzero digit,
+        // lowest one is not squared at all and doesn't need decompression
+        let mut powers = vec![];
+        let mut digits = vec![];
+        let mut power = self.clone();
+
+        for &value in naf.iter().skip(1) {
+            power.cyclotomic_square_compressed();
+
+            if value != 0 {
+                powers.push(power.clone());
+                digits.push(value);
+            }
+        }
+
+        let decompressed = Self::cyclotomic_decompress_batch(&powers)?;
+
+        let mut res = Self::one(self.extension_field);
+        if naf[0] != 0 {
+            res = self.clone();
+            if naf[0] < 0 {
+                res.conjugate();
+            }
+        }
+
+        for (mut t, value) in decompressed.into_iter().zip(digits) {
+            if value < 0 {
+                t.conjugate();
+            }
+
+            res.mul_assign(&t);
+        }
+
+        Some(res)
+    }
+
+    /// T2 torus compression (https://eprint.iacr.org/2003/198.pdf) of the element
+    /// x = c0 + c1*w with x^(q^6 + 1) = 1 into m = (1 + c0) / c1, so x = (m + w) / (m - w).
+    /// Returns `None` for the identity, the only such element with c1 = 0
+    pub fn torus_t2_compress(&self) -> Option<Fp6<'a, E, F>> {
+        let mut m = self.c0.clone();
+        m.add_assign(&Fp6::one(self.extension_field.field));
+        m.mul_assign(&self.c1.inverse()?);
+
+        Some(m)
+    }
+
+    /// Recovers x = (m + w) / (m - w) = (m^2 + v + 2*m*w) / (m^2 - v)
+    pub fn torus_t2_decompress(extension_field: &'a Extension2Over3Over2<'a, E, F>, m: &Fp6<'a, E, F>) -> Option<Self> {
+        let mut m_squared = m.clone();
+        m_squared.square();
+        let mut v = Fp6::one(extension_field.field);
+        v.mul_by_nonresidue(extension_field);
+
+        let mut den = m_squared.clone();
+        den.sub_assign(&v);
+        let den = den.inverse()?;
+
+        let mut c0 = m_squared;
+        c0.add_assign(&v);
+        c0.mul_assign(&den);
+
+        let mut c1 = m.clone();
+        c1.double();
+        c1.mul_assign(&den);
+
+        Some(Self {
+            c0,
+            c1,
+            extension_field
+        })
+    }
+
+    /// Exponentiation of the element in T2 compressed form. Running value is kept
+    /// as m = M / Z with the identity at Z = 0, so x^2 is (M^2 + v*Z^2 : 2*M*Z) and
+    /// multiplication by the base is (M*m + v*Z : M + m*Z), that is two Fp6
+    /// multiplications instead of a Fp12 one, and the result is compressed back
+    /// with a single inversion. Returns `None` if the result is the identity
+    pub fn torus_t2_exp<S: AsRef<[u64]>>(extension_field: &'a Extension2Over3Over2<'a, E, F>, m: &Fp6<'a, E, F>, exp: S) -> Option<Fp6<'a, E, F>> {
+        use crate::pairings::into_ternary_wnaf;
+        let naf = into_ternary_wnaf(exp.as_ref());
+
+        // inverse of (m + w) / (m - w) is (-m + w) / (-m - w)
+        let mut m_neg = m.clone();
+        m_neg.negate();
+
+        let mut num = Fp6::one(extension_field.field);
+        let mut den = Fp6::zero(extension_field.field);
+
+        for &value in naf.iter().rev() {
+            // M^2 + v*Z^2, 2*M*Z
+            let mut num_squared = num.clone();
+            num_squared.square();
+            let mut den_squared = den.clone();
+            den_squared.square();
+            den.add_assign(&num);
+            den.square();
+            den.sub_assign(&num_squared);
+            den.sub_assign(&den_squared);
+            den_squared.mul_by_nonresidue(extension_field);
+            num = num_squared;
+            num.add_assign(&den_squared);
+
+            if value != 0 {
+                let base = if value > 0 { m } else { &m_neg };
+
+                // M*m + v*Z, M + m*Z
+                let mut t0 = den.clone();
+                t0.mul_by_nonresidue(extension_field);
+                den.mul_assign(base);
+                den.add_assign(&num);
+                num.mul_assign(base);
+                num.add_assign(&t0);
+            }
+        }
+
+        num.mul_assign(&den.inverse()?);
+
+        Some(num)
+    }
+
+    /// T6 torus compression of the element of cyclotomic subgroup into a pair of Fp2
+    /// elements. For x = (m + w) / (m - w) with m = m0 + m1*v + m2*v^2 the condition
+    /// x^(q^8 + q^4 + 1) = 1 is a quadric 3*m0*m1 = 1 + 3*xi*m2^2 that has no points
+    /// with m1 = 0 (xi is not a square), so (m1, m2) are enough to recover m0.
+    /// Returns `None` for the identity
+    pub fn torus_t6_compress(&self) -> Option<(Fp2<'a, E, F>, Fp2<'a, E, F>)> {
+        let m = self.torus_t2_compress()?;
+
+        Some((m.c1, m.c2))
+    }
+
+    /// Recovers the element from the output of `torus_t6_compress`
+    pub fn torus_t6_decompress(extension_field: &'a Extension2Over3Over2<'a, E, F>, m1: &Fp2<'a, E, F>, m2: &Fp2<'a, E, F>) -> Option<Self> {
+        let mut three_m1 = m1.clone();
+        three_m1.double();
+        three_m1.add_assign(m1);
+
+        // m0 = (1 + 3*xi*m2^2) / (3*m1)
+        let mut m0 = m2.clone();
+        m0.square();
+        m0.mul_by_nonresidue(extension_field.field);
+        let t0 = m0.clone();
+        m0.double();
+        m0.add_assign(&t0);
+        m0.add_assign(&Fp2::one(extension_field.field.field));
+        m0.mul_assign(&three_m1.inverse()?);
+
+        let mut m = Fp6::zero(extension_field.field);
+        m.c0 = m0;
+        m.c1 = m1.clone();
+        m.c2 = m2.clone();
+
+        Self::torus_t2_decompress(extension_field, &m)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp12<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.c0.conditional_assign(&other.c0, choice);
+        self.c1.conditional_assign(&other.c1, choice);
+    }
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp12<'a, E, F> {
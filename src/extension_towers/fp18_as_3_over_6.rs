@@ -0,0 +1,433 @@
+use crate::field::{SizedPrimeField};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ConditionallySelectable};
+use super::fp3::Fp3;
+use super::fp6_as_2_over_3::{Fp6, Extension2Over3};
+
+
+// this implementation assumes extension using polynomial w^3 - xi = 0 over Fp6,
+// multiply_by_non_residue function in the extension field actually depends
+// on the nature of the higher extension, but is supplied at runtime,
+// KSS18 curves usually use xi = z, so w^6 = v and Fp18 is a sextic extension of Fp3
+pub struct Fp18<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub c0: Fp6<'a, E, F>,
+    pub c1: Fp6<'a, E, F>,
+    pub c2: Fp6<'a, E, F>,
+    pub extension_field: &'a Extension3Over6<'a, E, F>
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp18<'a, E, F> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Fq18({} + {} * w + {} * w^2)", self.c0, self.c1, self.c2)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp18<'a, E, F> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Fq18({} + {} * w + {} * w^2)", self.c0, self.c1, self.c2)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Fp18<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self{
+            c0: self.c0.clone(),
+            c1: self.c1.clone(),
+            c2: self.c2.clone(),
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > PartialEq for Fp18<'a, E, F> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && 
+        self.c1 == other.c1 &&
+        self.c2 == other.c2
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp18<'a, E, F> {
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp18<'a, E, F> {
+    pub fn zero(extension_field: &'a Extension3Over6<'a, E, F>) -> Self {
+        let zero = Fp6::zero(extension_field.field);
+        
+        Self {
+            c0: zero.clone(),
+            c1: zero.clone(),
+            c2: zero,
+            extension_field
+        }
+    }
+
+    pub fn one(extension_field: &'a Extension3Over6<'a, E, F>) -> Self {
+        let zero = Fp6::zero(extension_field.field);
+        let one = Fp6::one(extension_field.field);
+        
+        Self {
+            c0: one,
+            c1: zero.clone(),
+            c2: zero,
+            extension_field
+        }
+    }
+
+    pub fn mul_by_01(&mut self, c0: &Fp6<'a, E, F>, c1: &Fp6<'a, E, F>) {
+        let mut a_a = self.c0.clone();
+        let mut b_b = self.c1.clone();
+        a_a.mul_assign(c0);
+        b_b.mul_assign(c1);
+
+        let mut t1 = c1.clone();
+        {
+            let mut tmp = self.c1.clone();
+            tmp.add_assign(&self.c2);
+
+            t1.mul_assign(&tmp);
+            t1.sub_assign(&b_b);
+            t1.mul_by_nonresidue(self.extension_field);
+            t1.add_assign(&a_a);
+        }
+
+        let mut t3 = c0.clone();
+        {
+            let mut tmp = self.c0.clone();
+            tmp.add_assign(&self.c2);
+
+            t3.mul_assign(&tmp);
+            t3.sub_assign(&a_a);
+            t3.add_assign(&b_b);
+        }
+
+        let mut t2 = c0.clone();
+        t2.add_assign(c1);
+        {
+            let mut tmp = self.c0.clone();
+            tmp.add_assign(&self.c1);
+
+            t2.mul_assign(&tmp);
+            t2.sub_assign(&a_a);
+            t2.sub_assign(&b_b);
+        }
+
+        self.c0 = t1;
+        self.c1 = t2;
+        self.c2 = t3;
+    }
+
+    pub fn mul_by_02(&mut self, c0: &Fp6<'a, E, F>, c2: &Fp6<'a, E, F>) {
+        let mut a_a = self.c0.clone();
+        let mut c_c = self.c2.clone();
+        a_a.mul_assign(c0);
+        c_c.mul_assign(c2);
+
+        // a1 * c2 * xi
+        let mut t0 = self.c1.clone();
+        t0.mul_assign(c2);
+        t0.mul_by_nonresidue(self.extension_field);
+
+        // a1 * c0
+        let mut t1 = self.c1.clone();
+        t1.mul_assign(c0);
+
+        // (c0 + c2) * (a0 + a2) - a0 * c0 - a2 * c2
+        let mut t2 = c0.clone();
+        t2.add_assign(c2);
+        {
+            let mut tmp = self.c0.clone();
+            tmp.add_assign(&self.c2);
+
+            t2.mul_assign(&tmp);
+            t2.sub_assign(&a_a);
+            t2.sub_assign(&c_c);
+        }
+
+        c_c.mul_by_nonresidue(self.extension_field);
+
+        self.c0 = a_a;
+        self.c0.add_assign(&t0);
+        self.c1 = t1;
+        self.c1.add_assign(&c_c);
+        self.c2 = t2;
+    }
+
+    /// Exponentiation of the element of cyclotomic subgroup, so inversion is
+    /// just a conjugation and signed digit representation of the exponent is used
+    pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(self.extension_field);
+        let mut self_inverse = self.clone();
+        self_inverse.conjugate();
+
+        let mut found_nonzero = false;
+        use crate::pairings::into_ternary_wnaf;
+        let naf = into_ternary_wnaf(exp.as_ref());
+
+        for &value in naf.iter().rev() {
+            if found_nonzero {
+                res.square();
+            }
+
+            if value != 0 {
+                found_nonzero = true;
+
+                if value > 0 {
+                    res.mul_assign(self);
+                } else {
+                    res.mul_assign(&self_inverse);
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp18<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.c0.conditional_assign(&other.c0, choice);
+        self.c1.conditional_assign(&other.c1, choice);
+        self.c2.conditional_assign(&other.c2, choice);
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp18<'a, E, F> {
+    /// Returns true iff this element is zero.
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && 
+        self.c1.is_zero() &&
+        self.c2.is_zero()
+    }
+
+    fn add_assign(&mut self, other: &Self) {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+        self.c2.add_assign(&other.c2);
+    }
+
+    fn double(&mut self) {
+        self.c0.double();
+        self.c1.double();
+        self.c2.double();
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+        self.c2.sub_assign(&other.c2);
+    }
+
+    fn negate(&mut self) {
+        self.c0.negate();
+        self.c1.negate();
+        self.c2.negate();
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let mut c0 = self.c2.clone();
+        c0.mul_by_nonresidue(self.extension_field);
+        c0.mul_assign(&self.c1);
+        c0.negate();
+        {
+            let mut c0s = self.c0.clone();
+            c0s.square();
+            c0.add_assign(&c0s);
+        }
+        let mut c1 = self.c2.clone();
+        c1.square();
+        c1.mul_by_nonresidue(self.extension_field);
+        {
+            let mut c01 = self.c0.clone();
+            c01.mul_assign(&self.c1);
+            c1.sub_assign(&c01);
+        }
+        let mut c2 = self.c1.clone();
+        c2.square();
+        {
+            let mut c02 = self.c0.clone();
+            c02.mul_assign(&self.c2);
+            c2.sub_assign(&c02);
+        }
+
+        let mut tmp1 = self.c2.clone();
+        tmp1.mul_assign(&c1);
+        let mut tmp2 = self.c1.clone();
+        tmp2.mul_assign(&c2);
+        tmp1.add_assign(&tmp2);
+        tmp1.mul_by_nonresidue(self.extension_field);
+        tmp2 = self.c0.clone();
+        tmp2.mul_assign(&c0);
+        tmp1.add_assign(&tmp2);
+
+        match tmp1.inverse() {
+            Some(t) => {
+                let mut tmp = Fp18 {
+                    c0: t.clone(),
+                    c1: t.clone(),
+                    c2: t,
+                    extension_field: self.extension_field
+                };
+                tmp.c0.mul_assign(&c0);
+                tmp.c1.mul_assign(&c1);
+                tmp.c2.mul_assign(&c2);
+
+                Some(tmp)
+            }
+            None => None,
+        }
+    }
+
+    fn mul_assign(&mut self, other: &Self)
+    {
+        let mut a_a = self.c0.clone();
+        let mut b_b = self.c1.clone();
+        let mut c_c = self.c2.clone();
+        a_a.mul_assign(&other.c0);
+        b_b.mul_assign(&other.c1);
+        c_c.mul_assign(&other.c2);
+
+        let mut t1 = other.c1.clone();
+        t1.add_assign(&other.c2);
+        {
+            let mut tmp = self.c1.clone();
+            tmp.add_assign(&self.c2);
+
+            t1.mul_assign(&tmp);
+            t1.sub_assign(&b_b);
+            t1.sub_assign(&c_c);
+            t1.mul_by_nonresidue(self.extension_field);
+            t1.add_assign(&a_a);
+        }
+
+        let mut t3 = other.c0.clone();
+        t3.add_assign(&other.c2);
+        {
+            let mut tmp = self.c0.clone();
+            tmp.add_assign(&self.c2);
+
+            t3.mul_assign(&tmp);
+            t3.sub_assign(&a_a);
+            t3.add_assign(&b_b);
+            t3.sub_assign(&c_c);
+        }
+
+        let mut t2 = other.c0.clone();
+        t2.add_assign(&other.c1);
+        {
+            let mut tmp = self.c0.clone();
+            tmp.add_assign(&self.c1);
+
+            t2.mul_assign(&tmp);
+            t2.sub_assign(&a_a);
+            t2.sub_assign(&b_b);
+            c_c.mul_by_nonresidue(self.extension_field);
+            t2.add_assign(&c_c);
+        }
+
+        self.c0 = t1;
+        self.c1 = t2;
+        self.c2 = t3;
+    }
+
+    fn square(&mut self)
+    {
+        let mut s0 = self.c0.clone();
+        s0.square();
+        let mut ab = self.c0.clone();
+        ab.mul_assign(&self.c1);
+        let mut s1 = ab;
+        s1.double();
+        let mut s2 = self.c0.clone();
+        s2.sub_assign(&self.c1);
+        s2.add_assign(&self.c2);
+        s2.square();
+        let mut bc = self.c1.clone();
+        bc.mul_assign(&self.c2);
+        let mut s3 = bc.clone();
+        s3.double();
+        let mut s4 = self.c2.clone();
+        s4.square();
+
+        self.c0 = s3.clone();
+        self.c0.mul_by_nonresidue(self.extension_field);
+        self.c0.add_assign(&s0);
+
+        self.c1 = s4.clone();
+        self.c1.mul_by_nonresidue(self.extension_field);
+        self.c1.add_assign(&s1);
+
+        self.c2 = s1;
+        self.c2.add_assign(&s2);
+        self.c2.add_assign(&s3);
+        self.c2.sub_assign(&s0);
+        self.c2.sub_assign(&s4);
+    }
+
+    fn conjugate(&mut self) {
+        // there is no quadratic subfield in this representation, so
+        // conjugation over Fp9 is done as a q^9 power Frobenius map
+        self.frobenius_map(9);
+    }
+
+    fn pow<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(self.extension_field);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn mul_by_nonresidue<EXT: FieldExtension<Element = Self>>(&mut self, for_extesion: &EXT) {
+        for_extesion.multiply_by_non_residue(self);
+        // self.extension_field.multiply_by_non_residue(self);
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        self.c0.frobenius_map(power);
+        self.c1.frobenius_map(power);
+        self.c2.frobenius_map(power);
+
+        // coefficients w^(q^i - 1) lie in Fp3, so both halves of Fp6 are multiplied
+        self.c1.c0.mul_assign(&self.extension_field.frobenius_coeffs_c1[power % 18]);
+        self.c1.c1.mul_assign(&self.extension_field.frobenius_coeffs_c1[power % 18]);
+        self.c2.c0.mul_assign(&self.extension_field.frobenius_coeffs_c2[power % 18]);
+        self.c2.c1.mul_assign(&self.extension_field.frobenius_coeffs_c2[power % 18]);
+    }
+}
+
+// For example, KSS18-508 has non-residue = z;
+pub struct Extension3Over6<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    pub non_residue: Fp6<'a, E, F>,
+    pub field: &'a Extension2Over3<'a, E, F>,
+    pub frobenius_coeffs_c1: [Fp3<'a, E, F>; 18],
+    pub frobenius_coeffs_c2: [Fp3<'a, E, F>; 18],
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldExtension for Extension3Over6<'a, E, F> {
+    const EXTENSION_DEGREE: usize = 3;
+    
+    type Element = Fp6<'a, E, F>;
+
+    fn multiply_by_non_residue(&self, el: &mut Self::Element) {
+        // this is simply a multiplication by non-residue that is Fp6 element cause everything else 
+        // is covered in explicit formulas for multiplications for Fp18
+        el.mul_assign(&self.non_residue);
+    }
+
+}
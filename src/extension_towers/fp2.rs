@@ -1,7 +1,7 @@
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, FieldExtension};
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ConditionallySelectable, ConstantTimeFieldElement};
 
 
 // this implementation assumes extension using polynomial u^2 + m = 0
@@ -73,6 +73,79 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp2<'a, E, F> {
     }
 }
 
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp2<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.c0.conditional_assign(&other.c0, choice);
+        self.c1.conditional_assign(&other.c1, choice);
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConstantTimeFieldElement for Fp2<'a, E, F> {
+    fn is_zero_ct(&self) -> u64 {
+        self.c0.is_zero_ct() & self.c1.is_zero_ct()
+    }
+
+    fn add_assign_ct(&mut self, other: &Self) {
+        self.c0.add_assign_ct(&other.c0);
+        self.c1.add_assign_ct(&other.c1);
+    }
+
+    fn double_ct(&mut self) {
+        self.c0.double_ct();
+        self.c1.double_ct();
+    }
+
+    fn sub_assign_ct(&mut self, other: &Self) {
+        self.c0.sub_assign_ct(&other.c0);
+        self.c1.sub_assign_ct(&other.c1);
+    }
+
+    fn negate_ct(&mut self) {
+        self.c0.negate_ct();
+        self.c1.negate_ct();
+    }
+
+    fn mul_assign_ct(&mut self, other: &Self) {
+        // same Karatsuba multiplication as in `mul_assign`
+        let mut v0 = self.c0.clone();
+        v0.mul_assign_ct(&other.c0);
+        let mut v1 = self.c1.clone();
+        v1.mul_assign_ct(&other.c1);
+
+        self.c1.add_assign_ct(&self.c0);
+        let mut t0 = other.c0.clone();
+        t0.add_assign_ct(&other.c1);
+        self.c1.mul_assign_ct(&t0);
+        self.c1.sub_assign_ct(&v0);
+        self.c1.sub_assign_ct(&v1);
+        self.c0 = v0;
+        v1.mul_assign_ct(&self.extension_field.non_residue);
+        self.c0.add_assign_ct(&v1);
+    }
+
+    fn square_ct(&mut self) {
+        // same complex squaring as in `square`
+        let mut v0 = self.c0.clone();
+        v0.sub_assign_ct(&self.c1);
+        let mut v3 = self.c0.clone();
+        let mut t0 = self.c1.clone();
+        t0.mul_assign_ct(&self.extension_field.non_residue);
+        v3.sub_assign_ct(&t0);
+        let mut v2 = self.c0.clone();
+        v2.mul_assign_ct(&self.c1);
+
+        v0.mul_assign_ct(&v3);
+        v0.add_assign_ct(&v2);
+
+        self.c1 = v2.clone();
+        self.c1.double_ct();
+        self.c0 = v0;
+        v2.mul_assign_ct(&self.extension_field.non_residue);
+        self.c0.add_assign_ct(&v2);
+    }
+}
+
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp2<'a, E, F> {
     /// Returns true iff this element is zero.
     fn is_zero(&self) -> bool {
@@ -0,0 +1,314 @@
+use crate::field::{SizedPrimeField};
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ConditionallySelectable};
+use super::fp12_as_3_over_4::{Fp12, Extension3Over4};
+use super::fp4_as_2_over_2::Fp4;
+
+// this implementation assumes extension using polynomial z^2 - w = 0
+pub struct Fp24<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
+    pub c0: Fp12<'a, E, F>,
+    pub c1: Fp12<'a, E, F>,
+    pub extension_field: &'a Extension2Over3Over4<'a, E, F>
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Display for Fp24<'a, E, F> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Fq24({} + {} * z)", self.c0, self.c1)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >std::fmt::Debug for Fp24<'a, E, F> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Fq24({} + {} * z)", self.c0, self.c1)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Clone for Fp24<'a, E, F> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self{
+            c0: self.c0.clone(),
+            c1: self.c1.clone(),
+            extension_field: self.extension_field
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > PartialEq for Fp24<'a, E, F> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && 
+        self.c1 == other.c1
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Eq for Fp24<'a, E, F> {
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp24<'a, E, F> {
+    pub fn zero(extension_field: &'a Extension2Over3Over4<'a, E, F>) -> Self {
+        let zero = Fp12::zero(extension_field.field);
+        
+        Self {
+            c0: zero.clone(),
+            c1: zero,
+            extension_field
+        }
+    }
+
+    pub fn one(extension_field: &'a Extension2Over3Over4<'a, E, F>) -> Self {
+        let zero = Fp12::zero(extension_field.field);
+        let one = Fp12::one(extension_field.field);
+        
+        Self {
+            c0: one,
+            c1: zero,
+            extension_field
+        }
+    }
+
+    pub fn mul_by_034(
+        &mut self,
+        c0: & Fp4<'a, E, F>,
+        c3: & Fp4<'a, E, F>,
+        c4: & Fp4<'a, E, F>,
+    ) {
+        let mut a = self.c0.clone();
+        a.c0.mul_assign(c0);
+        a.c1.mul_assign(c0);
+        a.c2.mul_assign(c0);
+
+        let mut b = self.c1.clone();
+        b.mul_by_01(c3, c4);
+
+        let mut t0 = c0.clone();
+        t0.add_assign(c3);
+
+        let mut e = self.c0.clone();
+        e.add_assign(&self.c1);
+        e.mul_by_01(&t0, c4);
+
+        self.c1 = e;
+        self.c1.sub_assign(&a);
+        self.c1.sub_assign(&b);
+
+
+        let mut t1 = b.clone();
+        t1.mul_by_nonresidue(self.extension_field);
+        self.c0 = a;
+        self.c0.add_assign(&t1);
+    }
+
+    pub fn mul_by_014(
+        &mut self,
+        c0: & Fp4<'a, E, F>,
+        c1: & Fp4<'a, E, F>,
+        c4: & Fp4<'a, E, F>,
+    ) {
+        let mut aa = self.c0.clone();
+        aa.mul_by_01(c0, c1);
+        let mut bb = self.c1.clone();
+        bb.mul_by_1(c4);
+        let mut o = c1.clone();
+        o.add_assign(c4);
+        self.c1.add_assign(&self.c0);
+        self.c1.mul_by_01(c0, &o);
+        self.c1.sub_assign(&aa);
+        self.c1.sub_assign(&bb);
+        self.c0 = bb;
+        self.c0.mul_by_nonresidue(self.extension_field);
+        self.c0.add_assign(&aa);
+    }
+
+    /// Exponentiation of the element of cyclotomic subgroup, so inversion is
+    /// just a conjugation and signed digit representation of the exponent is used
+    pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(self.extension_field);
+        let mut self_inverse = self.clone();
+        self_inverse.conjugate();
+
+        let mut found_nonzero = false;
+        use crate::pairings::into_ternary_wnaf;
+        let naf = into_ternary_wnaf(exp.as_ref());
+
+        for &value in naf.iter().rev() {
+            if found_nonzero {
+                res.square();
+            }
+
+            if value != 0 {
+                found_nonzero = true;
+
+                if value > 0 {
+                    res.mul_assign(self);
+                } else {
+                    res.mul_assign(&self_inverse);
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp24<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.c0.conditional_assign(&other.c0, choice);
+        self.c1.conditional_assign(&other.c1, choice);
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp24<'a, E, F> {
+    /// Returns true iff this element is zero.
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && 
+        self.c1.is_zero()
+    }
+
+    fn add_assign(&mut self, other: &Self) {
+        self.c0.add_assign(&other.c0);
+        self.c1.add_assign(&other.c1);
+    }
+
+    fn double(&mut self) {
+        self.c0.double();
+        self.c1.double();
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0.sub_assign(&other.c0);
+        self.c1.sub_assign(&other.c1);
+    }
+
+    fn negate(&mut self) {
+        self.c0.negate();
+        self.c1.negate();
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let mut c0s = self.c0.clone();
+        c0s.square();
+        let mut c1s = self.c1.clone();
+        c1s.square();
+        c1s.mul_by_nonresidue(self.extension_field);
+        c0s.sub_assign(&c1s);
+
+        c0s.inverse().map(|t| {
+            let mut tmp = Fp24 { 
+                c0: t.clone(), 
+                c1: t.clone(),
+                extension_field: self.extension_field
+            };
+            tmp.c0.mul_assign(&self.c0);
+            tmp.c1.mul_assign(&self.c1);
+            tmp.c1.negate();
+
+            tmp
+        })
+    }
+
+    fn mul_assign(&mut self, other: &Self)
+    {
+        let mut aa = self.c0.clone();
+        aa.mul_assign(&other.c0);
+        let mut bb = self.c1.clone();
+        bb.mul_assign(&other.c1);
+        let mut o = other.c0.clone();
+        o.add_assign(&other.c1);
+        self.c1.add_assign(&self.c0);
+        self.c1.mul_assign(&o);
+        self.c1.sub_assign(&aa);
+        self.c1.sub_assign(&bb);
+        self.c0 = bb;
+        self.c0.mul_by_nonresidue(self.extension_field);
+        self.c0.add_assign(&aa);
+    }
+
+    fn square(&mut self)
+    {
+        let mut ab = self.c0.clone();
+        ab.mul_assign(&self.c1);
+        let mut c0c1 = self.c0.clone();
+        c0c1.add_assign(&self.c1);
+        let mut c0 = self.c1.clone();
+        c0.mul_by_nonresidue(self.extension_field);
+        c0.add_assign(&self.c0);
+        c0.mul_assign(&c0c1);
+        c0.sub_assign(&ab);
+        self.c1 = ab.clone();
+        self.c1.add_assign(&ab);
+        ab.mul_by_nonresidue(self.extension_field);
+        c0.sub_assign(&ab);
+        self.c0 = c0;
+    }
+
+    fn conjugate(&mut self) {
+        self.c1.negate();
+    }
+
+    fn pow<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one(self.extension_field);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn mul_by_nonresidue<EXT: FieldExtension<Element = Self>>(&mut self, _for_extesion: &EXT) {
+        unreachable!();
+        // for_extesion.multiply_by_non_residue(self);
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        self.c0.frobenius_map(power);
+        self.c1.frobenius_map(power);
+
+        self.c1
+            .c0
+            .mul_assign(&self.extension_field.frobenius_coeffs_c1[power % 24]);
+        self.c1
+            .c1
+            .mul_assign(&self.extension_field.frobenius_coeffs_c1[power % 24]);
+        self.c1
+            .c2
+            .mul_assign(&self.extension_field.frobenius_coeffs_c1[power % 24]);
+    }
+}
+
+pub struct Extension2Over3Over4<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
+    pub non_residue: Fp12<'a, E, F>,
+    pub field: &'a Extension3Over4<'a, E, F>,
+    pub frobenius_coeffs_c1: [Fp4<'a, E, F>; 24],
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldExtension for Extension2Over3Over4<'a, E, F> {
+    const EXTENSION_DEGREE: usize = 2;
+    
+    type Element = Fp12<'a, E, F>;
+
+    fn multiply_by_non_residue(&self, el: &mut Self::Element) {
+        // IMPORTANT: This only works cause the structure of extension field for Fp24
+        // is z^2 - w = 0!
+        // take an element in Fp12 that is 3 over 4 and multiply by non-residue
+        // (c0 + c1 * w + c2 * w^2)*w with w^3 - xi = 0 -> (c2*xi + c0 * w + c1 * w^2)
+        let mut new_c0 = el.c2.clone();
+        new_c0.mul_by_nonresidue(el.extension_field);
+        el.c2 = el.c1.clone();
+        el.c1 = el.c0.clone();
+        el.c0 = new_c0;
+    }
+
+}
@@ -1,7 +1,7 @@
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, FieldExtension};
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ConditionallySelectable};
 
 // this implementation assumes extension using polynomial u^3 + m = 0
 pub struct Fp3<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
@@ -78,6 +78,15 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp3<'a, E, F> {
     }
 }
 
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp3<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.c0.conditional_assign(&other.c0, choice);
+        self.c1.conditional_assign(&other.c1, choice);
+        self.c2.conditional_assign(&other.c2, choice);
+    }
+}
+
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp3<'a, E, F> {
     /// Returns true iff this element is zero.
     fn is_zero(&self) -> bool {
@@ -1,7 +1,7 @@
 use crate::fp::Fp;
 use crate::field::{SizedPrimeField};
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, FieldExtension};
+use crate::traits::{FieldElement, BitIterator, FieldExtension, ConditionallySelectable};
 use super::fp3::{Fp3, Extension3};
 
 pub struct Fp6<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> >{
@@ -93,6 +93,127 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp6<'a, E, F> {
 
         res
     }
+
+    /// T2 torus compression of the element x = c0 + c1*w with x^(q^3 + 1) = 1 into
+    /// m = (1 + c0) / c1, so x = (m + w) / (m - w). Returns `None` for the identity
+    pub fn torus_t2_compress(&self) -> Option<Fp3<'a, E, F>> {
+        let mut m = self.c0.clone();
+        m.add_assign(&Fp3::one(self.extension_field.field));
+        m.mul_assign(&self.c1.inverse()?);
+
+        Some(m)
+    }
+
+    /// Recovers x = (m + w) / (m - w) = (m^2 + u + 2*m*w) / (m^2 - u)
+    pub fn torus_t2_decompress(extension_field: &'a Extension2Over3<'a, E, F>, m: &Fp3<'a, E, F>) -> Option<Self> {
+        let mut m_squared = m.clone();
+        m_squared.square();
+        let mut u = Fp3::one(extension_field.field);
+        u.mul_by_nonresidue(extension_field);
+
+        let mut den = m_squared.clone();
+        den.sub_assign(&u);
+        let den = den.inverse()?;
+
+        let mut c0 = m_squared;
+        c0.add_assign(&u);
+        c0.mul_assign(&den);
+
+        let mut c1 = m.clone();
+        c1.double();
+        c1.mul_assign(&den);
+
+        Some(Self {
+            c0,
+            c1,
+            extension_field
+        })
+    }
+
+    /// Exponentiation of the element in T2 compressed form with m = M / Z kept
+    /// in projective form, same as for Fp12. Returns `None` if the result is the identity
+    pub fn torus_t2_exp<S: AsRef<[u64]>>(extension_field: &'a Extension2Over3<'a, E, F>, m: &Fp3<'a, E, F>, exp: S) -> Option<Fp3<'a, E, F>> {
+        use crate::pairings::into_ternary_wnaf;
+        let naf = into_ternary_wnaf(exp.as_ref());
+
+        let mut m_neg = m.clone();
+        m_neg.negate();
+
+        let mut num = Fp3::one(extension_field.field);
+        let mut den = Fp3::zero(extension_field.field);
+
+        for &value in naf.iter().rev() {
+            // M^2 + u*Z^2, 2*M*Z
+            let mut num_squared = num.clone();
+            num_squared.square();
+            let mut den_squared = den.clone();
+            den_squared.square();
+            den.add_assign(&num);
+            den.square();
+            den.sub_assign(&num_squared);
+            den.sub_assign(&den_squared);
+            den_squared.mul_by_nonresidue(extension_field);
+            num = num_squared;
+            num.add_assign(&den_squared);
+
+            if value != 0 {
+                let base = if value > 0 { m } else { &m_neg };
+
+                // M*m + u*Z, M + m*Z
+                let mut t0 = den.clone();
+                t0.mul_by_nonresidue(extension_field);
+                den.mul_assign(base);
+                den.add_assign(&num);
+                num.mul_assign(base);
+                num.add_assign(&t0);
+            }
+        }
+
+        num.mul_assign(&den.inverse()?);
+
+        Some(num)
+    }
+
+    /// T6 torus compression of the element of cyclotomic subgroup into a pair of Fp
+    /// elements, m = m0 + m1*u + m2*u^2 lies on 3*m0*m1 = 1 + 3*beta*m2^2 where
+    /// beta = u^3. Returns `None` for the identity
+    pub fn torus_t6_compress(&self) -> Option<(Fp<'a, E, F>, Fp<'a, E, F>)> {
+        let m = self.torus_t2_compress()?;
+
+        Some((m.c1, m.c2))
+    }
+
+    /// Recovers the element from the output of `torus_t6_compress`
+    pub fn torus_t6_decompress(extension_field: &'a Extension2Over3<'a, E, F>, m1: &Fp<'a, E, F>, m2: &Fp<'a, E, F>) -> Option<Self> {
+        let mut three_m1 = m1.clone();
+        three_m1.double();
+        three_m1.add_assign(m1);
+
+        // m0 = (1 + 3*beta*m2^2) / (3*m1)
+        let mut m0 = m2.clone();
+        m0.square();
+        m0.mul_by_nonresidue(extension_field.field);
+        let t0 = m0.clone();
+        m0.double();
+        m0.add_assign(&t0);
+        m0.add_assign(&Fp::one(extension_field.field.field));
+        m0.mul_assign(&three_m1.inverse()?);
+
+        let mut m = Fp3::zero(extension_field.field);
+        m.c0 = m0;
+        m.c1 = m1.clone();
+        m.c2 = m2.clone();
+
+        Self::torus_t2_decompress(extension_field, &m)
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp6<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.c0.conditional_assign(&other.c0, choice);
+        self.c1.conditional_assign(&other.c1, choice);
+    }
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp6<'a, E, F> {
@@ -1,5 +1,10 @@
 pub mod fp2;
 pub mod fp3;
+pub mod fp4_as_2_over_2;
 pub mod fp6_as_2_over_3;
 pub mod fp6_as_3_over_2;
-pub mod fp12_as_2_over3_over_2;
\ No newline at end of file
+pub mod fp8_as_2_over_4;
+pub mod fp12_as_2_over3_over_2;
+pub mod fp12_as_3_over_4;
+pub mod fp18_as_3_over_6;
+pub mod fp24_as_2_over3_over_4;
\ No newline at end of file
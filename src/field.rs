@@ -44,6 +44,14 @@ struct U832(U832Repr);
 #[NumberOfLimbs = "14"]
 struct U896(U896Repr);
 
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "15"]
+struct U960(U960Repr);
+
+#[derive(ElementRepresentation)]
+#[NumberOfLimbs = "16"]
+struct U1024(U1024Repr);
+
 /// PrimeField is a structure that it instantiated at the runtime 
 /// and holds all the necessary information for further arithmetic
 /// operations (mainly precompiled Montgommery constants)
@@ -100,7 +108,7 @@ pub trait SizedPrimeField: Sized + Send + Sync + std::fmt::Debug
     fn is_valid_repr(&self, repr: Self::Repr) -> bool;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PrimeField<E: ElementRepr> {
     mont_power: u64,
     modulus_bits: u64,
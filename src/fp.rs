@@ -2,7 +2,10 @@ use crate::representation::{ElementRepr, RepresentationDecodingError};
 use crate::traits::FieldElement;
 use crate::traits::BitIterator;
 use crate::traits::FieldExtension;
+use crate::traits::{ConditionallySelectable, ConstantTimeFieldElement, choice_mask};
 use crate::field::SizedPrimeField;
+use crate::op_counter;
+use crate::arithmetics::{adc, sbb};
 
 pub struct Fp<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > {
     pub(crate) field: &'a F,
@@ -146,6 +149,18 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp<'a, E, F> {
         Self::from_repr(field, repr)
     }
 
+    /// Same as `sub_assign`, but is not counted as a separate operation,
+    /// so can be used inside of other operations.
+    #[inline(always)]
+    fn sub_assign_uncounted(&mut self, other: &Self) {
+        // If `other` is larger than `self`, we'll need to add the modulus to self first.
+        if other.repr > self.repr {
+            self.repr.add_nocarry(&self.field.modulus());
+        }
+
+        self.repr.sub_noborrow(&other.repr);
+    }
+
     /// Subtracts the modulus from this element if this element is not in the
     /// field. Only used interally.
     #[inline(always)]
@@ -154,6 +169,99 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > Fp<'a, E, F> {
             self.repr.sub_noborrow(&self.field.modulus());
         }
     }
+
+    /// Same as `reduce`, but always subtracts and then selects, so there
+    /// is no branch on the value
+    #[inline(always)]
+    fn reduce_ct(&mut self) {
+        let mut reduced = self.repr;
+        let mut borrow = 0;
+        for (a, b) in reduced.as_mut().iter_mut().zip(self.field.modulus().as_ref().iter()) {
+            *a = sbb(*a, *b, &mut borrow);
+        }
+
+        // borrow means that the value is already in the field
+        let mask = choice_mask(borrow);
+        for (a, b) in self.repr.as_mut().iter_mut().zip(reduced.as_ref().iter()) {
+            *a = (*a & mask) | (*b & !mask);
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConditionallySelectable for Fp<'a, E, F> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        let mask = choice_mask(choice);
+        for (a, b) in self.repr.as_mut().iter_mut().zip(other.repr.as_ref().iter()) {
+            *a ^= mask & (*a ^ *b);
+        }
+    }
+}
+
+impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > ConstantTimeFieldElement for Fp<'a, E, F> {
+    #[inline(always)]
+    fn is_zero_ct(&self) -> u64 {
+        let any_bit = self.repr.as_ref().iter().fold(0u64, |acc, limb| acc | limb);
+
+        ((any_bit | any_bit.wrapping_neg()) >> 63) ^ 1
+    }
+
+    #[inline]
+    fn add_assign_ct(&mut self, other: &Self) {
+        op_counter::count_add();
+        self.repr.add_nocarry(&other.repr);
+        self.reduce_ct();
+    }
+
+    #[inline]
+    fn double_ct(&mut self) {
+        op_counter::count_add();
+        self.repr.mul2();
+        self.reduce_ct();
+    }
+
+    #[inline]
+    fn sub_assign_ct(&mut self, other: &Self) {
+        op_counter::count_add();
+        let mut borrow = 0;
+        for (a, b) in self.repr.as_mut().iter_mut().zip(other.repr.as_ref().iter()) {
+            *a = sbb(*a, *b, &mut borrow);
+        }
+
+        // If `other` was larger than `self`, the modulus is added back. Addition
+        // of a masked modulus doesn't branch on values of the elements
+        let mask = choice_mask(borrow);
+        let modulus = self.field.modulus();
+        let mut carry = 0;
+        for (a, b) in self.repr.as_mut().iter_mut().zip(modulus.as_ref().iter()) {
+            *a = adc(*a, *b & mask, &mut carry);
+        }
+    }
+
+    #[inline]
+    fn negate_ct(&mut self) {
+        op_counter::count_add();
+        let mut tmp = self.field.modulus();
+        tmp.sub_noborrow(&self.repr);
+
+        // zero stays zero instead of becoming the modulus
+        let mask = choice_mask(self.is_zero_ct() ^ 1);
+        for (a, b) in self.repr.as_mut().iter_mut().zip(tmp.as_ref().iter()) {
+            *a = *b & mask;
+        }
+    }
+
+    #[inline]
+    fn mul_assign_ct(&mut self, other: &Self) {
+        op_counter::count_mul();
+        self.repr.mont_mul_assign_ct(&other.repr, &self.field.modulus(), self.field.mont_inv());
+    }
+
+    #[inline]
+    fn square_ct(&mut self) {
+        op_counter::count_square();
+        self.repr.mont_square_ct(&self.field.modulus(), self.field.mont_inv());
+    }
 }
 
 impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a, E, F> {
@@ -165,6 +273,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
 
     #[inline]
     fn add_assign(&mut self, other: &Self) {
+        op_counter::count_add();
         // This cannot exceed the backing capacity.
         self.repr.add_nocarry(&other.repr);
 
@@ -174,6 +283,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
 
     #[inline]
     fn double(&mut self) {
+        op_counter::count_add();
         // This cannot exceed the backing capacity.
         self.repr.mul2();
 
@@ -183,16 +293,13 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
 
     #[inline]
     fn sub_assign(&mut self, other: &Self) {
-        // If `other` is larger than `self`, we'll need to add the modulus to self first.
-        if other.repr > self.repr {
-            self.repr.add_nocarry(&self.field.modulus());
-        }
-
-        self.repr.sub_noborrow(&other.repr);
+        op_counter::count_add();
+        self.sub_assign_uncounted(other);
     }
 
     #[inline]
     fn negate(&mut self) {
+        op_counter::count_add();
         if !self.is_zero() {
             let mut tmp = self.field.modulus();
             tmp.sub_noborrow(&self.repr);
@@ -201,6 +308,7 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
     }
 
     fn inverse(&self) -> Option<Self> {
+        op_counter::count_inverse();
         if self.is_zero() {
             None
         } else {
@@ -244,10 +352,10 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
 
                 if v < u {
                     u.sub_noborrow(&v);
-                    b.sub_assign(&c);
+                    b.sub_assign_uncounted(&c);
                 } else {
                     v.sub_noborrow(&u);
-                    c.sub_assign(&b);
+                    c.sub_assign_uncounted(&b);
                 }
             }
 
@@ -262,12 +370,14 @@ impl<'a, E: ElementRepr, F: SizedPrimeField<Repr = E> > FieldElement for Fp<'a,
     #[inline]
     fn mul_assign(&mut self, other: &Self)
     {
+        op_counter::count_mul();
         self.repr.mont_mul_assign(&other.repr, &self.field.modulus(), self.field.mont_inv());
     }
 
     #[inline]
     fn square(&mut self)
     {
+        op_counter::count_square();
         self.repr.mont_square(&self.field.modulus(), self.field.mont_inv());
     }
 
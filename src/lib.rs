@@ -1,4 +1,6 @@
 #![feature(test)]
+// errors carry no payload throughout the crate
+#![allow(clippy::result_unit_err)]
 
 extern crate byteorder;
 extern crate num_bigint;
@@ -10,21 +12,28 @@ extern crate rand_xorshift;
 extern crate repr_derive;
 
 mod arithmetics;
-mod traits;
-mod representation;
-mod field;
-mod fp;
-mod weierstrass;
+pub mod traits;
+pub mod representation;
+pub mod field;
+pub mod fp;
+pub mod weierstrass;
 mod mont_inverse;
 mod multiexp;
 mod api;
-mod extension_towers;
-mod pairings;
+mod cache;
+mod context;
+mod op_counter;
+pub mod extension_towers;
+pub mod pairings;
 
 #[cfg(test)]
 mod test;
 
-pub use api::{API, PrecompileAPI};
+pub use api::{API, PrecompileAPI, CachedAPI, OperationType};
+pub use op_counter::OperationCounts;
+pub use context::CurveContext;
+#[cfg(feature = "op_counter")]
+pub use op_counter::{current_counts, reset_counts};
 
 extern crate test as rust_test;
 
@@ -628,4 +637,577 @@ mod tests {
         assert!(res_double_and_add.0 == wnaf_res.0);
         assert!(res_double_and_add.1 == wnaf_res.1);
     }
+
+    fn check_complete_formulas<'a, FE: crate::representation::ElementRepr, F: SizedPrimeField<Repr = FE>, GE: crate::representation::ElementRepr + crate::representation::IntoWnaf, G: SizedPrimeField<Repr = GE>>(
+        point: &CurvePoint<'a, FE, F, GE, G>,
+        group_order: GE,
+        scalar: GE
+    ) {
+        use crate::weierstrass::projective::ProjectivePoint;
+
+        let p = ProjectivePoint::from_jacobian(point);
+        assert!(p.check_on_curve());
+        let zero = ProjectivePoint::zero(point.curve);
+
+        let mut two_p = point.clone();
+        two_p.double();
+        let mut three_p = two_p.clone();
+        three_p.add_assign(point);
+
+        // P + P and 2P + P without special cases
+        let mut t = p.clone();
+        t.add_assign(&p);
+        assert!(t.into_xy() == two_p.into_xy());
+        let mut t = p.clone();
+        t.double();
+        assert!(t.into_xy() == two_p.into_xy());
+        t.add_assign(&p);
+        assert!(t.into_xy() == three_p.into_xy());
+        assert!(t.check_on_curve());
+        assert!(t.to_jacobian().into_xy() == three_p.into_xy());
+
+        // same through the mixed addition with P normalized
+        let mut t = ProjectivePoint::from_jacobian(&two_p);
+        t.add_assign_mixed(&p);
+        assert!(t.into_xy() == three_p.into_xy());
+        let mut t = p.clone();
+        t.add_assign_mixed(&p);
+        assert!(t.into_xy() == two_p.into_xy());
+
+        // P + (-P) and the point at infinity
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        let mut t = p.clone();
+        t.add_assign(&p_neg);
+        assert!(t.is_zero());
+        let mut t = p.clone();
+        t.add_assign_mixed(&p_neg);
+        assert!(t.is_zero());
+        assert!(t.to_jacobian().is_zero());
+        let mut t = zero.clone();
+        t.add_assign(&p);
+        assert!(t.into_xy() == p.into_xy());
+        let mut t = zero.clone();
+        t.add_assign_mixed(&p);
+        assert!(t.into_xy() == p.into_xy());
+        let mut t = p.clone();
+        t.add_assign(&zero);
+        assert!(t.into_xy() == p.into_xy());
+        let mut t = zero.clone();
+        t.double();
+        assert!(t.is_zero());
+
+        // scalar multiplication agrees with Jacobian one
+        assert!(p.mul(group_order).is_zero());
+        assert!(p.wnaf_mul(group_order).is_zero());
+        let expected = point.mul(scalar).into_xy();
+        assert!(p.mul(scalar).into_xy() == expected);
+        assert!(p.wnaf_mul(scalar).into_xy() == expected);
+    }
+
+    #[test]
+    fn test_complete_formulas_bn254() {
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let one = Fp::one(&field);
+        let a_coeff = Fp::zero(&field);
+        let mut b_coeff = one.clone();
+        b_coeff.double();
+        b_coeff.add_assign(&one);
+
+        let curve = WeierstrassCurve::new(
+            &group, 
+            a_coeff, 
+            b_coeff);
+
+        let mut two = one.clone();
+        two.double();
+
+        let point = CurvePoint::point_from_xy(
+            &curve, 
+            one, 
+            two);
+
+        let group_order = [0x43e1f593f0000001,
+                    0x2833e84879b97091,
+                    0xb85045b68181585d,
+                    0x30644e72e131a029];
+
+        check_complete_formulas(&point, U256Repr(group_order), U256Repr([0x1234567890abcdef, 0xfedcba0987654321, 0x1111, 0x2222]));
+    }
+
+    #[test]
+    fn test_complete_formulas_p256() {
+        let field = new_field::<U320Repr>("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff", 16).unwrap();
+        let group = new_field::<U320Repr>("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551", 16).unwrap();
+        let mut a_coeff = Fp::one(&field);
+        a_coeff.double();
+        a_coeff.add_assign(&Fp::one(&field));
+        a_coeff.negate();
+        let b_coeff = Fp::from_be_bytes(&field, &hex::decode("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b").unwrap(), true).unwrap();
+
+        let curve = WeierstrassCurve::new(
+            &group, 
+            a_coeff, 
+            b_coeff);
+
+        let x = Fp::from_be_bytes(&field, &hex::decode("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296").unwrap(), true).unwrap();
+        let y = Fp::from_be_bytes(&field, &hex::decode("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5").unwrap(), true).unwrap();
+
+        let point = CurvePoint::point_from_xy(
+            &curve, 
+            x, 
+            y);
+        assert!(point.check_on_curve());
+        assert!(!curve.has_points_of_order_two());
+
+        let group_order = [0xf3b9cac2fc632551,
+                    0xbce6faada7179e84,
+                    0xffffffffffffffff,
+                    0xffffffff00000000,
+                    0];
+
+        check_complete_formulas(&point, U320Repr(group_order), U320Repr([0x1234567890abcdef, 0xfedcba0987654321, 0x1111, 0x2222, 0]));
+    }
+
+    fn fp_from_hex<'a, FE: crate::representation::ElementRepr, F: SizedPrimeField<Repr = FE>>(field: &'a F, value: &str) -> Fp<'a, FE, F> {
+        use num_traits::Num;
+        let bytes = num_bigint::BigUint::from_str_radix(value, 16).unwrap().to_bytes_be();
+
+        Fp::from_be_bytes(field, &bytes, true).unwrap()
+    }
+
+    fn check_nist_curve<'a, FE: crate::representation::ElementRepr, F: SizedPrimeField<Repr = FE>, GE: crate::representation::ElementRepr + crate::representation::IntoWnaf, G: SizedPrimeField<Repr = GE>>(
+        field: &'a F,
+        curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+        generator: [&str; 2],
+        double: [&str; 2],
+        multiple: [&str; 2],
+        group_order: GE,
+        scalar: GE
+    ) {
+        use crate::weierstrass::CurveType;
+
+        assert_eq!(curve.curve_type, CurveType::AIsMinus3);
+
+        let point = CurvePoint::point_from_xy(curve, fp_from_hex(field, generator[0]), fp_from_hex(field, generator[1]));
+        assert!(point.check_on_curve());
+
+        let mut two_p = point.clone();
+        two_p.double();
+        assert!(two_p.into_xy() == (fp_from_hex(field, double[0]), fp_from_hex(field, double[1])));
+        let mut t = point.clone();
+        t.add_assign(&point);
+        assert!(t.into_xy() == two_p.into_xy());
+
+        let expected = (fp_from_hex(field, multiple[0]), fp_from_hex(field, multiple[1]));
+        assert!(point.mul(scalar).into_xy() == expected);
+        assert!(point.wnaf_mul(scalar).into_xy() == expected);
+        let mut non_normalized = two_p.clone();
+        non_normalized.sub_assign(&point);
+        assert!(non_normalized.mul(scalar).into_xy() == expected);
+        assert!(point.mul(group_order).is_zero());
+
+        // generic complete formulas in the constant-time ladder
+        assert!(point.mul_constant_time(scalar).into_xy() == expected);
+        assert!(non_normalized.mul_constant_time(scalar).into_xy() == expected);
+        assert!(point.mul_constant_time(group_order).is_zero());
+
+        check_complete_formulas(&point, group_order, scalar);
+    }
+
+    #[test]
+    fn test_a_is_minus_3_p256() {
+        let field = new_field::<U320Repr>("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff", 16).unwrap();
+        let group = new_field::<U320Repr>("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551", 16).unwrap();
+        let mut a_coeff = Fp::one(&field);
+        a_coeff.double();
+        a_coeff.add_assign(&Fp::one(&field));
+        a_coeff.negate();
+        let b_coeff = fp_from_hex(&field, "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b");
+
+        let curve = WeierstrassCurve::new(&group, a_coeff, b_coeff);
+
+        check_nist_curve(
+            &field,
+            &curve,
+            ["6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296", "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"],
+            ["7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978", "07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1"],
+            ["afa751288c724dc4967749a4f3a6a0ffd86c55ed7ac9c1e5acb6a9e42ac75eb3", "7ff086a08db608ca0ac591785556ceb80502fb0c342686025cd69e1314d791b6"],
+            U320Repr([0xf3b9cac2fc632551, 0xbce6faada7179e84, 0xffffffffffffffff, 0xffffffff00000000, 0]),
+            U320Repr([0xba09876543211111, 0x567890abcdeffedc, 0x1234, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_a_is_minus_3_p384() {
+        let field = new_field::<U448Repr>("fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff", 16).unwrap();
+        let group = new_field::<U448Repr>("ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973", 16).unwrap();
+        let mut a_coeff = Fp::one(&field);
+        a_coeff.double();
+        a_coeff.add_assign(&Fp::one(&field));
+        a_coeff.negate();
+        let b_coeff = fp_from_hex(&field, "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef");
+
+        let curve = WeierstrassCurve::new(&group, a_coeff, b_coeff);
+
+        check_nist_curve(
+            &field,
+            &curve,
+            ["aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7", "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f"],
+            ["08d999057ba3d2d969260045c55b97f089025959a6f434d651d207d19fb96e9e4fe0e86ebe0e64f85b96a9c75295df61", "8e80f1fa5b1b3cedb7bfe8dffd6dba74b275d875bc6cc43e904e505f256ab4255ffd43e94d39e22d61501e700a940e80"],
+            ["a0054ded858e19430e79c20dc68b8ce522cd5e3859bc58fad34f530c9f3727e4b25f4578565edad5c39fc7438f35f140", "8122561a18dbbd001702e07761388655b0c2b15a96bb5f103559bcd2b058ea7e2eda6d317f4ecaa73109b64d6bfa6d44"],
+            U448Repr([0xecec196accc52973, 0x581a0db248b0a77a, 0xc7634d81f4372ddf, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0]),
+            U448Repr([0xba09876543211111, 0x567890abcdeffedc, 0x1234, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_b_is_zero_curve() {
+        use crate::weierstrass::CurveType;
+
+        // y^2 = x^3 + x over the BN254 base field
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let curve = WeierstrassCurve::new(&group, Fp::one(&field), Fp::zero(&field));
+        assert_eq!(curve.curve_type, CurveType::BIsZero);
+
+        // (0, 0) is on the curve and has order two
+        let two_torsion = CurvePoint::point_from_xy(&curve, Fp::zero(&field), Fp::zero(&field));
+        assert!(two_torsion.check_on_curve());
+        let mut t = two_torsion.clone();
+        t.double();
+        assert!(t.is_zero());
+        let mut t = two_torsion.clone();
+        t.add_assign(&two_torsion);
+        assert!(t.is_zero());
+
+        let point = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_hex(&field, "3"),
+            fp_from_hex(&field, "228aac9c1a871e92ed261943a31509ffe43913e8f5350b91fcc9692c5f710b6f"));
+        assert!(point.check_on_curve());
+        let mut two_p = point.clone();
+        two_p.double();
+        assert!(two_p.into_xy() == (
+            fp_from_hex(&field, "ce78c62e6b7e671867bce52cd338f07e42282d1712f6936bab3588ea021549c"),
+            fp_from_hex(&field, "23f9aeedaf9387fea7c4b8a84fa5079c2d71a1c2bf0aee91f4cddbd7c8efe147")));
+        let mut t = point.clone();
+        t.add_assign_mixed(&point);
+        assert!(t.into_xy() == two_p.into_xy());
+
+        // y^2 = x^3 - 3*x + 3 is not mistaken for a = -3 with b = 0
+        let mut minus_three = Fp::one(&field);
+        minus_three.double();
+        minus_three.add_assign(&Fp::one(&field));
+        minus_three.negate();
+        let curve = WeierstrassCurve::new(&group, minus_three.clone(), Fp::zero(&field));
+        assert_eq!(curve.curve_type, CurveType::BIsZero);
+        let curve = WeierstrassCurve::new(&group, minus_three, Fp::one(&field));
+        assert_eq!(curve.curve_type, CurveType::AIsMinus3);
+    }
+
+    #[test]
+    fn test_constant_time_mul_bn254() {
+        use crate::representation::ElementRepr;
+        use crate::traits::ConditionallySelectable;
+        use crate::extension_towers::fp2::{Fp2, Extension2};
+        use crate::weierstrass::twist::{WeierstrassCurveTwist, TwistPoint};
+        use rand::{RngCore, SeedableRng};
+        use rand_xorshift::XorShiftRng;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+
+        let mut a = Fp::one(&field);
+        let b = Fp::zero(&field);
+        a.conditional_assign(&b, 0);
+        assert!(a == Fp::one(&field));
+        a.conditional_assign(&b, 1);
+        assert!(a == b);
+
+        let one = Fp::one(&field);
+        let mut three = one.clone();
+        three.double();
+        three.add_assign(&one);
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), three.clone());
+        let mut two = one.clone();
+        two.double();
+        let point = CurvePoint::point_from_xy(&curve, one.clone(), two);
+
+        let mut non_residue = one.clone();
+        non_residue.negate();
+        let extension_2 = Extension2 {
+            field: &field,
+            non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&field), Fp::zero(&field)]
+        };
+        // b/(u+9)
+        let mut twist_b = Fp2::zero(&extension_2);
+        twist_b.c0 = fp_from_hex(&field, "9");
+        twist_b.c1 = one.clone();
+        let mut twist_b = twist_b.inverse().unwrap();
+        twist_b.mul_by_fp(&three);
+        let twist = WeierstrassCurveTwist::new(&group, &extension_2, Fp2::zero(&extension_2), twist_b);
+        let mut q_x = Fp2::zero(&extension_2);
+        q_x.c0 = fp_from_hex(&field, "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed");
+        q_x.c1 = fp_from_hex(&field, "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2");
+        let mut q_y = Fp2::zero(&extension_2);
+        q_y.c0 = fp_from_hex(&field, "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa");
+        q_y.c1 = fp_from_hex(&field, "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b");
+        let twist_point = TwistPoint::point_from_xy(&twist, q_x, q_y);
+        assert!(twist_point.check_on_curve());
+
+        let mut order_minus_one = group.modulus();
+        order_minus_one.sub_noborrow(&U256Repr::from(1));
+        let mut scalars = vec![U256Repr::from(0), U256Repr::from(1), U256Repr::from(2), order_minus_one];
+        for _ in 0..8 {
+            let mut scalar = U256Repr::default();
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes[1..]);
+            scalar.read_be(&bytes[..]).unwrap();
+            scalars.push(scalar);
+        }
+
+        for scalar in scalars.into_iter() {
+            let expected = point.mul(scalar);
+            let res = point.mul_constant_time(scalar);
+            assert!(res.is_zero() == expected.is_zero());
+            if !expected.is_zero() {
+                assert!(res.into_xy() == expected.into_xy());
+            }
+
+            let expected = twist_point.mul(scalar);
+            let res = twist_point.mul_constant_time(scalar);
+            assert!(res.is_zero() == expected.is_zero());
+            if !expected.is_zero() {
+                assert!(res.into_xy() == expected.into_xy());
+            }
+        }
+
+        // scalars are reduced modulo the group order: k = r, k = r + 1 and k = r * 2^256 + 5
+        let order = group.modulus();
+        assert!(point.mul_constant_time(order).is_zero());
+        assert!(twist_point.mul_constant_time(order).is_zero());
+
+        let mut order_plus_one = order;
+        order_plus_one.add_nocarry(&U256Repr::from(1));
+        assert!(point.mul_constant_time(order_plus_one).into_xy() == point.into_xy());
+        assert!(twist_point.mul_constant_time(order_plus_one).into_xy() == twist_point.into_xy());
+
+        let mut oversized = vec![5u64, 0, 0, 0];
+        oversized.extend_from_slice(order.as_ref());
+        assert!(point.mul_constant_time(&oversized).into_xy() == point.mul([5u64]).into_xy());
+        assert!(twist_point.mul_constant_time(&oversized).into_xy() == twist_point.mul([5u64]).into_xy());
+
+        // the ladder doesn't depend on the representation of the input point
+        let mut non_normalized = point.clone();
+        non_normalized.double();
+        assert!(non_normalized.mul_constant_time(order_plus_one).into_xy() == non_normalized.into_xy());
+    }
+
+    fn check_glv<'a, FE: crate::representation::ElementRepr, F: SizedPrimeField<Repr = FE>, GE: crate::representation::ElementRepr + crate::representation::IntoWnaf, G: SizedPrimeField<Repr = GE>>(
+        point: &CurvePoint<'a, FE, F, GE, G>,
+        scalars: &[GE]
+    ) {
+        use crate::weierstrass::glv::joint_sparse_form;
+
+        let glv = point.curve.glv_parameters().unwrap();
+        let order_bits = point.curve.scalar_field.modulus_bits() as usize;
+
+        // lambda * P = (beta * x, y)
+        let lambda = glv.lambda.to_bytes_le();
+        let mut lambda_repr = GE::default();
+        lambda_repr.read_le(&[&lambda[..], &vec![0u8; GE::NUM_LIMBS * 8 - lambda.len()][..]].concat()[..]).unwrap();
+        let (x, y) = point.into_xy();
+        let mut beta_x = x.clone();
+        beta_x.mul_assign(&crate::context::fp_from_mont_repr(x.field, glv.beta));
+        assert!(point.wnaf_mul(lambda_repr).into_xy() == (beta_x, y));
+
+        for scalar in scalars.iter() {
+            let ((_, k1), (_, k2)) = glv.decompose(scalar.as_ref());
+            // half of the doublings of double-and-add, with a few bits of slack
+            assert!(joint_sparse_form(&k1, &k2).len() <= order_bits / 2 + 4);
+
+            let expected = point.wnaf_mul(*scalar);
+            for res in [point.mul_glv(*scalar), point.mul(*scalar)].iter() {
+                assert!(res.is_zero() == expected.is_zero());
+                if !expected.is_zero() {
+                    assert!(res.into_xy() == expected.into_xy());
+                }
+            }
+        }
+    }
+
+    fn glv_test_scalars<GE: crate::representation::ElementRepr, G: SizedPrimeField<Repr = GE>>(group: &G) -> Vec<GE> {
+        use rand::{RngCore, SeedableRng};
+        use rand_xorshift::XorShiftRng;
+
+        let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let mut order_minus_one = group.modulus();
+        order_minus_one.sub_noborrow(&GE::from(1));
+        let mut scalars = vec![GE::from(0), GE::from(1), GE::from(2), order_minus_one, group.modulus()];
+        for _ in 0..16 {
+            let mut scalar = group.modulus();
+            for limb in scalar.as_mut().iter_mut() {
+                *limb &= rng.next_u64();
+            }
+            scalars.push(scalar);
+        }
+
+        scalars
+    }
+
+    #[test]
+    fn test_glv_bn254() {
+        use crate::representation::ElementRepr;
+
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "3"));
+        assert!(curve.glv_parameters().unwrap().cofactor_is_one());
+        let point = CurvePoint::point_from_xy(&curve, fp_from_hex(&field, "1"), fp_from_hex(&field, "2"));
+
+        let scalars = glv_test_scalars(&group);
+        check_glv(&point, &scalars);
+
+        // the endomorphism doesn't depend on the representation of the input point
+        let mut non_normalized = point.clone();
+        non_normalized.double();
+        for scalar in scalars.iter() {
+            let expected = non_normalized.mul(scalar);
+            let res = non_normalized.mul_glv(scalar);
+            assert!(res.is_zero() == expected.is_zero());
+            if !expected.is_zero() {
+                assert!(res.into_xy() == expected.into_xy());
+            }
+        }
+
+        // scalars larger than the group order
+        let scalar = U256Repr([0xffffffffffffffff; 4]);
+        let mut reduced = scalar;
+        reduced.sub_noborrow(&group.modulus());
+        reduced.sub_noborrow(&group.modulus());
+        reduced.sub_noborrow(&group.modulus());
+        reduced.sub_noborrow(&group.modulus());
+        reduced.sub_noborrow(&group.modulus());
+        assert!(point.mul_glv(scalar).into_xy() == point.wnaf_mul(reduced).into_xy());
+
+        // contexts keep parameters after the first multiplication, but don't derive
+        // them for other operations or points that are not on the curve
+        let context = crate::context::CurveContext::<U256Repr, U256Repr>::from_curve(&WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "3")));
+        let add = |curve: &WeierstrassCurve<_, _, _, _>| {
+            let mut point = CurvePoint::point_from_xy(curve, fp_from_hex(curve.base_field, "1"), fp_from_hex(curve.base_field, "2"));
+            point.add_assign(&point.clone());
+            point.into_xy().0.into_repr()
+        };
+        let mul = |curve: &WeierstrassCurve<_, _, _, _>| {
+            let point = CurvePoint::point_from_xy(curve, fp_from_hex(curve.base_field, "1"), fp_from_hex(curve.base_field, "2"));
+            point.mul(U256Repr::from(2)).into_xy().0.into_repr()
+        };
+        let off_curve_mul = |curve: &WeierstrassCurve<_, _, _, _>| {
+            let point = CurvePoint::point_from_xy(curve, fp_from_hex(curve.base_field, "1"), fp_from_hex(curve.base_field, "3"));
+            point.mul(U256Repr::from(2)).into_xy().0.into_repr()
+        };
+        assert_eq!(context.with_curve(add), context.with_curve(add));
+        context.with_curve(off_curve_mul);
+        assert!(context.glv.get().is_none());
+        assert_eq!(context.with_curve(mul), context.with_curve(add));
+        assert!(context.glv.get().unwrap().is_some());
+        assert!(context.with_curve(|curve| curve.glv.get().is_some()));
+    }
+
+    #[test]
+    fn test_glv_secp256k1() {
+        let field = new_field::<U320Repr>("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f", 16).unwrap();
+        let group = new_field::<U320Repr>("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141", 16).unwrap();
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "7"));
+
+        // the well known pair from the SEC 2 curve
+        let glv = curve.glv_parameters().unwrap();
+        assert!(glv.cofactor_is_one());
+        assert!(glv.beta == fp_from_hex(&field, "7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee").repr);
+        assert!(glv.lambda.to_str_radix(16) == "5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72");
+
+        let point = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_hex(&field, "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+            fp_from_hex(&field, "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"));
+
+        check_glv(&point, &glv_test_scalars(&group));
+    }
+
+    #[test]
+    fn test_glv_bls12_381_g1() {
+        let field = new_field::<U384Repr>("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab", 16).unwrap();
+        let group = new_field::<U256Repr>("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001", 16).unwrap();
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "4"));
+
+        // cofactor is (x - 1)^2 / 3 for x = -0xd201000000010000
+        let glv = curve.glv_parameters().unwrap();
+        assert!(!glv.cofactor_is_one());
+        assert!(glv.cofactor.to_str_radix(16) == "396c8c005555e1568c00aaab0000aaab");
+
+        let point = CurvePoint::point_from_xy(
+            &curve,
+            fp_from_hex(&field, "17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"),
+            fp_from_hex(&field, "08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"));
+
+        check_glv(&point, &glv_test_scalars(&group));
+
+        // no endomorphism for a = 0 if p = 2 mod 3, e.g. over the P-384 prime
+        let field = new_field::<U448Repr>("fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff", 16).unwrap();
+        let group = new_field::<U448Repr>("ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973", 16).unwrap();
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "7"));
+        assert!(curve.glv_parameters().is_none());
+
+        // and for the order that doesn't divide the number of points
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U320Repr>("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551", 16).unwrap();
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "3"));
+        assert!(curve.glv_parameters().is_none());
+    }
+
+    #[test]
+    fn test_glv_depends_on_b() {
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+
+        // twists of BN254 with the same p and r, but the order r doesn't divide the
+        // number of points, so there are no parameters
+        for b in ["2", "5", "7"].iter() {
+            let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, b));
+            assert!(curve.glv_parameters().is_none());
+        }
+
+        // b = 3 * 2^6 is isomorphic to b = 3 and keeps the parameters
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "c0"));
+        assert!(curve.glv_parameters().unwrap().cofactor_is_one());
+    }
+
+    #[test]
+    fn test_points_of_order_two() {
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let mut minus_one = Fp::one(&field);
+        minus_one.negate();
+        let mut minus_two = minus_one.clone();
+        minus_two.double();
+
+        // BN254 has prime order
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), fp_from_hex(&field, "3"));
+        assert!(!curve.has_points_of_order_two());
+
+        // x^3 - 1 has three roots as p = 1 mod 3
+        let curve = WeierstrassCurve::new(&group, Fp::zero(&field), minus_one);
+        assert!(curve.has_points_of_order_two());
+
+        // x^3 + x - 2 = (x - 1)(x^2 + x + 2) has one root as -7 is not a square
+        let curve = WeierstrassCurve::new(&group, Fp::one(&field), minus_two);
+        assert!(curve.has_points_of_order_two());
+
+        let curve = WeierstrassCurve::new(&group, Fp::one(&field), Fp::zero(&field));
+        assert!(curve.has_points_of_order_two());
+    }
 }
\ No newline at end of file
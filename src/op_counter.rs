@@ -0,0 +1,103 @@
+//! Counters of base field operations for gas modelling. Every extension
+//! field and curve operation is eventually a sequence of `Fp` operations,
+//! so counting those is enough to get exact costs of any call.
+//!
+//! Counters are only maintained with `op_counter` feature enabled,
+//! otherwise all the functions in this module are no-ops.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationCounts {
+    /// Montgomery multiplications
+    pub mul: u64,
+    /// Montgomery squarings
+    pub square: u64,
+    /// Additions, subtractions, doublings and negations
+    pub add: u64,
+    /// Inversions
+    pub inverse: u64,
+}
+
+#[cfg(feature = "op_counter")]
+thread_local! {
+    static COUNTS: std::cell::Cell<OperationCounts> = std::cell::Cell::new(OperationCounts::default());
+}
+
+#[cfg(feature = "op_counter")]
+#[inline(always)]
+fn update<F: FnOnce(&mut OperationCounts)>(f: F) {
+    COUNTS.with(|counts| {
+        let mut current = counts.get();
+        f(&mut current);
+        counts.set(current);
+    });
+}
+
+#[cfg(not(feature = "op_counter"))]
+#[inline(always)]
+fn update<F: FnOnce(&mut OperationCounts)>(_f: F) {
+}
+
+#[inline(always)]
+pub(crate) fn count_mul() {
+    update(|c| c.mul += 1);
+}
+
+#[inline(always)]
+pub(crate) fn count_square() {
+    update(|c| c.square += 1);
+}
+
+#[inline(always)]
+pub(crate) fn count_add() {
+    update(|c| c.add += 1);
+}
+
+#[inline(always)]
+pub(crate) fn count_inverse() {
+    update(|c| c.inverse += 1);
+}
+
+/// Returns counts of operations performed by the current thread since the last reset
+#[cfg(feature = "op_counter")]
+pub fn current_counts() -> OperationCounts {
+    COUNTS.with(|counts| counts.get())
+}
+
+#[cfg(feature = "op_counter")]
+pub fn reset_counts() {
+    COUNTS.with(|counts| counts.set(OperationCounts::default()));
+}
+
+#[cfg(all(test, feature = "op_counter"))]
+mod tests {
+    use super::{current_counts, reset_counts, OperationCounts};
+    use crate::field::{U256Repr, new_field};
+    use crate::fp::Fp;
+    use crate::traits::FieldElement;
+
+    #[test]
+    fn test_count_base_field_operations() {
+        let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let mut a = Fp::one(&field);
+        a.double();
+        let b = a.clone();
+
+        reset_counts();
+        a.mul_assign(&b);
+        a.square();
+        a.add_assign(&b);
+        a.sub_assign(&b);
+        a.negate();
+        let _ = a.inverse().unwrap();
+
+        assert_eq!(current_counts(), OperationCounts {
+            mul: 1,
+            square: 1,
+            add: 3,
+            inverse: 1
+        });
+
+        reset_counts();
+        assert_eq!(current_counts(), OperationCounts::default());
+    }
+}
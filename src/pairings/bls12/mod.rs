@@ -1,16 +1,24 @@
-use crate::field::SizedPrimeField;
+use crate::field::{SizedPrimeField, PrimeField};
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
 use crate::traits::{FieldElement, BitIterator, MsbBitIterator};
 use crate::weierstrass::Group;
 use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
 use crate::weierstrass::twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::weierstrass::gls::{GlsParameters, endomorphism_powers, mul_4_way, signed_from_limbs};
+use std::sync::OnceLock;
+use crate::weierstrass::glv::{biguint_from_limbs, limbs_from_biguint, cube_root_of_unity};
+use crate::field::biguint_to_u64_vec;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
 use crate::extension_towers::fp2::{Fp2, Extension2};
 use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
 use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
-use crate::pairings::PairingEngine;
+use crate::context::{CurveContext, Fp12TowerContext, fp_from_mont_repr, fp2_from_mont_repr, fp2_into_mont_repr};
+use crate::pairings::gt::Gt;
+use crate::pairings::{PairingEngine, encode_fp2_ell_coeffs, decode_fp2_ell_coeffs};
 
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum TwistType {
     D,
     M
@@ -25,6 +33,22 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> PreparedTwistPoint<'a,
     pub fn is_zero(&self) -> bool {
         self.is_infinity
     }
+
+    /// Serializes line coefficients, so the point can be prepared once and cached
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_fp2_ell_coeffs(self.is_infinity, &self.ell_coeffs)
+    }
+
+    /// Decodes line coefficients produced by `to_bytes`. Does not check that
+    /// those were prepared by the engine the point is used with
+    pub fn from_bytes(extension: &'a Extension2<'a, FE, F>, bytes: &[u8]) -> Result<Self, ()> {
+        let (is_infinity, ell_coeffs) = decode_fp2_ell_coeffs(extension, bytes)?;
+
+        Ok(Self {
+            is_infinity,
+            ell_coeffs
+        })
+    }
 }
 
 pub struct Bls12Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
@@ -34,9 +58,11 @@ pub struct Bls12Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE:
     pub base_field: &'a F,
     pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
     pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
-    fp2_extension: &'a Extension2<'a, FE, F>,
-    fp6_extension: &'a Extension3Over2<'a, FE, F>,
-    fp12_extension: &'a Extension2Over3Over2<'a, FE, F>,
+    pub fp2_extension: &'a Extension2<'a, FE, F>,
+    pub fp6_extension: &'a Extension3Over2<'a, FE, F>,
+    pub fp12_extension: &'a Extension2Over3Over2<'a, FE, F>,
+    // derived on the first use, see `gls_parameters`
+    gls: OnceLock<GlsParameters>
 }
 
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Bls12Instance<'a, FE, F, GE, G> {
@@ -211,6 +237,106 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         }
     }
 
+    // psi(Q) = (x^p * xi^((p-1)/3), y^p * xi^((p-1)/2)) for D-type twist and with
+    // inverse coefficients for M-type twist, xi^((p-1)/2) = xi^((p-1)/3) * xi^((p-1)/6)
+    fn psi_coefficients(&self) -> (Fp2<'a, FE, F>, Fp2<'a, FE, F>) {
+        let x_coeff = self.fp6_extension.frobenius_coeffs_c1[1].clone();
+        let mut y_coeff = x_coeff.clone();
+        y_coeff.mul_assign(&self.fp12_extension.frobenius_coeffs_c1[1]);
+
+        match self.twist_type {
+            TwistType::D => (x_coeff, y_coeff),
+            TwistType::M => (x_coeff.inverse().unwrap(), y_coeff.inverse().unwrap()),
+        }
+    }
+
+    /// Untwist-Frobenius-twist endomorphism, acts on G2 as multiplication by p
+    pub fn psi(&self, twist_point: &TwistPoint<'a, FE, F, GE, G>) -> TwistPoint<'a, FE, F, GE, G> {
+        let (x_coeff, y_coeff) = self.psi_coefficients();
+
+        twist_point.psi(&x_coeff, &y_coeff)
+    }
+
+    /// Multiplication of a point in G2 with the scalar split 4-way over psi
+    pub fn mul_g2<S: AsRef<[u64]>>(&self, twist_point: &TwistPoint<'a, FE, F, GE, G>, scalar: S) -> TwistPoint<'a, FE, F, GE, G> {
+        self.multiexp_g2_impl(std::iter::once((twist_point, scalar.as_ref())))
+    }
+
+    /// Multiexp over points in G2 with every scalar split 4-way over psi. Returns
+    /// `None` if numbers of points and scalars differ
+    pub fn multiexp_g2<S: AsRef<[u64]>>(&self, twist_points: &[TwistPoint<'a, FE, F, GE, G>], scalars: &[S]) -> Option<TwistPoint<'a, FE, F, GE, G>> {
+        if twist_points.len() != scalars.len() {
+            return None;
+        }
+
+        Some(self.multiexp_g2_impl(twist_points.iter().zip(scalars.iter().map(|s| s.as_ref()))))
+    }
+
+    /// Decomposition of scalars for `mul_g2` and `multiexp_g2`, derived on the first call
+    fn gls_parameters(&self) -> &GlsParameters {
+        self.gls.get_or_init(|| {
+            GlsParameters::for_bls12(&self.x, self.x_is_negative, self.curve_twist.scalar_field.modulus().as_ref())
+        })
+    }
+
+    fn multiexp_g2_impl<'b, I>(&self, pairs: I) -> TwistPoint<'a, FE, F, GE, G>
+    where 'a: 'b,
+        I: IntoIterator<Item = (&'b TwistPoint<'a, FE, F, GE, G>, &'b [u64])>
+    {
+        let gls = self.gls_parameters();
+        let (x_coeff, y_coeff) = self.psi_coefficients();
+        let terms = pairs.into_iter().map(|(point, scalar)| {
+            (endomorphism_powers(point, |p| p.psi(&x_coeff, &y_coeff)), gls.decompose(scalar))
+        }).collect();
+
+        mul_4_way(&TwistPoint::zero(self.curve_twist), terms)
+    }
+
+    // beta = x^5 - 3x^4 + 3x^3 - x + 1 for sigma(x, y) = (beta * x, y) that acts on G1
+    // as multiplication by x^2 - 1, the other cube root of unity gives -x^2
+    fn sigma_beta(&self) -> Option<Fp<'a, FE, F>> {
+        let x = signed_from_limbs(&self.x, self.x_is_negative);
+        let x_cubed = &x * &x * &x;
+        let beta = &x_cubed * &x * &x - BigInt::from(3) * &x_cubed * &x + BigInt::from(3) * &x_cubed - &x + BigInt::one();
+
+        cube_root_of_unity(self.base_field, &beta)
+    }
+
+    /// Bowe's test (https://eprint.iacr.org/2019/814.pdf): P is in G1 iff
+    /// [(x^2 - 1)/3](2 sigma(P) - P - sigma^2(P)) = sigma^2(P). Point must be on the curve
+    pub fn is_in_g1(&self, point: &CurvePoint<'a, FE, F, GE, G>) -> bool {
+        let x = biguint_from_limbs(&self.x);
+        let x_squared_minus_one = &x * &x - BigUint::one();
+        let beta = match self.sigma_beta() {
+            Some(beta) => beta,
+            None => return point.mul(self.curve.scalar_field.modulus()).is_zero()
+        };
+
+        let sigma = point.sigma(&beta);
+        let sigma_2 = sigma.sigma(&beta);
+
+        let mut t = sigma;
+        t.double();
+        t.sub_assign(point);
+        t.sub_assign(&sigma_2);
+        let mut t = t.mul(biguint_to_u64_vec(x_squared_minus_one / BigUint::from(3u64)));
+        t.sub_assign(&sigma_2);
+
+        t.is_zero()
+    }
+
+    /// Scott's test (https://eprint.iacr.org/2021/1130.pdf): Q is in G2 iff psi(Q) = [x]Q.
+    /// Point must be on the twist
+    pub fn is_in_g2(&self, twist_point: &TwistPoint<'a, FE, F, GE, G>) -> bool {
+        let mut t = twist_point.mul(&self.x);
+        if self.x_is_negative {
+            t.negate();
+        }
+        t.sub_assign(&self.psi(twist_point));
+
+        t.is_zero()
+    }
+
     pub fn prepare(&self, twist_point: & TwistPoint<'a, FE, F, GE, G>) -> PreparedTwistPoint<'a, FE, F> {
         debug_assert!(twist_point.is_normalized());
 
@@ -250,33 +376,63 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
                 &'b TwistPoint<'a, FE, F, GE, G>)
         >
     {
+        let mut g1_references = vec![];
+        let mut prepared = vec![];
+
+        for (p, q) in i.into_iter() {
+            if !p.is_zero() && !q.is_zero() {
+                prepared.push(self.prepare(q));
+                g1_references.push(*p);
+            }
+        }
+
+        let pairs: Vec<_> = g1_references.into_iter().zip(prepared.iter()).collect();
+
+        // coefficients are produced by this engine, so have the right length
+        self.prepared_miller_loop(&pairs[..]).unwrap()
+    }
+
+    fn ell_coeffs_len(&self) -> usize {
+        MsbBitIterator::new(&self.x).skip(1).map(|i| if i { 2 } else { 1 }).sum()
+    }
+
+    /// Multi-Miller loop over the G1 points in affine coordinates and twist points
+    /// prepared by `prepare`. Pairs with a point at infinity are skipped, returns
+    /// `None` if some prepared point has a wrong number of line coefficients
+    pub fn prepared_miller_loop<'b, I>(&self, i: I) -> Option<Fp12<'a, FE, F>>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
+                &'b PreparedTwistPoint<'a, FE, F>)
+        >
+    {
+        let ell_coeffs_len = self.ell_coeffs_len();
+
         let mut g1_references = vec![];
         let mut prepared_coeffs = vec![];
 
         for (p, q) in i.into_iter() {
             if !p.is_zero() && !q.is_zero() {
-                let coeffs = self.prepare(&q.clone());
-                let ell_coeffs = coeffs.ell_coeffs;
-                prepared_coeffs.push(ell_coeffs);
+                if q.ell_coeffs.len() != ell_coeffs_len {
+                    return None;
+                }
+                prepared_coeffs.push(q.ell_coeffs.iter());
                 g1_references.push(p);
             }
         }
 
-        let mut prepared_coeffs: Vec<_> = prepared_coeffs.into_iter().map(|el| el.into_iter()).collect();
-
         let mut f = Fp12::one(self.fp12_extension);
 
         for i in MsbBitIterator::new(&self.x).skip(1) {
-        // for i in BitIterator::new(&self.x).skip(1) {
             f.square();
 
             for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                self.ell(&mut f, &coeffs.next().unwrap(), p);
+                self.ell(&mut f, coeffs.next().unwrap(), p);
             }
 
             if i {
                 for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                    self.ell(&mut f, &coeffs.next().unwrap(), p);
+                    self.ell(&mut f, coeffs.next().unwrap(), p);
                 }
             }
         }
@@ -285,87 +441,43 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
             f.conjugate();
         }
 
-        f
+        Some(f)
     }
 
-    fn final_exponentiation(&self, f: &Fp12<'a, FE, F>) -> Option<Fp12<'a, FE, F>> {
-        // Computing the final exponentation following
-        // https://eprint.iacr.org/2016/130.pdf.
-        // We don't use their "faster" formula because it is difficult to make
-        // it work for curves with odd `P::X`.
-        // Hence we implement the algorithm from Table 1 below.
+    /// Same as `pair`, but with twist points that were prepared beforehand. Returns
+    /// `None` if the slices have different lengths
+    pub fn pair_prepared(
+        &self,
+        points: &[CurvePoint<'a, FE, F, GE, G>],
+        prepared: &[PreparedTwistPoint<'a, FE, F>]
+    ) -> Option<Fp12<'a, FE, F>> {
+        if points.len() != prepared.len() {
+            return None;
+        }
 
-        // f1 = r.conjugate() = f^(p^6)
-        let mut f1 = f.clone();
-        f1.frobenius_map(6);
+        let pairs: Vec<_> = points.iter().zip(prepared.iter()).collect();
+        let loop_result = self.prepared_miller_loop(&pairs[..])?;
 
-        match f.inverse() {
-            Some(mut f2) => {
-                // f2 = f^(-1);
-                // r = f^(p^6 - 1)
-                let mut r = f1.clone();
-                r.mul_assign(&f2);
-
-                // f2 = f^(p^6 - 1)
-                f2 = r.clone();
-                // r = f^((p^6 - 1)(p^2))
-                r.frobenius_map(2);
-
-                // r = f^((p^6 - 1)(p^2) + (p^6 - 1))
-                // r = f^((p^6 - 1)(p^2 + 1))
-                r.mul_assign(&f2);
-
-                // Hard part of the final exponentation is below:
-                // From https://eprint.iacr.org/2016/130.pdf, Table 1
-                let mut y0 = r.clone();
-                y0.cyclotomic_square();
-                y0.conjugate();
-
-                let mut y5 = r.clone();
-                self.exp_by_x(&mut y5);
-
-                let mut y1 = y5.clone();
-                y1.cyclotomic_square();
-
-                let mut y3 = y0.clone();
-                y3.mul_assign(&y5);
-
-                let mut y0 = y3.clone();
-                self.exp_by_x(&mut y0);
-            
-                let mut y2 = y0.clone();
-                self.exp_by_x(&mut y2);
-
-                let mut y4 = y2.clone();
-                self.exp_by_x(&mut y4);
-                y4.mul_assign(&y1);
-
-                let mut y1 = y4.clone();
-                self.exp_by_x(&mut y1);
-
-                y3.conjugate();
-                y1.mul_assign(&y3);
-                y1.mul_assign(&r);
-
-                let mut y3 = r.clone();
-                y3.conjugate();
-                y0.mul_assign(&r);
-                y0.frobenius_map(3);
-
-                y4.mul_assign(&y3);
-                y4.frobenius_map(1);
-                
-                y5.mul_assign(&y2);
-                y5.frobenius_map(2);
-
-                y5.mul_assign(&y0);
-                y5.mul_assign(&y4);
-                y5.mul_assign(&y1);
-
-                Some(y5)
-            },
-            None => None,
-        }
+        self.final_exponentiation(&loop_result)
+    }
+
+    /// Same as `pair`, but wraps the result into the target group element
+    pub fn pair_gt(
+        &self,
+        points: &[CurvePoint<'a, FE, F, GE, G>],
+        twists: &[TwistPoint<'a, FE, F, GE, G>]
+    ) -> Option<Gt<'a, FE, F, GE, G>> {
+        let value = self.pair(points, twists)?;
+
+        Some(Gt::from_fp12_unchecked(value, self.curve.scalar_field))
+    }
+
+    pub fn gt_one(&self) -> Gt<'a, FE, F, GE, G> {
+        Gt::one(self.fp12_extension, self.curve.scalar_field)
+    }
+
+    pub fn gt_from_bytes(&self, bytes: &[u8]) -> Result<Gt<'a, FE, F, GE, G>, ()> {
+        Gt::from_bytes(self.fp12_extension, self.curve.scalar_field, bytes)
     }
 }
 
@@ -375,16 +487,209 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     type G1 = CurvePoint<'a, FE, F, GE, G>;
     type G2 = TwistPoint<'a, FE, F, GE, G>;
 
-    fn pair<'b>
-        (&self, points: &'b [CurvePoint<'a, FE, F, GE, G>], twists: &'b [TwistPoint<'a, FE, F, GE, G>]) -> Option<Self::PairingResult> {
-            let mut pairs = vec![];
-            for (p, q) in points.iter().zip(twists.iter()) {
-                pairs.push((p, q));
-            }
-            let loop_result = self.miller_loop(&pairs[..]);
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &Fp12<'a, FE, F>) -> Option<Fp12<'a, FE, F>> {
+        // f1 = r.conjugate() = f^(p^6)
+        let mut f1 = f.clone();
+        f1.frobenius_map(6);
 
-            self.final_exponentiation(&loop_result)
-        }   
+        // f2 = f^(-1);
+        let f2 = f.inverse()?;
+
+        // r = f^(p^6 - 1)
+        let mut r = f1;
+        r.mul_assign(&f2);
+
+        // f2 = f^(p^6 - 1)
+        let f2 = r.clone();
+        // r = f^((p^6 - 1)(p^2))
+        r.frobenius_map(2);
+
+        // r = f^((p^6 - 1)(p^2) + (p^6 - 1))
+        // r = f^((p^6 - 1)(p^2 + 1))
+        r.mul_assign(&f2);
+
+        Some(r)
+    }
+
+    fn final_exponentiation_hard_part(&self, r: &Fp12<'a, FE, F>) -> Fp12<'a, FE, F> {
+        // Raises to the power 3(p^4 - p^2 + 1)/r = (x - 1)^2 (x + p) (x^2 + p^2 - 1) + 3
+        // following Hayashida, Hayasaka and Teruya https://eprint.iacr.org/2020/875.pdf.
+        // It gives the same result as the chain from Table 1 of https://eprint.iacr.org/2016/130.pdf
+        // for both signs of x with five exponentiations by x, but saves three multiplications,
+        // a squaring and a Frobenius map. Sign of x is handled in `exp_by_x`
+
+        let mut r_inv = r.clone();
+        r_inv.conjugate();
+
+        // t = r^((x - 1)^2)
+        let mut t = r.clone();
+        self.exp_by_x(&mut t);
+        t.mul_assign(&r_inv);
+
+        let mut t_inv = t.clone();
+        t_inv.conjugate();
+        self.exp_by_x(&mut t);
+        t.mul_assign(&t_inv);
+
+        // t = t^(x + p)
+        let mut t_p = t.clone();
+        t_p.frobenius_map(1);
+        self.exp_by_x(&mut t);
+        t.mul_assign(&t_p);
+
+        // t = t^(x^2 + p^2 - 1)
+        let mut t_p2 = t.clone();
+        t_p2.frobenius_map(2);
+        let mut t_inv = t.clone();
+        t_inv.conjugate();
+        self.exp_by_x(&mut t);
+        self.exp_by_x(&mut t);
+        t.mul_assign(&t_p2);
+        t.mul_assign(&t_inv);
+
+        // t * r^3
+        let mut r3 = r.clone();
+        r3.cyclotomic_square();
+        r3.mul_assign(r);
+        t.mul_assign(&r3);
+
+        t
+    }
+
+    fn target_one(&self) -> Fp12<'a, FE, F> {
+        Fp12::one(self.fp12_extension)
+    }
+}
+
+/// Owned parameters of the BLS12 curve and it's extension tower. Can be moved
+/// and shared between threads, and the engine is reconstructed on the
+/// stack by `with_engine` without any recomputation
+#[derive(Clone, Debug)]
+pub struct Bls12Context<FE: ElementRepr, GE: ElementRepr> {
+    curve: CurveContext<FE, GE>,
+    tower: Fp12TowerContext<FE>,
+    twist_a: [FE; 2],
+    twist_b: [FE; 2],
+    x: Vec<u64>,
+    x_is_negative: bool,
+    twist_type: TwistType,
+    // filled by the first multiplication in G2 of an engine, so contexts
+    // in the cache don't derive it again
+    gls: OnceLock<GlsParameters>
+}
+
+impl<FE: ElementRepr, GE: ElementRepr> Bls12Context<FE, GE> {
+    /// Context of the BLS12 curve y^2 = x^3 + b given by `curve` for the parameter x, given
+    /// by the absolute value and the sign. The Fp12 tower is built over the non-residues as in
+    /// `Fp12TowerContext::new`, and the twist is y^2 = x^3 + b/xi for D-type or b*xi for
+    /// M-type with xi = `fp2_non_residue`. Returns an error if p and r don't match x
+    pub fn new(
+        curve: CurveContext<FE, GE>,
+        x: &BigUint,
+        x_is_negative: bool,
+        fp_non_residue: &BigUint,
+        fp2_non_residue: (&BigUint, &BigUint),
+        twist_type: TwistType
+    ) -> Result<Self, ()> {
+        let signed_x = if x_is_negative { -BigInt::from(x.clone()) } else { BigInt::from(x.clone()) };
+        // r = x^4 - x^2 + 1 and p = (x - 1)^2 * r / 3 + x
+        let r = (&signed_x * &signed_x - 1) * &signed_x * &signed_x + 1;
+        let x_minus_one = &signed_x - 1;
+        let p = &x_minus_one * &x_minus_one * &r / 3 + &signed_x;
+        let modulus = BigInt::from(biguint_from_limbs(curve.base_field.modulus().as_ref()));
+        let order = BigInt::from(biguint_from_limbs(curve.scalar_field.modulus().as_ref()));
+        if x.is_zero() || !fp_from_mont_repr(&curve.base_field, curve.a).is_zero() || p != modulus || r != order {
+            return Err(());
+        }
+
+        let tower = Fp12TowerContext::new(&curve.base_field, fp_non_residue, fp2_non_residue)?;
+
+        curve.with_curve(|curve_ref| {
+            tower.with_tower(&curve.base_field, |extension_2, extension_6, extension_12| {
+                let xi = &extension_6.non_residue;
+                let mut twist_b = match twist_type {
+                    TwistType::D => xi.inverse().ok_or(())?,
+                    TwistType::M => xi.clone()
+                };
+                twist_b.mul_by_fp(&curve_ref.b);
+                let twist = WeierstrassCurveTwist::new(&curve.scalar_field, extension_2, Fp2::zero(extension_2), twist_b);
+
+                let engine = Bls12Instance {
+                    x: limbs_from_biguint(x),
+                    x_is_negative,
+                    twist_type,
+                    base_field: &curve.base_field,
+                    curve: curve_ref,
+                    curve_twist: &twist,
+                    fp2_extension: extension_2,
+                    fp6_extension: extension_6,
+                    fp12_extension: extension_12,
+                    gls: OnceLock::new()
+                };
+
+                Ok(Self::from_instance(&engine))
+            })
+        })
+    }
+
+    pub fn from_instance(engine: &Bls12Instance<'_, FE, PrimeField<FE>, GE, PrimeField<GE>>) -> Self {
+        Self {
+            curve: CurveContext::from_curve(engine.curve),
+            tower: Fp12TowerContext::from_extension(engine.fp12_extension),
+            twist_a: fp2_into_mont_repr(&engine.curve_twist.a),
+            twist_b: fp2_into_mont_repr(&engine.curve_twist.b),
+            x: engine.x.clone(),
+            x_is_negative: engine.x_is_negative,
+            twist_type: engine.twist_type,
+            gls: engine.gls.clone()
+        }
+    }
+
+    pub fn curve(&self) -> &CurveContext<FE, GE> {
+        &self.curve
+    }
+
+    pub fn with_engine<R, Func>(&self, f: Func) -> R
+        where Func: for<'a> FnOnce(&'a Bls12Instance<'a, FE, PrimeField<FE>, GE, PrimeField<GE>>) -> R
+    {
+        self.curve.with_curve(|curve| {
+            self.tower.with_tower(&self.curve.base_field, |extension_2, extension_6, extension_12| {
+                let twist = WeierstrassCurveTwist::new(
+                    &self.curve.scalar_field,
+                    extension_2,
+                    fp2_from_mont_repr(extension_2, self.twist_a),
+                    fp2_from_mont_repr(extension_2, self.twist_b)
+                );
+
+                let engine = Bls12Instance {
+                    x: self.x.clone(),
+                    x_is_negative: self.x_is_negative,
+                    twist_type: self.twist_type,
+                    base_field: &self.curve.base_field,
+                    curve,
+                    curve_twist: &twist,
+                    fp2_extension: extension_2,
+                    fp6_extension: extension_6,
+                    fp12_extension: extension_12,
+                    gls: OnceLock::new()
+                };
+                if let Some(gls) = self.gls.get() {
+                    let _ = engine.gls.set(gls.clone());
+                }
+
+                let result = f(&engine);
+                if let Some(gls) = engine.gls.into_inner() {
+                    let _ = self.gls.set(gls);
+                }
+
+                result
+            })
+        })
+    }
 }
 
 #[cfg(test)]
@@ -393,7 +698,7 @@ mod tests {
     use num_traits::FromPrimitive;
     use num_integer::Integer;
     use num_traits::Zero;
-    use crate::field::{U384Repr, U256Repr, new_field};
+    use crate::field::{U384Repr, U256Repr, new_field, SizedPrimeField};
     use crate::fp::Fp;
     use crate::traits::{FieldElement};
     use crate::extension_towers::fp2::{Fp2, Extension2};
@@ -405,6 +710,11 @@ mod tests {
     use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
     use crate::weierstrass::twist::{TwistPoint, WeierstrassCurveTwist};
     use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+    use crate::field::biguint_to_u64_vec;
+    use crate::context::{fp_from_mont_repr, fp2_from_mont_repr, fp2_into_mont_repr};
+    use num_bigint::BigInt;
+    use num_traits::One;
     use rust_test::Bencher;
 
     #[test]
@@ -459,7 +769,7 @@ mod tests {
         };
 
 
-        let coeffs = frobenius_calculator_fp12(modulus, &extension_12).unwrap();
+        let coeffs = frobenius_calculator_fp12(modulus.clone(), &extension_12).unwrap();
         extension_12.frobenius_coeffs_c1 = coeffs;
 
         let b_fp = Fp::from_repr(&base_field, U384Repr::from(4)).unwrap();
@@ -518,18 +828,208 @@ mod tests {
             fp2_extension: &extension_2,
             fp6_extension: &extension_6,
             fp12_extension: &extension_12,
+            gls: std::sync::OnceLock::new()
         };
 
-        let pairing_result = bls12_engine.pair(&[p], &[q]).unwrap();
+        let (p_x, p_y) = (p.x.repr, p.y.repr);
+        let (q_x, q_y) = (fp2_into_mont_repr(&q.x), fp2_into_mont_repr(&q.y));
+        let context = super::Bls12Context::from_instance(&bls12_engine);
+
+        let pairing_result = bls12_engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
 
         // let expected_c0_c0_c0 = BigUint::from_str_radix("1250ebd871fc0a92a7b2d83168d0d727272d441befa15c503dd8e90ce98db3e7b6d194f60839c508a84305aaca1789b6", 16).unwrap();
         
         assert!(format!("{}",pairing_result.c0.c0.c0) == "0x1250ebd871fc0a92a7b2d83168d0d727272d441befa15c503dd8e90ce98db3e7b6d194f60839c508a84305aaca1789b6");
         // println!("Res = {}", pairing_result);
+
+        // owned context gives the same result after being moved to another thread
+        let context_result = std::thread::spawn(move || {
+            context.with_engine(|engine| {
+                let p = CurvePoint::point_from_xy(engine.curve, fp_from_mont_repr(engine.base_field, p_x), fp_from_mont_repr(engine.base_field, p_y));
+                let q_x = fp2_from_mont_repr(engine.curve_twist.base_field, q_x);
+                let q_y = fp2_from_mont_repr(engine.curve_twist.base_field, q_y);
+                let q = TwistPoint::point_from_xy(engine.curve_twist, q_x, q_y);
+
+                format!("{}", engine.pair(&[p], &[q]).unwrap())
+            })
+        }).join().unwrap();
+        assert_eq!(context_result, format!("{}", pairing_result));
     }
 
-    #[bench]
-    fn bench_bls12_381_pairing(b: &mut Bencher) {
+    #[test]
+    fn test_bls12_381_torus_compression() {
+        with_bls12_381_engine(|engine, p, q| {
+            let extension_12 = engine.fp12_extension;
+            let scalar_field = engine.curve.scalar_field;
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // torus compression keeps a half (T2) or a third (T6) of the coefficients
+            let gt = engine.pair_gt(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+            let k = Fp::from_repr(scalar_field, U256Repr::from(0x1234567890abcdefu64)).unwrap();
+            let m = pairing_result.torus_t2_compress().unwrap();
+            assert!(Fp12::torus_t2_decompress(extension_12, &m).unwrap() == pairing_result);
+            let (m1, m2) = pairing_result.torus_t6_compress().unwrap();
+            assert!(Fp12::torus_t6_decompress(extension_12, &m1, &m2).unwrap() == pairing_result);
+            assert!(Fp12::one(extension_12).torus_t2_compress().is_none());
+
+            let m_k = Fp12::torus_t2_exp(extension_12, &m, k.into_repr()).unwrap();
+            assert!(Fp12::torus_t2_decompress(extension_12, &m_k).unwrap() == *gt.pow(&k).as_fp12());
+            assert!(Fp12::torus_t2_exp(extension_12, &m, scalar_field.modulus()).is_none());
+        });
+    }
+
+    #[test]
+    fn test_bls12_381_subgroup_checks() {
+        with_bls12_381_engine(|engine, p, q| {
+            let base_field = engine.base_field;
+            let scalar_field = engine.curve.scalar_field;
+            let curve = engine.curve;
+
+            // endomorphism based subgroup checks agree with multiplication by the order
+            assert!(crate::pairings::tests::check_subgroup_membership(p, q, |p| engine.is_in_g1(p), |q| engine.is_in_g2(q)) > 0);
+
+            // (0, 2) is on the curve, but not in G1
+            let not_in_g1 = CurvePoint::point_from_xy(curve, Fp::zero(base_field), Fp::from_repr(base_field, U384Repr::from(2)).unwrap());
+            assert!(not_in_g1.check_on_curve());
+            assert!(!not_in_g1.mul(scalar_field.modulus()).is_zero());
+            assert!(!engine.is_in_g1(&not_in_g1));
+        });
+    }
+
+    #[test]
+    fn test_bls12_381_g2_endomorphism() {
+        with_bls12_381_engine(|engine, _, q| {
+            let context = super::Bls12Context::from_instance(engine);
+
+            // psi acts as p on G2, 4-way multiplication agrees with double-and-add
+            crate::pairings::tests::check_g2_endomorphism(q, |q| engine.psi(q), |q, k| engine.mul_g2(q, k), |points, scalars| engine.multiexp_g2(points, scalars));
+
+            // parameters of the decomposition are derived once per engine and kept by contexts
+            assert!(engine.gls.get().is_some());
+            assert!(super::Bls12Context::from_instance(engine).gls.get().is_some());
+            assert!(context.gls.get().is_none());
+            assert!(context.with_engine(|engine| engine.mul_g2(&TwistPoint::zero(engine.curve_twist), [1u64]).is_zero()));
+            assert!(context.gls.get().is_some());
+        });
+    }
+
+    #[test]
+    fn test_bls12_381_target_group() {
+        with_bls12_381_engine(|engine, p, q| {
+            let scalar_field = engine.curve.scalar_field;
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // target group element supports group operations and a round trip through bytes
+            let gt = engine.pair_gt(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+            let mut product = gt.inverse();
+            product.mul_assign(&gt);
+            assert!(product == engine.gt_one());
+
+            let k = Fp::from_repr(scalar_field, U256Repr::from(0x1234567890abcdefu64)).unwrap();
+            let mut p_k = p.mul(k.into_repr());
+            p_k.normalize();
+            assert!(engine.pair_gt(&[p_k], std::slice::from_ref(q)).unwrap() == gt.pow(&k));
+
+            let gt_encoding = gt.to_bytes();
+            assert_eq!(gt_encoding.len(), 12 * 48);
+            assert!(engine.gt_from_bytes(&gt_encoding).unwrap() == gt);
+            assert!(engine.gt_from_bytes(&gt_encoding[..gt_encoding.len() - 1]).is_err());
+
+            let mut perturbed = pairing_result.clone();
+            perturbed.c1.c2.c1.double();
+            assert!(super::Gt::from_fp12(perturbed, scalar_field).is_none());
+        });
+    }
+
+    #[test]
+    fn test_bls12_381_pairing_check() {
+        with_bls12_381_engine(|engine, p, q| {
+            let curve = engine.curve;
+
+            // product of pairings is checked with a single final exponentiation
+            let mut p_neg = p.clone();
+            p_neg.negate();
+            assert_eq!(engine.pairing_check(&[(p.clone(), q.clone()), (p_neg.clone(), q.clone())]), Ok(true));
+            assert_eq!(engine.pairing_check(&[(p.clone(), q.clone()), (CurvePoint::zero(curve), q.clone())]), Ok(false));
+            assert_eq!(engine.pairing_check(&[(CurvePoint::zero(curve), q.clone())]), Ok(true));
+            assert_eq!(engine.pairing_check(&[]), Ok(true));
+            assert!(engine.pair(&[p.clone(), p_neg.clone()], std::slice::from_ref(q)).is_none());
+        });
+    }
+
+    #[test]
+    fn test_bls12_381_prepared_pairing() {
+        with_bls12_381_engine(|engine, p, q| {
+            let extension_2 = engine.fp2_extension;
+            let extension_12 = engine.fp12_extension;
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // prepared twist point gives the same result after a round trip through bytes
+            let encoding = engine.prepare(q).to_bytes();
+            let prepared = super::PreparedTwistPoint::from_bytes(extension_2, &encoding).unwrap();
+            assert!(engine.pair_prepared(std::slice::from_ref(p), std::slice::from_ref(&prepared)).unwrap() == pairing_result);
+
+            // every point needs its own prepared twist point
+            assert!(engine.pair_prepared(&[p.clone(), p.clone()], std::slice::from_ref(&prepared)).is_none());
+            assert!(engine.pair_prepared(&[], std::slice::from_ref(&prepared)).is_none());
+
+            // multi-Miller loop over e(P, Q) * e(-P, Q)
+            let mut p_neg = p.clone();
+            p_neg.negate();
+            assert!(engine.pair_prepared(&[p.clone(), p_neg], &[prepared, engine.prepare(q)]).unwrap() == Fp12::one(extension_12));
+
+            // truncated encoding and a wrong number of line coefficients are rejected
+            assert!(super::PreparedTwistPoint::from_bytes(extension_2, &encoding[..encoding.len() - 1]).is_err());
+            let short = super::PreparedTwistPoint::from_bytes(extension_2, &encoding[..encoding.len() - 288]).unwrap();
+            assert!(engine.pair_prepared(std::slice::from_ref(p), &[short]).is_none());
+        });
+    }
+
+    #[test]
+    fn test_bls12_381_reference_pairing() {
+        with_bls12_381_engine(|engine, p, q| {
+            let base_field = engine.base_field;
+            let extension_12 = engine.fp12_extension;
+            let modulus = crate::weierstrass::glv::biguint_from_limbs(base_field.modulus().as_ref());
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // optimal ate over x - q agrees with the reference Tate pairing evaluated with
+            // untwisted Q = (x' / w^2, y' / w^3) as the Miller point, the hard part of the
+            // final exponentiation computes a cube of it
+            let group_order = BigUint::from_str_radix("52435875175126190479447740508185965837690552500527637822603658699938581184513", 10).unwrap();
+            let reference = ReferencePairing::new(Fp12::zero(extension_12), Fp12::one(extension_12), modulus.clone(), group_order.clone(), 12).unwrap();
+
+            let (p_x_affine, p_y_affine) = p.into_xy();
+            let mut p_embedded = (Fp12::zero(extension_12), Fp12::zero(extension_12));
+            p_embedded.0.c0.c0.c0 = p_x_affine;
+            p_embedded.1.c0.c0.c0 = p_y_affine;
+
+            let mut w = Fp12::zero(extension_12);
+            w.c1.c0.c0 = Fp::one(base_field);
+            let mut w_2 = w.clone();
+            w_2.square();
+            let mut w_3 = w_2.clone();
+            w_3.mul_assign(&w);
+
+            let (q_x_affine, q_y_affine) = q.into_xy();
+            let mut q_untwisted = (Fp12::zero(extension_12), Fp12::zero(extension_12));
+            q_untwisted.0.c0.c0 = q_x_affine;
+            q_untwisted.0.mul_assign(&w_2.inverse().unwrap());
+            q_untwisted.1.c0.c0 = q_y_affine;
+            q_untwisted.1.mul_assign(&w_3.inverse().unwrap());
+
+            let x = -BigInt::from(0xd201000000010000u64);
+            let exponent = reference.ate_exponent(&[x, -BigInt::one()]).unwrap();
+            let exponent = (exponent * BigUint::from(3u64)) % &group_order;
+            let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+            assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+        });
+    }
+
+    // engine, generators of G1 and G2 of BLS12-381 for tests and benchmarks
+    fn with_bls12_381_engine<R, F>(f: F) -> R
+        where F: for<'a> FnOnce(&'a super::Bls12Instance<'a, U384Repr, crate::field::PrimeField<U384Repr>, U256Repr, crate::field::PrimeField<U256Repr>>, &CurvePoint<'a, U384Repr, crate::field::PrimeField<U384Repr>, U256Repr, crate::field::PrimeField<U256Repr>>, &TwistPoint<'a, U384Repr, crate::field::PrimeField<U384Repr>, U256Repr, crate::field::PrimeField<U256Repr>>) -> R
+    {
         let modulus = BigUint::from_str_radix("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", 10).unwrap();
         let base_field = new_field::<U384Repr>("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", 10).unwrap();
         let scalar_field = new_field::<U256Repr>("52435875175126190479447740508185965837690552500527637822603658699938581184513", 10).unwrap();
@@ -639,10 +1139,48 @@ mod tests {
             fp2_extension: &extension_2,
             fp6_extension: &extension_6,
             fp12_extension: &extension_12,
+            gls: std::sync::OnceLock::new()
         };
 
-        b.iter(|| {
-            bls12_engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+        f(&bls12_engine, &p, &q)
+    }
+
+    #[bench]
+    fn bench_bls12_381_pairing(b: &mut Bencher) {
+        with_bls12_381_engine(|engine, p, q| {
+            b.iter(|| {
+                engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+            });
+        });
+    }
+
+    // a scalar of full length, for which the 4-way split is not trivial
+    fn g2_bench_scalar() -> Vec<u64> {
+        vec![0x92a3b4c5d6e7f809, 0x1a2b3c4d5e6f7081, 0x92a3b4c5d6e7f809, 0x1a2b3c4d5e6f7081]
+    }
+
+    #[bench]
+    fn bench_bls12_381_g2_mul(b: &mut Bencher) {
+        let scalar = g2_bench_scalar();
+        with_bls12_381_engine(|_, _, q| {
+            b.iter(|| q.mul(&scalar));
+        });
+    }
+
+    #[bench]
+    fn bench_bls12_381_g2_mul_gls(b: &mut Bencher) {
+        let scalar = g2_bench_scalar();
+        with_bls12_381_engine(|engine, _, q| {
+            b.iter(|| engine.mul_g2(q, &scalar));
+        });
+    }
+
+    #[bench]
+    fn bench_bls12_381_g2_multiexp_gls(b: &mut Bencher) {
+        let scalars = vec![g2_bench_scalar(); 4];
+        with_bls12_381_engine(|engine, _, q| {
+            let points: Vec<_> = (0..4).map(|i| q.mul([i as u64 + 1])).collect();
+            b.iter(|| engine.multiexp_g2(&points, &scalars).unwrap());
         });
     }
 
@@ -748,8 +1286,13 @@ mod tests {
             fp2_extension: &extension_2,
             fp6_extension: &extension_6,
             fp12_extension: &extension_12,
+            gls: std::sync::OnceLock::new()
         };
 
+        // psi acts as p on G2, 4-way multiplication agrees with double-and-add
+        crate::pairings::tests::check_g2_endomorphism(&q, |q| bls12_engine.psi(q), |q, k| bls12_engine.mul_g2(q, k), |points, scalars| bls12_engine.multiexp_g2(points, scalars));
+        assert!(bls12_engine.is_in_g1(&p) && bls12_engine.is_in_g2(&q));
+
         let pairing_result = bls12_engine.pair(&[p], &[q]).unwrap();
 
         assert!(format!("{}",pairing_result.c0.c0.c0) == "0x00b718ff624a95f189bfb44bcd6d6556226837c1f74d1afbf4bea573b71c17d3a243cae41d966e2164aad0991fd790cc");
@@ -0,0 +1,611 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, MsbBitIterator};
+use crate::weierstrass::Group;
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::quartic_twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+use crate::extension_towers::fp12_as_3_over_4::Extension3Over4;
+use crate::extension_towers::fp24_as_2_over3_over_4::{Fp24, Extension2Over3Over4};
+use crate::pairings::PairingEngine;
+use crate::pairings::bls12::TwistType;
+
+// Ate pairing for the BLS24 curves. Tower is Fp24 = Fp12[z]/(z^2 - w),
+// Fp12 = Fp4[w]/(w^3 - xi) and the second argument is taken from the sextic
+// twist defined over Fp4, so Miller loop is the same as for BLS12 curves
+
+pub struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    is_infinity: bool,
+    pub ell_coeffs: Vec<(Fp4<'a, FE, F>, Fp4<'a, FE, F>, Fp4<'a, FE, F>)>
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> PreparedTwistPoint<'a, FE, F> {
+    pub fn is_zero(&self) -> bool {
+        self.is_infinity
+    }
+}
+
+pub struct Bls24Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    pub x: Vec<u64>,
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+    pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
+    fp4_extension: &'a Extension2Over2<'a, FE, F>,
+    fp12_extension: &'a Extension3Over4<'a, FE, F>,
+    fp24_extension: &'a Extension2Over3Over4<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Bls24Instance<'a, FE, F, GE, G> {
+    fn ell(
+        &self,
+        f: &mut Fp24<'a, FE, F>,
+        coeffs: &(Fp4<'a, FE, F>, Fp4<'a, FE, F>, Fp4<'a, FE, F>),
+        p: & CurvePoint<'a, FE, F, GE, G>,
+    ) {
+        debug_assert!(p.is_normalized());
+        let mut c0 = coeffs.0.clone();
+        let mut c1 = coeffs.1.clone();
+        let mut c2 = coeffs.2.clone();
+
+        match self.twist_type {
+            TwistType::M => {
+                c2.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                f.mul_by_014(&c0, &c1, &c2);
+            },
+            TwistType::D => {
+                c0.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                f.mul_by_034(&c0, &c1, &c2);
+            },
+        }
+    }
+
+    fn exp_by_x(&self, f: &mut Fp24<'a, FE, F>) {
+        *f = f.cyclotomic_exp(&self.x);
+        if self.x_is_negative {
+            f.conjugate();
+        }
+    }
+
+    fn doubling_step(
+        &self,
+        r: &mut TwistPoint<'a, FE, F, GE, G>,
+        two_inv: &Fp<'a, FE, F>,
+    ) -> (Fp4<'a, FE, F>, Fp4<'a, FE, F>, Fp4<'a, FE, F>) {
+        // Use adapted formulas from ZEXE instead
+        let mut a = r.x.clone();
+        a.mul_assign(&r.y);
+        a.mul_by_fp(two_inv);
+        let mut b = r.y.clone();
+        b.square();
+        let mut c = r.z.clone();
+        c.square();
+
+        let mut e = self.curve_twist.b.clone();
+        let mut t0 = c.clone();
+        t0.double();
+        t0.add_assign(&c);
+
+        e.mul_assign(&t0);
+
+        let mut f = e.clone();
+        f.double();
+        f.add_assign(&e);
+
+        let mut g = b.clone();
+        g.add_assign(&f);
+        g.mul_by_fp(two_inv);
+
+        let mut h = r.y.clone();
+        h.add_assign(&r.z);
+        h.square();
+
+        let mut t1 = b.clone();
+        t1.add_assign(&c);
+
+        h.sub_assign(&t1);
+
+        let mut i = e.clone();
+        i.sub_assign(&b);
+
+        let mut j = r.x.clone();
+        j.square();
+
+        let mut e_square = e.clone();
+        e_square.square();
+
+        r.x = b.clone();
+        r.x.sub_assign(&f);
+        r.x.mul_assign(&a);
+
+        let mut e_square_by_3 = e_square.clone();
+        e_square_by_3.double();
+        e_square_by_3.add_assign(&e_square);
+
+        r.y = g;
+        r.y.square();
+        r.y.sub_assign(&e_square_by_3);
+
+        r.z = b.clone();
+        r.z.mul_assign(&h);
+
+        let mut j_by_three = j.clone();
+        j_by_three.double();
+        j_by_three.add_assign(&j);
+        h.negate();
+
+        match self.twist_type {
+            TwistType::M => {
+                (i, j_by_three, h)
+            },
+            TwistType::D => {
+                (h, j_by_three, i)
+            },
+        }
+    }
+
+    fn addition_step(
+        &self,
+        r: &mut TwistPoint<'a, FE, F, GE, G>,
+        q: &TwistPoint<'a, FE, F, GE, G>,
+    ) -> (Fp4<'a, FE, F>, Fp4<'a, FE, F>, Fp4<'a, FE, F>) {
+        debug_assert!(q.is_normalized());
+        // use adapted zexe formulas too instead of ones from pairing crate
+        let mut theta = q.y.clone();
+        theta.mul_assign(&r.z);
+        theta.negate();
+        theta.add_assign(&r.y);
+
+        let mut lambda = q.x.clone();
+        lambda.mul_assign(&r.z);
+        lambda.negate();
+        lambda.add_assign(&r.x);
+
+        let mut c = theta.clone();
+        c.square();
+        let mut d = lambda.clone();
+        d.square();
+        let mut e = lambda.clone();
+        e.mul_assign(&d);
+        let mut f = r.z.clone();
+        f.mul_assign(&c);
+        let mut g = r.x.clone();
+        g.mul_assign(&d);
+
+        let mut h = g.clone();
+        h.double();
+        h.negate();
+        h.add_assign(&e);
+        h.add_assign(&f);
+        
+
+        r.x = lambda.clone();
+        r.x.mul_assign(&h);
+
+        let mut t0 = g.clone();
+        t0.sub_assign(&h);
+        t0.mul_assign(&theta);
+
+        r.y.mul_assign(&e);
+        r.y.negate();
+        r.y.add_assign(&t0);
+
+        r.z.mul_assign(&e);
+
+        let mut t1 = lambda.clone();
+        t1.mul_assign(&q.y);
+        
+        let mut j = theta.clone();
+        j.mul_assign(&q.x);
+        j.sub_assign(&t1);
+
+        theta.negate();
+        match self.twist_type {
+            TwistType::M => (j, theta, lambda),
+            TwistType::D => (lambda, theta, j),
+        }
+    }
+
+    pub fn prepare(&self, twist_point: & TwistPoint<'a, FE, F, GE, G>) -> PreparedTwistPoint<'a, FE, F> {
+        debug_assert!(twist_point.is_normalized());
+
+        let mut two_inv = Fp::one(self.base_field);
+        two_inv.double();
+        let two_inv = two_inv.inverse().unwrap();
+
+        if twist_point.is_zero() {
+            return PreparedTwistPoint {
+                ell_coeffs: vec![],
+                is_infinity:   true,
+            };
+        }
+
+        let mut ell_coeffs = vec![];
+        let mut r = TwistPoint::point_from_xy(self.curve_twist, twist_point.x.clone(), twist_point.y.clone());
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+        // for i in BitIterator::new(&self.x).skip(1) {
+            ell_coeffs.push(self.doubling_step(&mut r, &two_inv));
+
+            if i {
+                ell_coeffs.push(self.addition_step(&mut r, twist_point));
+            }
+        }
+
+        PreparedTwistPoint {
+            ell_coeffs,
+            is_infinity: false,
+        }
+    }
+
+    fn miller_loop<'b, I>(&self, i: I) -> Fp24<'a, FE, F>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
+                &'b TwistPoint<'a, FE, F, GE, G>)
+        >
+    {
+        let mut g1_references = vec![];
+        let mut prepared_coeffs = vec![];
+
+        for (p, q) in i.into_iter() {
+            if !p.is_zero() && !q.is_zero() {
+                let coeffs = self.prepare(q);
+                let ell_coeffs = coeffs.ell_coeffs;
+                prepared_coeffs.push(ell_coeffs);
+                g1_references.push(p);
+            }
+        }
+
+        let mut prepared_coeffs: Vec<_> = prepared_coeffs.into_iter().map(|el| el.into_iter()).collect();
+
+        let mut f = Fp24::one(self.fp24_extension);
+
+        for i in MsbBitIterator::new(&self.x).skip(1) {
+        // for i in BitIterator::new(&self.x).skip(1) {
+            f.square();
+
+            for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+                self.ell(&mut f, &coeffs.next().unwrap(), p);
+            }
+
+            if i {
+                for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+                    self.ell(&mut f, &coeffs.next().unwrap(), p);
+                }
+            }
+        }
+
+        if self.x_is_negative {
+            f.conjugate();
+        }
+
+        f
+    }
+}
+
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for Bls24Instance<'a, FE, F, GE, G> {
+    type PairingResult = Fp24<'a, FE, F>;
+    type G1 = CurvePoint<'a, FE, F, GE, G>;
+    type G2 = TwistPoint<'a, FE, F, GE, G>;
+
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &Fp24<'a, FE, F>) -> Option<Fp24<'a, FE, F>> {
+        // easy part is (q^12 - 1)*(q^4 + 1)
+
+        // f1 = f^(q^12)
+        let mut f1 = f.clone();
+        f1.conjugate();
+
+        let f2 = f.inverse()?;
+
+        // r = f^(q^12 - 1)
+        let mut r = f1;
+        r.mul_assign(&f2);
+
+        // r = f^((q^12 - 1)(q^4 + 1))
+        let mut f2 = r.clone();
+        f2.frobenius_map(4);
+        r.mul_assign(&f2);
+
+        Some(r)
+    }
+
+    fn final_exponentiation_hard_part(&self, r: &Fp24<'a, FE, F>) -> Fp24<'a, FE, F> {
+        // hard part is computed as 3*(q^8 - q^4 + 1)/r = sum(lambda_i * q^i) with
+        // lambda_7 = (x - 1)^2, lambda_6 = x * lambda_7, lambda_5 = x * lambda_6,
+        // lambda_4 = x * lambda_5, lambda_3 = x * lambda_4 - lambda_7,
+        // lambda_2 = x * lambda_3, lambda_1 = x * lambda_2, lambda_0 = x * lambda_1 + 3
+        // that are eight exponentiations by x and one by x - 1
+
+        // r is in the cyclotomic subgroup now
+        let mut r_inv = r.clone();
+        r_inv.conjugate();
+
+        // y7 = r^((x - 1)^2)
+        let mut y7 = r.clone();
+        self.exp_by_x(&mut y7);
+        y7.mul_assign(&r_inv);
+        let mut y7_inv = y7.clone();
+        y7_inv.conjugate();
+        self.exp_by_x(&mut y7);
+        y7.mul_assign(&y7_inv);
+
+        let mut y6 = y7.clone();
+        self.exp_by_x(&mut y6);
+
+        let mut y5 = y6.clone();
+        self.exp_by_x(&mut y5);
+
+        let mut y4 = y5.clone();
+        self.exp_by_x(&mut y4);
+
+        let mut y3 = y4.clone();
+        self.exp_by_x(&mut y3);
+        let mut y7_inv = y7.clone();
+        y7_inv.conjugate();
+        y3.mul_assign(&y7_inv);
+
+        let mut y2 = y3.clone();
+        self.exp_by_x(&mut y2);
+
+        let mut y1 = y2.clone();
+        self.exp_by_x(&mut y1);
+
+        let mut y0 = y1.clone();
+        self.exp_by_x(&mut y0);
+        let mut r_cubed = r.clone();
+        r_cubed.square();
+        r_cubed.mul_assign(r);
+        y0.mul_assign(&r_cubed);
+
+        let mut res = y0;
+        for (i, mut y) in vec![y1, y2, y3, y4, y5, y6, y7].into_iter().enumerate() {
+            y.frobenius_map(i + 1);
+            res.mul_assign(&y);
+        }
+
+        res
+    }
+
+    fn target_one(&self) -> Fp24<'a, FE, F> {
+        Fp24::one(self.fp24_extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, BigInt};
+    use crate::field::{U256Repr, U320Repr, new_field, biguint_to_u64_vec};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement};
+    use crate::extension_towers::fp2::{Fp2, Extension2};
+    use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+    use crate::extension_towers::fp12_as_3_over_4::{Fp12, Extension3Over4};
+    use crate::extension_towers::fp24_as_2_over3_over_4::{Fp24, Extension2Over3Over4};
+    use num_traits::Num;
+    use crate::pairings::{frobenius_calculator_fp2, frobenius_calculator_fp4_as_2_over_2};
+    use crate::pairings::{frobenius_calculator_fp12_as_3_over_4, frobenius_calculator_fp24};
+    use crate::pairings::bls12::TwistType;
+    use crate::weierstrass::{Group};
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::quartic_twist::{TwistPoint, WeierstrassCurveTwist};
+    use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+    use num_integer::Integer;
+
+    fn fp4_from_decimal<'a>(extension_4: &'a Extension2Over2<'a, U320Repr, crate::field::PrimeField<U320Repr>>, coeffs: [&str; 4]) -> Fp4<'a, U320Repr, crate::field::PrimeField<U320Repr>> {
+        let base_field = extension_4.field.field;
+        let mut els = coeffs.iter().map(|c| {
+            let c = BigUint::from_str_radix(c, 10).unwrap().to_bytes_be();
+            Fp::from_be_bytes(base_field, &c, true).unwrap()
+        });
+
+        let mut el = Fp4::zero(extension_4);
+        el.c0.c0 = els.next().unwrap();
+        el.c0.c1 = els.next().unwrap();
+        el.c1.c0 = els.next().unwrap();
+        el.c1.c1 = els.next().unwrap();
+
+        el
+    }
+
+    #[test]
+    fn test_bls24_315_pairing() {
+        let modulus = BigUint::from_str_radix("39705142709513438335025689890408969744933502416914749335064285505637884093126342347073617133569", 10).unwrap();
+        let base_field = new_field::<U320Repr>("39705142709513438335025689890408969744933502416914749335064285505637884093126342347073617133569", 10).unwrap();
+        let scalar_field = new_field::<U256Repr>("11502027791375260645628074404575422495959608200132055716665986169834464870401", 10).unwrap();
+        let fp_non_residue = Fp::from_repr(&base_field, U320Repr::from(13)).unwrap();
+
+        let mut extension_2 = Extension2 {
+            field: &base_field,
+            non_residue: fp_non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp2(&extension_2).unwrap();
+        extension_2.frobenius_coeffs_c1 = coeffs;
+
+        let one = Fp::one(&base_field);
+
+        let mut fp2_non_residue = Fp2::zero(&extension_2); // non-residue is 0 + 1*u
+        fp2_non_residue.c1 = one.clone();
+
+        let mut extension_4 = Extension2Over2 {
+            non_residue: fp2_non_residue,
+            field: &extension_2,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp4_as_2_over_2(modulus.clone(), &extension_4).unwrap();
+        extension_4.frobenius_coeffs_c1 = coeffs;
+
+        let mut fp4_non_residue = Fp4::zero(&extension_4); // non-residue is 0 + 1*v
+        fp4_non_residue.c1 = Fp2::one(&extension_2);
+
+        let f_c1 = [Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4)];
+
+        let mut extension_12 = Extension3Over4 {
+            non_residue: fp4_non_residue.clone(),
+            field: &extension_4,
+            frobenius_coeffs_c1: f_c1.clone(),
+            frobenius_coeffs_c2: f_c1,
+        };
+
+        let (coeffs_c1, coeffs_c2) = frobenius_calculator_fp12_as_3_over_4(modulus.clone(), &extension_12).unwrap();
+        extension_12.frobenius_coeffs_c1 = coeffs_c1;
+        extension_12.frobenius_coeffs_c2 = coeffs_c2;
+
+        let f_c1 = [Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4),
+                    Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4), Fp4::zero(&extension_4)];
+
+        let mut extension_24 = Extension2Over3Over4 {
+            non_residue: Fp12::zero(&extension_12),
+            field: &extension_12,
+            frobenius_coeffs_c1: f_c1,
+        };
+
+        let coeffs = frobenius_calculator_fp24(modulus.clone(), &extension_24).unwrap();
+        extension_24.frobenius_coeffs_c1 = coeffs;
+
+        // y^2 = x^3 + 1, D-type twist is y^2 = x^3 + 1/v
+        let b_fp = one.clone();
+        let a_fp = Fp::zero(&base_field);
+
+        let mut b_fp4 = fp4_non_residue.inverse().unwrap();
+        b_fp4.mul_by_fp(&b_fp);
+        let a_fp4 = Fp4::zero(&extension_4);
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let twist = WeierstrassCurveTwist::new(&scalar_field, &extension_4, a_fp4, b_fp4);
+
+        let p_x = BigUint::from_str_radix("16795384585322627672728192924523563227366917890649586150329052275134155277327465407081410209743", 10).unwrap().to_bytes_be();
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = BigUint::from_str_radix("38760381574527554648560284714622508391527760957711556250564980351890417956618605887926137785272", 10).unwrap().to_bytes_be();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        let q_x = fp4_from_decimal(&extension_4, [
+            "8727951979821338569433626790272909814799232166906008632179713520605824932390011845227521961927",
+            "37043736716364891805921713972526592325216611580757030369877741977776453117064110352063640395203",
+            "16185928931420053387986809870694383174618400496436770030110635679193141371610091035696206894060",
+            "31778423039606838598160105113603654425973456771539935178353918494780132115880139759118458939011"
+        ]);
+        let q_y = fp4_from_decimal(&extension_4, [
+            "4968838795988231246958605966712506674880112961890335320818743643337756378244471672500337518290",
+            "28964283233859240092341630525332050294784909304493045983601800949328702418921993379079232721597",
+            "5103229972170250719869507313147705481413030551940816929101288745740724134156344681632664900742",
+            "17966632502586644755654625397851441294100666911065556873707551638224001055038259169506023237946"
+        ]);
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        let engine = super::Bls24Instance {
+            x: vec![0xbfcfffff],
+            x_is_negative: true,
+            twist_type: TwistType::D,
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &twist,
+            fp4_extension: &extension_4,
+            fp12_extension: &extension_12,
+            fp24_extension: &extension_24,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        let expected = [
+            "0x031d5cc9d8b8aa2d286e851f91a964601dccc4d37e67c53192ffbb307350c4441157f0d1b8de41bb",
+            "0x028f13c6faa129ba7a5831d666b9ec0696c451e89f06139b707f807d98e6d094be028bf96ef032e2",
+            "0x00e15c72c1304588f0fbc3e43da823cfd09372ce0fd2cad83f033ffd707a296833bd66ab9d9210c4",
+            "0x006dae1ece5c85a598775fe11d34ae5993452e566df3f7698974dcda7a7bbddad567eef9eace5a69",
+            "0x03fae8c28e5c3ee3c24d9385d7a2774b7cc949c11dd61bd8678b77897605ebec43d1740ef3707417",
+            "0x02e1caab5b25d9d9f03cdc50625a72d92321badeb9a29005392018828662d0c80fc514c0fe368f0b",
+            "0x044bfdd2181a870ee9c4fd720e3e110eb0f61a87e5ba84aa58d1acb8c80d825d9b0355c95c6d7406",
+            "0x00d8242ff4ae7d957a6d3069aae8dec1247bda43da351dbcb09ce5f641a5f4d7226dc2a9fc333182",
+            "0x01f3c267b65bf0276ca12ada5d3134859c3aaad90497db5cd9a4beb5b1671f8e0ec9f7cf7fe7198d",
+            "0x00b45aa0efaff10008ab17330611aa2b7f73171271d960aa6cfc7de80a6c3bb1fd3206e508fd08ef",
+            "0x0105a05702127b17f007296a37beb1ea11934713282869819077a8f0b3057691c4d6e5459c94f780",
+            "0x01998d05a08de33b84f49de15af43b6bb0138fb0eb0fcff1d1c9c59191cc81dc02aaaaf425872e87",
+            "0x029583b5ca97f76b974400361b314155ea3722fac790a156446ce7f6527673b3ec9bbff7052b8777",
+            "0x01470d3c72b3a2c614b073e5022f407ae29ce357aa0ead5a2a48e5d7e6eb7b9605dcffb8ea8f03fe",
+            "0x03994c9564fcf36b7e124f1cb073127f0558f86053c0014775417796013f8693a876804076ca70e0",
+            "0x00a382c1e404e1870f337794117e757641872357fec3f4687fa0d77db45e4a69a2a0e2c07c019531",
+            "0x027caf85623cfed32a06c27d77ab5e7d0a23c78e98cfc6c43ad9546af64d990483a7295cca76ba99",
+            "0x00e44def160a6e6c41da8570d0bd35d9362687ea94680a10658cb25732735cd50e83e65856087a1a",
+            "0x007f8a0e12c01a4c147be5e8e398160470b4bcd45b5551ab4ad1333f11cdb0a4969ec5c8c407269a",
+            "0x0054ad6b35eb4e5bdd7fd962eed059aaa7c15f6c68aa56738e37330281b7e303f0a5233d1c20afdc",
+            "0x020dde943ba34cb282affc14e39cd24f9fcea94ddc46723d32746ceacdc18277ce1292721dba3814",
+            "0x02dff6fd9f8c8259894eb09e29bd3b992113abdbabccfdf9bb4a8746c49c8fc73e5901272a745adb",
+            "0x033eeb831f3077285d88f793ac59d0806b19466705deb2d007a8306352c899562c068f32f256fc04",
+            "0x03fad7d146ea98d791df4e850392a82be897bfc24c055966218dd818488fb157d90cf6a54955d15c",
+        ];
+
+        let mut coeffs = vec![];
+        for fp12 in [&pairing_result.c0, &pairing_result.c1].iter() {
+            for fp4 in [&fp12.c0, &fp12.c1, &fp12.c2].iter() {
+                for fp2 in [&fp4.c0, &fp4.c1].iter() {
+                    coeffs.push(format!("{}", fp2.c0));
+                    coeffs.push(format!("{}", fp2.c1));
+                }
+            }
+        }
+
+        assert_eq!(coeffs, expected);
+
+        // ate pairing over x = q mod r agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' * z^2, y' * z^3) as the Miller point, hard part of the
+        // final exponentiation gives the cube of it
+        let group_order = BigUint::from_str_radix("11502027791375260645628074404575422495959608200132055716665986169834464870401", 10).unwrap();
+        let reference = ReferencePairing::new(Fp24::zero(&extension_24), Fp24::one(&extension_24), modulus, group_order.clone(), 24).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp24::zero(&extension_24), Fp24::zero(&extension_24));
+        p_embedded.0.c0.c0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0.c0.c0 = p_y_affine;
+
+        let (q_x_affine, q_y_affine) = q.into_xy();
+        let mut q_untwisted = (Fp24::zero(&extension_24), Fp24::zero(&extension_24));
+        q_untwisted.0.c0.c1 = q_x_affine;
+        q_untwisted.1.c1.c1 = q_y_affine;
+
+        let exponent = reference.ate_exponent(&[-BigInt::from(0xbfcfffffu64), -BigInt::from(1u64)]).unwrap();
+        let exponent = (exponent * BigUint::from(3u64)).mod_floor(&group_order);
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p.clone(), p_neg], &[q.clone(), q.clone()]).unwrap() == Fp24::one(&extension_24));
+    }
+}
@@ -1,18 +1,26 @@
-use crate::field::SizedPrimeField;
+use crate::field::{SizedPrimeField, PrimeField};
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, MsbBitIterator};
+use crate::traits::{FieldElement, BitIterator};
 use crate::weierstrass::Group;
 use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
 use crate::weierstrass::twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::weierstrass::gls::{GlsParameters, endomorphism_powers, mul_4_way, signed_from_limbs};
+use crate::weierstrass::glv::{cube_root_of_unity, signed_limbs, biguint_from_limbs, limbs_from_biguint};
+use std::sync::OnceLock;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
 use crate::extension_towers::fp2::{Fp2, Extension2};
 use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
 use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
-use crate::pairings::{PairingEngine, into_ternary_wnaf};
+use crate::context::{CurveContext, Fp12TowerContext, fp_from_mont_repr, fp2_from_mont_repr, fp2_into_mont_repr};
+use crate::pairings::gt::Gt;
+use crate::pairings::{PairingEngine, into_ternary_wnaf, encode_fp2_ell_coeffs, decode_fp2_ell_coeffs};
 
-// TODO: fix using https://eprint.iacr.org/2013/722.pdf
+// Optimal ate pairing following https://eprint.iacr.org/2013/722.pdf, Miller loop
+// runs over the signed digit representation of 6u+2
 
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum TwistType {
     D,
     M
@@ -27,6 +35,22 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> PreparedTwistPoint<'a,
     pub fn is_zero(&self) -> bool {
         self.is_infinity
     }
+
+    /// Serializes line coefficients, so the point can be prepared once and cached
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_fp2_ell_coeffs(self.is_infinity, &self.ell_coeffs)
+    }
+
+    /// Decodes line coefficients produced by `to_bytes`. Does not check that
+    /// those were prepared by the engine the point is used with
+    pub fn from_bytes(extension: &'a Extension2<'a, FE, F>, bytes: &[u8]) -> Result<Self, ()> {
+        let (is_infinity, ell_coeffs) = decode_fp2_ell_coeffs(extension, bytes)?;
+
+        Ok(Self {
+            is_infinity,
+            ell_coeffs
+        })
+    }
 }
 
 pub struct BnInstance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
@@ -37,10 +61,12 @@ pub struct BnInstance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: El
     pub base_field: &'a F,
     pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
     pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
-    fp2_extension: &'a Extension2<'a, FE, F>,
-    fp6_extension: &'a Extension3Over2<'a, FE, F>,
-    fp12_extension: &'a Extension2Over3Over2<'a, FE, F>,
-    non_residue_in_p_minus_one_over_2: Fp2<'a, FE, F>
+    pub fp2_extension: &'a Extension2<'a, FE, F>,
+    pub fp6_extension: &'a Extension3Over2<'a, FE, F>,
+    pub fp12_extension: &'a Extension2Over3Over2<'a, FE, F>,
+    non_residue_in_p_minus_one_over_2: Fp2<'a, FE, F>,
+    // derived on the first use, see `gls_parameters`
+    gls: OnceLock<GlsParameters>
 }
 
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> BnInstance<'a, FE, F, GE, G> {
@@ -265,6 +291,122 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         }
     }
 
+    // psi(Q) = (x^p * xi^((p-1)/3), y^p * xi^((p-1)/2)) for D-type twist
+    // and with inverse coefficients for M-type twist
+    fn psi_coefficients(&self) -> (Fp2<'a, FE, F>, Fp2<'a, FE, F>) {
+        match self.twist_type {
+            TwistType::D => (
+                self.fp6_extension.frobenius_coeffs_c1[1].clone(),
+                self.non_residue_in_p_minus_one_over_2.clone()
+            ),
+            TwistType::M => (
+                self.fp6_extension.frobenius_coeffs_c1[1].inverse().unwrap(),
+                self.non_residue_in_p_minus_one_over_2.inverse().unwrap()
+            ),
+        }
+    }
+
+    /// Untwist-Frobenius-twist endomorphism, acts on G2 as multiplication by p
+    pub fn psi(&self, twist_point: &TwistPoint<'a, FE, F, GE, G>) -> TwistPoint<'a, FE, F, GE, G> {
+        let (x_coeff, y_coeff) = self.psi_coefficients();
+
+        twist_point.psi(&x_coeff, &y_coeff)
+    }
+
+    /// Multiplication of a point in G2 with the scalar split 4-way over psi
+    pub fn mul_g2<S: AsRef<[u64]>>(&self, twist_point: &TwistPoint<'a, FE, F, GE, G>, scalar: S) -> TwistPoint<'a, FE, F, GE, G> {
+        self.multiexp_g2_impl(std::iter::once((twist_point, scalar.as_ref())))
+    }
+
+    /// Multiexp over points in G2 with every scalar split 4-way over psi. Returns
+    /// `None` if numbers of points and scalars differ
+    pub fn multiexp_g2<S: AsRef<[u64]>>(&self, twist_points: &[TwistPoint<'a, FE, F, GE, G>], scalars: &[S]) -> Option<TwistPoint<'a, FE, F, GE, G>> {
+        if twist_points.len() != scalars.len() {
+            return None;
+        }
+
+        Some(self.multiexp_g2_impl(twist_points.iter().zip(scalars.iter().map(|s| s.as_ref()))))
+    }
+
+    /// Decomposition of scalars for `mul_g2` and `multiexp_g2`, derived on the first call
+    fn gls_parameters(&self) -> &GlsParameters {
+        self.gls.get_or_init(|| {
+            GlsParameters::for_bn(&self.u, self.u_is_negative, self.curve_twist.scalar_field.modulus().as_ref())
+        })
+    }
+
+    fn multiexp_g2_impl<'b, I>(&self, pairs: I) -> TwistPoint<'a, FE, F, GE, G>
+    where 'a: 'b,
+        I: IntoIterator<Item = (&'b TwistPoint<'a, FE, F, GE, G>, &'b [u64])>
+    {
+        let gls = self.gls_parameters();
+        let (x_coeff, y_coeff) = self.psi_coefficients();
+        let terms = pairs.into_iter().map(|(point, scalar)| {
+            (endomorphism_powers(point, |p| p.psi(&x_coeff, &y_coeff)), gls.decompose(scalar))
+        }).collect();
+
+        mul_4_way(&TwistPoint::zero(self.curve_twist), terms)
+    }
+
+    // beta = 18u^3 + 18u^2 + 9u + 1 for sigma(x, y) = (beta * x, y) that acts on G1 as
+    // multiplication by lambda = 36u^3 + 18u^2 + 6u + 1, the other cube root of unity
+    // gives -lambda - 1
+    fn sigma_beta(&self) -> Option<Fp<'a, FE, F>> {
+        let u = signed_from_limbs(&self.u, self.u_is_negative);
+        let beta = BigInt::from(18) * &u * &u * &u + BigInt::from(18) * &u * &u + BigInt::from(9) * &u + BigInt::one();
+
+        cube_root_of_unity(self.base_field, &beta)
+    }
+
+    /// With a = 6u^2 + 4u + 1 and b = 2u + 1 that satisfy a^2 - ab + b^2 = r and
+    /// a + b * lambda = 0 mod r, P is in G1 iff [a]P + [b]sigma(P) = 0. It holds for
+    /// points of G1, and if it holds then multiplication by a + b * sigma^2 gives
+    /// [r]P = 0 as sigma^2 + sigma + 1 = 0 on any curve y^2 = x^3 + b, so points of
+    /// other curves over the same field are rejected too. Point must be on the curve
+    pub fn is_in_g1(&self, point: &CurvePoint<'a, FE, F, GE, G>) -> bool {
+        let beta = match self.sigma_beta() {
+            Some(beta) => beta,
+            None => return point.mul(self.curve.scalar_field.modulus()).is_zero()
+        };
+
+        let u = signed_from_limbs(&self.u, self.u_is_negative);
+        let (a_is_negative, a) = signed_limbs(BigInt::from(6) * &u * &u + BigInt::from(4) * &u + BigInt::one());
+        let (b_is_negative, b) = signed_limbs(BigInt::from(2) * &u + BigInt::one());
+
+        let mut t = point.mul(a);
+        if a_is_negative {
+            t.negate();
+        }
+        let mut sigma_b = point.sigma(&beta).mul(b);
+        if b_is_negative {
+            sigma_b.negate();
+        }
+        t.add_assign(&sigma_b);
+
+        t.is_zero()
+    }
+
+    /// Scott's test (https://eprint.iacr.org/2021/1130.pdf): Q is in G2 iff
+    /// [u + 1]Q + psi([u]Q) + psi^2([u]Q) = psi^3([2u]Q). Point must be on the twist
+    pub fn is_in_g2(&self, twist_point: &TwistPoint<'a, FE, F, GE, G>) -> bool {
+        let mut u_q = twist_point.mul(&self.u);
+        if self.u_is_negative {
+            u_q.negate();
+        }
+        let psi_u_q = self.psi(&u_q);
+        let psi_2_u_q = self.psi(&psi_u_q);
+        let mut psi_3_two_u_q = self.psi(&psi_2_u_q);
+        psi_3_two_u_q.double();
+
+        let mut t = u_q;
+        t.add_assign(twist_point);
+        t.add_assign(&psi_u_q);
+        t.add_assign(&psi_2_u_q);
+        t.sub_assign(&psi_3_two_u_q);
+
+        t.is_zero()
+    }
+
     pub fn prepare(&self, twist_point: & TwistPoint<'a, FE, F, GE, G>) -> PreparedTwistPoint<'a, FE, F> {
         debug_assert!(twist_point.is_normalized());
 
@@ -282,14 +424,16 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         let mut ell_coeffs = vec![];
         let mut r = TwistPoint::point_from_xy(&self.curve_twist, twist_point.x.clone(), twist_point.y.clone());
 
-        // let wnaf = into_ternary_wnaf(self.u)
+        let mut minus_q = twist_point.clone();
+        minus_q.negate();
 
-        for i in MsbBitIterator::new(&self.six_u_plus_2).skip(1) {
-        // for i in BitIterator::new(&self.six_u_plus_2).skip(1) {
+        for &digit in into_ternary_wnaf(&self.six_u_plus_2).iter().rev().skip(1) {
             ell_coeffs.push(self.doubling_step(&mut r, &two_inv));
 
-            if i {
-                ell_coeffs.push(self.addition_step(&mut r, &twist_point));
+            if digit == 1 {
+                ell_coeffs.push(self.addition_step(&mut r, twist_point));
+            } else if digit == -1 {
+                ell_coeffs.push(self.addition_step(&mut r, &minus_q));
             }
         }
 
@@ -297,18 +441,17 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
             r.negate();
         }
 
-        let mut q = twist_point.clone();
-
-        q.x.c1.negate();
-        q.x.mul_assign(&self.fp6_extension.frobenius_coeffs_c1[1]);
-
-        q.y.c1.negate();
-        q.y.mul_assign(&self.non_residue_in_p_minus_one_over_2);
+        let q = self.psi(twist_point);
 
         ell_coeffs.push(self.addition_step(&mut r, &q));
 
+        // -psi^2(Q) = (x * xi^((p^2-1)/3), y), as xi^((p^2-1)/2) = -1
+        let x_coeff_2 = match self.twist_type {
+            TwistType::D => self.fp6_extension.frobenius_coeffs_c1[2].clone(),
+            TwistType::M => self.fp6_extension.frobenius_coeffs_c1[2].inverse().unwrap(),
+        };
         let mut minusq2 = twist_point.clone();
-        minusq2.x.mul_assign(&self.fp6_extension.frobenius_coeffs_c1[2]);
+        minusq2.x.mul_assign(&x_coeff_2);
 
         ell_coeffs.push(self.addition_step(&mut r, &minusq2));
 
@@ -325,33 +468,68 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
                 &'b TwistPoint<'a, FE, F, GE, G>)
         >
     {
+        let mut g1_references = vec![];
+        let mut prepared = vec![];
+
+        for (p, q) in i.into_iter() {
+            if !p.is_zero() && !q.is_zero() {
+                prepared.push(self.prepare(q));
+                g1_references.push(*p);
+            }
+        }
+
+        let pairs: Vec<_> = g1_references.into_iter().zip(prepared.iter()).collect();
+
+        // coefficients are produced by this engine, so have the right length
+        self.prepared_miller_loop(&pairs[..]).unwrap()
+    }
+
+    fn ell_coeffs_len(&self) -> usize {
+        let mut len = 2;
+        for &digit in into_ternary_wnaf(&self.six_u_plus_2).iter().rev().skip(1) {
+            len += if digit == 0 { 1 } else { 2 };
+        }
+
+        len
+    }
+
+    /// Multi-Miller loop over the G1 points in affine coordinates and twist points
+    /// prepared by `prepare`. Pairs with a point at infinity are skipped, returns
+    /// `None` if some prepared point has a wrong number of line coefficients
+    pub fn prepared_miller_loop<'b, I>(&self, i: I) -> Option<Fp12<'a, FE, F>>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
+                &'b PreparedTwistPoint<'a, FE, F>)
+        >
+    {
+        let ell_coeffs_len = self.ell_coeffs_len();
+
         let mut g1_references = vec![];
         let mut prepared_coeffs = vec![];
 
         for (p, q) in i.into_iter() {
             if !p.is_zero() && !q.is_zero() {
-                let coeffs = self.prepare(&q.clone());
-                let ell_coeffs = coeffs.ell_coeffs;
-                prepared_coeffs.push(ell_coeffs);
+                if q.ell_coeffs.len() != ell_coeffs_len {
+                    return None;
+                }
+                prepared_coeffs.push(q.ell_coeffs.iter());
                 g1_references.push(p);
             }
         }
 
-        let mut prepared_coeffs: Vec<_> = prepared_coeffs.into_iter().map(|el| el.into_iter()).collect();
-
         let mut f = Fp12::one(self.fp12_extension);
 
-        for i in MsbBitIterator::new(&self.six_u_plus_2).skip(1) {    
-        // for i in BitIterator::new(&self.six_u_plus_2).skip(1) {
+        for &digit in into_ternary_wnaf(&self.six_u_plus_2).iter().rev().skip(1) {
             f.square();
 
             for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                self.ell(&mut f, &coeffs.next().unwrap(), p);
+                self.ell(&mut f, coeffs.next().unwrap(), p);
             }
 
-            if i {
+            if digit != 0 {
                 for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-                    self.ell(&mut f, &coeffs.next().unwrap(), p);
+                    self.ell(&mut f, coeffs.next().unwrap(), p);
                 }
             }
         }
@@ -361,137 +539,284 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         }
 
         for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            self.ell(&mut f, &coeffs.next().unwrap(), p);
+            self.ell(&mut f, coeffs.next().unwrap(), p);
         }
 
         for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
-            self.ell(&mut f, &coeffs.next().unwrap(), p);
+            self.ell(&mut f, coeffs.next().unwrap(), p);
         }
 
-        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+        for coeffs in prepared_coeffs.iter_mut() {
             debug_assert!(coeffs.next().is_none());
         }
 
-        println!("F = {}", f);
+        Some(f)
+    }
+
+    /// Same as `pair`, but with twist points that were prepared beforehand. Returns
+    /// `None` if the slices have different lengths
+    pub fn pair_prepared(
+        &self,
+        points: &[CurvePoint<'a, FE, F, GE, G>],
+        prepared: &[PreparedTwistPoint<'a, FE, F>]
+    ) -> Option<Fp12<'a, FE, F>> {
+        if points.len() != prepared.len() {
+            return None;
+        }
+
+        let pairs: Vec<_> = points.iter().zip(prepared.iter()).collect();
+        let loop_result = self.prepared_miller_loop(&pairs[..])?;
 
-        f
+        self.final_exponentiation(&loop_result)
     }
 
-    pub fn final_exponentiation(&self, f: &Fp12<'a, FE, F>) -> Option<Fp12<'a, FE, F>> {
-        // use Zexe and pairing crate fused
-        // https://eprint.iacr.org/2012/232.pdf
+    /// Same as `pair`, but wraps the result into the target group element
+    pub fn pair_gt(
+        &self,
+        points: &[CurvePoint<'a, FE, F, GE, G>],
+        twists: &[TwistPoint<'a, FE, F, GE, G>]
+    ) -> Option<Gt<'a, FE, F, GE, G>> {
+        let value = self.pair(points, twists)?;
 
-        // f1 = r.conjugate() = f^(p^6)
-        let mut f1 = f.clone();
-        f1.frobenius_map(6);
+        Some(Gt::from_fp12_unchecked(value, self.curve.scalar_field))
+    }
 
-        match f.inverse() {
-            Some(mut f2) => {
-                let mut r = f1.clone();
-                r.mul_assign(&f2);
+    pub fn gt_one(&self) -> Gt<'a, FE, F, GE, G> {
+        Gt::one(self.fp12_extension, self.curve.scalar_field)
+    }
 
-                f2 = r.clone();
+    pub fn gt_from_bytes(&self, bytes: &[u8]) -> Result<Gt<'a, FE, F, GE, G>, ()> {
+        Gt::from_bytes(self.fp12_extension, self.curve.scalar_field, bytes)
+    }
+}
 
-                r.frobenius_map(2);
-                r.mul_assign(&f2);
 
-                let mut fp = r.clone();
-                fp.frobenius_map(1);
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for BnInstance<'a, FE, F, GE, G> {
+    type PairingResult = Fp12<'a, FE, F>;
+    type G1 = CurvePoint<'a, FE, F, GE, G>;
+    type G2 = TwistPoint<'a, FE, F, GE, G>;
 
-	            let mut fp2 = r.clone();
-                fp2.frobenius_map(2);
-                let mut fp3 = fp2.clone();
-                fp3.frobenius_map(1);
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
 
-                let mut fu = r.clone();
-                self.exp_by_x(&mut fu);
-                // exp_by_x(&mut fu, x);
+    fn final_exponentiation_easy_part(&self, f: &Fp12<'a, FE, F>) -> Option<Fp12<'a, FE, F>> {
+        // Easy part is (p^6 - 1)(p^2 + 1)
 
-                let mut fu2 = fu.clone();
-                self.exp_by_x(&mut fu2);
-                // exp_by_x(&mut fu2, x);
+        // f1 = r.conjugate() = f^(p^6)
+        let mut f1 = f.clone();
+        f1.frobenius_map(6);
 
-                let mut fu3 = fu2.clone();
-                self.exp_by_x(&mut fu3);
-                // exp_by_x(&mut fu3, x);
+        let f2 = f.inverse()?;
+        let mut r = f1;
+        r.mul_assign(&f2);
 
-                let mut y3 = fu.clone();
-                y3.frobenius_map(1);
+        let f2 = r.clone();
 
-                let mut fu2p = fu2.clone();
-                fu2p.frobenius_map(1);
+        r.frobenius_map(2);
+        r.mul_assign(&f2);
 
-                let mut fu3p = fu3.clone();
-                fu3p.frobenius_map(1);
+        Some(r)
+    }
 
-                let mut y2 = fu2.clone();
-                y2.frobenius_map(2);
+    fn final_exponentiation_hard_part(&self, f: &Fp12<'a, FE, F>) -> Fp12<'a, FE, F> {
+        // Chain of Fuentes-Castaneda et al. https://eprint.iacr.org/2012/232.pdf in the form
+        // of Duquesne and Ghammam https://eprint.iacr.org/2015/192.pdf, Algorithm 6. It
+        // raises to the power 2u(6u^2 + 3u + 1) * (p^4 - p^2 + 1)/r instead of the exact
+        // (p^4 - p^2 + 1)/r, that is still coprime with r, with three exponentiations by u.
+        // Sign of u is handled in `exp_by_x`
 
-                let mut y0 = fp.clone();
-                y0.mul_assign(&fp2);
-                y0.mul_assign(&fp3);
+        let mut t0 = f.clone();
+        self.exp_by_x(&mut t0);
+        t0.conjugate();
+        t0.cyclotomic_square();
 
-                let mut y1 = r.clone();
-                y1.conjugate();
+        let mut t1 = t0.clone();
+        t1.cyclotomic_square();
+        t1.mul_assign(&t0);
 
-                let mut y5 = fu2.clone();
-                y5.conjugate();
+        let mut t2 = t1.clone();
+        self.exp_by_x(&mut t2);
+        t2.conjugate();
 
-                y3.conjugate();
+        let mut t3 = t1.clone();
+        t3.conjugate();
+        t1 = t2.clone();
+        t1.mul_assign(&t3);
 
-                let mut y4 = fu.clone();
-                y4.mul_assign(&fu2p);
-                y4.conjugate();
+        t3 = t2.clone();
+        t3.cyclotomic_square();
 
-                let mut y6 = fu3.clone();
-                y6.mul_assign(&fu3p);
-                y6.conjugate();
+        let mut t4 = t3;
+        self.exp_by_x(&mut t4);
+        t4.mul_assign(&t1);
 
-                y6.square();
-                y6.mul_assign(&y4);
-                y6.mul_assign(&y5);
+        let mut t3 = t4.clone();
+        t3.mul_assign(&t0);
 
-                let mut t1 = y3.clone();
-                t1.mul_assign(&y5);
-                t1.mul_assign(&y6);
+        t0 = t2;
+        t0.mul_assign(&t4);
+        t0.mul_assign(f);
 
-                y6.mul_assign(&y2);
+        let mut t2 = t3.clone();
+        t2.frobenius_map(1);
+        t0.mul_assign(&t2);
 
-                t1.square();
-                t1.mul_assign(&y6);
-                t1.square();
+        t4.frobenius_map(2);
+        t0.mul_assign(&t4);
 
-                let mut t0 = t1.clone();
-                t0.mul_assign(&y1);
+        let mut t2 = f.clone();
+        t2.conjugate();
+        t2.mul_assign(&t3);
+        t2.frobenius_map(3);
+        t0.mul_assign(&t2);
 
-                t1.mul_assign(&y0);
+        t0
+    }
 
-                t0.square();
-                t0.mul_assign(&t1);
+    fn target_one(&self) -> Fp12<'a, FE, F> {
+        Fp12::one(self.fp12_extension)
+    }
+}
 
-                Some(t0)
-            }
-            None => None,
+/// Owned parameters of the BN curve and it's extension tower. Can be moved
+/// and shared between threads, and the engine is reconstructed on the
+/// stack by `with_engine` without any recomputation
+#[derive(Clone, Debug)]
+pub struct BnContext<FE: ElementRepr, GE: ElementRepr> {
+    curve: CurveContext<FE, GE>,
+    tower: Fp12TowerContext<FE>,
+    twist_a: [FE; 2],
+    twist_b: [FE; 2],
+    u: Vec<u64>,
+    six_u_plus_2: Vec<u64>,
+    u_is_negative: bool,
+    twist_type: TwistType,
+    non_residue_in_p_minus_one_over_2: [FE; 2],
+    // filled by the first multiplication in G2 of an engine, so contexts
+    // in the cache don't derive it again
+    gls: OnceLock<GlsParameters>
+}
+
+impl<FE: ElementRepr, GE: ElementRepr> BnContext<FE, GE> {
+    /// Context of the BN curve y^2 = x^3 + b given by `curve` for the parameter u, given by
+    /// the absolute value and the sign. The Fp12 tower is built over the non-residues as in
+    /// `Fp12TowerContext::new`, and the twist is y^2 = x^3 + b/xi for D-type or b*xi for
+    /// M-type with xi = `fp2_non_residue`. Returns an error if p and r don't match u
+    pub fn new(
+        curve: CurveContext<FE, GE>,
+        u: &BigUint,
+        u_is_negative: bool,
+        fp_non_residue: &BigUint,
+        fp2_non_residue: (&BigUint, &BigUint),
+        twist_type: TwistType
+    ) -> Result<Self, ()> {
+        let signed_u = if u_is_negative { -BigInt::from(u.clone()) } else { BigInt::from(u.clone()) };
+        // r = 36u^4 + 36u^3 + 18u^2 + 6u + 1 and p = r + 6u^2
+        let r = ((BigInt::from(36) * &signed_u + 36) * &signed_u + 18) * &signed_u * &signed_u + BigInt::from(6) * &signed_u + 1;
+        let p = &r + BigInt::from(6) * &signed_u * &signed_u;
+        let modulus = BigInt::from(biguint_from_limbs(curve.base_field.modulus().as_ref()));
+        let order = BigInt::from(biguint_from_limbs(curve.scalar_field.modulus().as_ref()));
+        if u.is_zero() || !fp_from_mont_repr(&curve.base_field, curve.a).is_zero() || p != modulus || r != order {
+            return Err(());
         }
+
+        let tower = Fp12TowerContext::new(&curve.base_field, fp_non_residue, fp2_non_residue)?;
+        // |6u + 2|
+        let six_u_plus_2 = if u_is_negative { u * 6u64 - 2u64 } else { u * 6u64 + 2u64 };
+
+        curve.with_curve(|curve_ref| {
+            tower.with_tower(&curve.base_field, |extension_2, extension_6, extension_12| {
+                let xi = &extension_6.non_residue;
+                let mut twist_b = match twist_type {
+                    TwistType::D => xi.inverse().ok_or(())?,
+                    TwistType::M => xi.clone()
+                };
+                twist_b.mul_by_fp(&curve_ref.b);
+                let twist = WeierstrassCurveTwist::new(&curve.scalar_field, extension_2, Fp2::zero(extension_2), twist_b);
+
+                // (p - 1)/2
+                let mut exp = curve.base_field.modulus();
+                exp.shr(1);
+
+                let engine = BnInstance {
+                    u: limbs_from_biguint(u),
+                    six_u_plus_2: limbs_from_biguint(&six_u_plus_2),
+                    u_is_negative,
+                    twist_type,
+                    base_field: &curve.base_field,
+                    curve: curve_ref,
+                    curve_twist: &twist,
+                    fp2_extension: extension_2,
+                    fp6_extension: extension_6,
+                    fp12_extension: extension_12,
+                    non_residue_in_p_minus_one_over_2: xi.pow(exp),
+                    gls: OnceLock::new()
+                };
+
+                Ok(Self::from_instance(&engine))
+            })
+        })
     }
-}
 
+    pub fn from_instance(engine: &BnInstance<'_, FE, PrimeField<FE>, GE, PrimeField<GE>>) -> Self {
+        Self {
+            curve: CurveContext::from_curve(engine.curve),
+            tower: Fp12TowerContext::from_extension(engine.fp12_extension),
+            twist_a: fp2_into_mont_repr(&engine.curve_twist.a),
+            twist_b: fp2_into_mont_repr(&engine.curve_twist.b),
+            u: engine.u.clone(),
+            six_u_plus_2: engine.six_u_plus_2.clone(),
+            u_is_negative: engine.u_is_negative,
+            twist_type: engine.twist_type,
+            non_residue_in_p_minus_one_over_2: fp2_into_mont_repr(&engine.non_residue_in_p_minus_one_over_2),
+            gls: engine.gls.clone()
+        }
+    }
 
-impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for BnInstance<'a, FE, F, GE, G> {
-    type PairingResult = Fp12<'a, FE, F>;
-    type G1 = CurvePoint<'a, FE, F, GE, G>;
-    type G2 = TwistPoint<'a, FE, F, GE, G>;
+    pub fn curve(&self) -> &CurveContext<FE, GE> {
+        &self.curve
+    }
 
-    fn pair<'b>
-        (&self, points: &'b [CurvePoint<'a, FE, F, GE, G>], twists: &'b [TwistPoint<'a, FE, F, GE, G>]) -> Option<Self::PairingResult> {
-            let mut pairs = vec![];
-            for (p, q) in points.iter().zip(twists.iter()) {
-                pairs.push((p, q));
-            }
-            let loop_result = self.miller_loop(&pairs[..]);
+    pub fn with_engine<R, Func>(&self, f: Func) -> R
+        where Func: for<'a> FnOnce(&'a BnInstance<'a, FE, PrimeField<FE>, GE, PrimeField<GE>>) -> R
+    {
+        self.curve.with_curve(|curve| {
+            self.tower.with_tower(&self.curve.base_field, |extension_2, extension_6, extension_12| {
+                let twist = WeierstrassCurveTwist::new(
+                    &self.curve.scalar_field,
+                    extension_2,
+                    fp2_from_mont_repr(extension_2, self.twist_a),
+                    fp2_from_mont_repr(extension_2, self.twist_b)
+                );
+
+                let engine = BnInstance {
+                    u: self.u.clone(),
+                    six_u_plus_2: self.six_u_plus_2.clone(),
+                    u_is_negative: self.u_is_negative,
+                    twist_type: self.twist_type,
+                    base_field: &self.curve.base_field,
+                    curve,
+                    curve_twist: &twist,
+                    fp2_extension: extension_2,
+                    fp6_extension: extension_6,
+                    fp12_extension: extension_12,
+                    non_residue_in_p_minus_one_over_2: fp2_from_mont_repr(extension_2, self.non_residue_in_p_minus_one_over_2),
+                    gls: OnceLock::new()
+                };
+                if let Some(gls) = self.gls.get() {
+                    let _ = engine.gls.set(gls.clone());
+                }
 
-            self.final_exponentiation(&loop_result)
-        }   
+                let result = f(&engine);
+                if let Some(gls) = engine.gls.into_inner() {
+                    let _ = self.gls.set(gls);
+                }
+
+                result
+            })
+        })
+    }
 }
 
 fn print_to_radix_16(string: &str) {
@@ -512,7 +837,7 @@ mod tests {
     use num_traits::FromPrimitive;
     use num_integer::Integer;
     use num_traits::Zero;
-    use crate::field::{U384Repr, U256Repr, new_field};
+    use crate::field::{U384Repr, U256Repr, SizedPrimeField, new_field};
     use crate::fp::Fp;
     use crate::traits::{FieldElement};
     use crate::extension_towers::fp2::{Fp2, Extension2};
@@ -524,6 +849,11 @@ mod tests {
     use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
     use crate::weierstrass::twist::{TwistPoint, WeierstrassCurveTwist};
     use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+    use crate::field::biguint_to_u64_vec;
+    use crate::context::{fp_from_mont_repr, fp2_from_mont_repr, fp2_into_mont_repr};
+    use num_bigint::BigInt;
+    use num_traits::One;
     use crate::representation::ElementRepr;
     use rust_test::Bencher;
 
@@ -664,15 +994,467 @@ mod tests {
             fp2_extension: &extension_2,
             fp6_extension: &extension_6,
             fp12_extension: &extension_12,
-            non_residue_in_p_minus_one_over_2: non_residue_in_p_minus_one_over_2
+            non_residue_in_p_minus_one_over_2: non_residue_in_p_minus_one_over_2,
+            gls: std::sync::OnceLock::new()
+        };
+
+        let (p_x, p_y) = (p.x.repr, p.y.repr);
+        let (q_x, q_y) = (fp2_into_mont_repr(&q.x), fp2_into_mont_repr(&q.y));
+        let context = super::BnContext::from_instance(&engine);
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        // owned context gives the same result after being moved to another thread
+        let context_result = std::thread::spawn(move || {
+            context.with_engine(|engine| {
+                let p = CurvePoint::point_from_xy(engine.curve, fp_from_mont_repr(engine.base_field, p_x), fp_from_mont_repr(engine.base_field, p_y));
+                let q_x = fp2_from_mont_repr(engine.curve_twist.base_field, q_x);
+                let q_y = fp2_from_mont_repr(engine.curve_twist.base_field, q_y);
+                let q = TwistPoint::point_from_xy(engine.curve_twist, q_x, q_y);
+
+                format!("{}", engine.pair(&[p], &[q]).unwrap())
+            })
+        }).join().unwrap();
+        assert_eq!(context_result, format!("{}", pairing_result));
+
+        // engine context is built once and then shared through the cache of the API
+        let api = crate::CachedAPI::new(2);
+        let header = modulus.to_bytes_be();
+        let cached = api.engine_context(&header, || Ok(super::BnContext::from_instance(&engine))).unwrap();
+        let again = api.engine_context(&header, || -> Result<super::BnContext<U256Repr, U256Repr>, ()> { panic!("must be cached") }).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&cached, &again));
+        assert_eq!(api.cached_engines(), 1);
+        assert_eq!(api.cached_contexts(), 0);
+
+        let cached_result = again.with_engine(|engine| {
+            let p = CurvePoint::point_from_xy(engine.curve, fp_from_mont_repr(engine.base_field, p_x), fp_from_mont_repr(engine.base_field, p_y));
+            let q_x = fp2_from_mont_repr(engine.curve_twist.base_field, q_x);
+            let q_y = fp2_from_mont_repr(engine.curve_twist.base_field, q_y);
+            let q = TwistPoint::point_from_xy(engine.curve_twist, q_x, q_y);
+
+            format!("{}", engine.pair(&[p], &[q]).unwrap())
+        });
+        assert_eq!(cached_result, format!("{}", pairing_result));
+        api.clear();
+        assert_eq!(api.cached_engines(), 0);
+    }
+
+    #[test]
+    fn test_bn254_subgroup_checks() {
+        with_bn254_engine(|engine, p, q| {
+            let base_field = engine.base_field;
+            let scalar_field = engine.curve.scalar_field;
+
+            // endomorphism based subgroup checks agree with multiplication by the order
+            assert_eq!(crate::pairings::tests::check_subgroup_membership(p, q, |p| engine.is_in_g1(p), |q| engine.is_in_g2(q)), 0);
+
+            // y^2 = x^3 + 2 over the same field has no points of order r, so a point
+            // of it is on the curve of an engine for it, but not in G1
+            let other_curve = WeierstrassCurve::new(scalar_field, Fp::zero(base_field), Fp::from_repr(base_field, U256Repr::from(2)).unwrap());
+            let other_engine = super::BnInstance {
+                u: engine.u.clone(),
+                six_u_plus_2: engine.six_u_plus_2.clone(),
+                curve: &other_curve,
+                non_residue_in_p_minus_one_over_2: engine.non_residue_in_p_minus_one_over_2.clone(),
+                gls: std::sync::OnceLock::new(),
+                ..*engine
+            };
+            let other_y = BigUint::from_str_radix("2ffecaa516289797bda3a21257d498311fdc5aa385fe5343a52cd275f54c49aa", 16).unwrap().to_bytes_be();
+            let other_point = CurvePoint::point_from_xy(&other_curve, Fp::from_repr(base_field, U256Repr::from(3)).unwrap(), Fp::from_be_bytes(base_field, &other_y, true).unwrap());
+            assert!(other_point.check_on_curve());
+            assert!(!other_point.mul(scalar_field.modulus()).is_zero());
+            assert!(!other_engine.is_in_g1(&other_point));
+            assert!(other_engine.is_in_g1(&CurvePoint::zero(&other_curve)));
+        });
+    }
+
+    #[test]
+    fn test_bn254_g2_endomorphism() {
+        with_bn254_engine(|engine, _, q| {
+            let context = super::BnContext::from_instance(engine);
+
+            // psi acts as p on G2, 4-way multiplication agrees with double-and-add
+            crate::pairings::tests::check_g2_endomorphism(q, |q| engine.psi(q), |q, k| engine.mul_g2(q, k), |points, scalars| engine.multiexp_g2(points, scalars));
+
+            // parameters of the decomposition are derived once per engine and kept by contexts
+            assert!(engine.gls.get().is_some());
+            assert!(super::BnContext::from_instance(engine).gls.get().is_some());
+            assert!(context.gls.get().is_none());
+            assert!(context.with_engine(|engine| engine.mul_g2(&TwistPoint::zero(engine.curve_twist), [1u64]).is_zero()));
+            assert!(context.gls.get().is_some());
+        });
+    }
+
+    #[test]
+    fn test_bn254_target_group() {
+        with_bn254_engine(|engine, p, q| {
+            let base_field = engine.base_field;
+            let scalar_field = engine.curve.scalar_field;
+            let modulus = crate::weierstrass::glv::biguint_from_limbs(base_field.modulus().as_ref());
+            let one = Fp::one(base_field);
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // target group element supports group operations and a round trip through bytes
+            let gt = engine.pair_gt(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+            assert!(gt.as_fp12() == &pairing_result);
+            assert!(super::Gt::is_in_subgroup(&pairing_result, scalar_field));
+            let mut product = gt.inverse();
+            product.mul_assign(&gt);
+            assert!(product.is_one());
+            assert!(product == engine.gt_one());
+
+            let k = Fp::from_repr(scalar_field, U256Repr::from(0x1234567890abcdefu64)).unwrap();
+            let mut p_k = p.mul(k.into_repr());
+            p_k.normalize();
+            assert!(engine.pair_gt(&[p_k], std::slice::from_ref(q)).unwrap() == gt.pow(&k));
+            assert!(gt.pow(&Fp::zero(scalar_field)).is_one());
+            assert!(gt.pow(&Fp::one(scalar_field)) == gt);
+
+            let gt_encoding = gt.to_bytes();
+            assert_eq!(gt_encoding.len(), 12 * 32);
+            assert!(engine.gt_from_bytes(&gt_encoding).unwrap() == gt);
+            assert!(engine.gt_from_bytes(&gt_encoding[..gt_encoding.len() - 1]).is_err());
+
+            // coefficient that is not reduced modulo q
+            let mut non_canonical = gt_encoding.clone();
+            let mut q_bytes = vec![0u8; 32 - modulus.to_bytes_be().len()];
+            q_bytes.extend(modulus.to_bytes_be());
+            non_canonical[..32].copy_from_slice(&q_bytes);
+            assert!(engine.gt_from_bytes(&non_canonical).is_err());
+
+            // element of Fp12 that is not in the target group
+            let mut perturbed = pairing_result.clone();
+            perturbed.c0.c0.c0.add_assign(&one);
+            assert!(super::Gt::from_fp12(perturbed.clone(), scalar_field).is_none());
+            let mut perturbed_gt = engine.gt_one();
+            perturbed_gt.mul_assign(&super::Gt::from_fp12_unchecked(perturbed, scalar_field));
+            assert!(engine.gt_from_bytes(&perturbed_gt.to_bytes()).is_err());
+            assert!(engine.gt_from_bytes(&vec![0u8; 12 * 32]).is_err());
+        });
+    }
+
+    #[test]
+    fn test_bn254_pairing_check() {
+        with_bn254_engine(|engine, p, q| {
+            let curve = engine.curve;
+
+            // product of pairings is checked with a single final exponentiation
+            let mut p_neg = p.clone();
+            p_neg.negate();
+            assert_eq!(engine.pairing_check(&[(p.clone(), q.clone()), (p_neg.clone(), q.clone())]), Ok(true));
+            assert_eq!(engine.pairing_check(&[(p.clone(), q.clone()), (CurvePoint::zero(curve), q.clone())]), Ok(false));
+            assert_eq!(engine.pairing_check(&[(CurvePoint::zero(curve), q.clone())]), Ok(true));
+            assert_eq!(engine.pairing_check(&[]), Ok(true));
+            assert!(engine.pair(&[p.clone(), p_neg.clone()], std::slice::from_ref(q)).is_none());
+        });
+    }
+
+    #[test]
+    fn test_bn254_prepared_pairing() {
+        with_bn254_engine(|engine, p, q| {
+            let extension_2 = engine.fp2_extension;
+            let extension_12 = engine.fp12_extension;
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // prepared twist point gives the same result after a round trip through bytes
+            let encoding = engine.prepare(q).to_bytes();
+            let prepared = super::PreparedTwistPoint::from_bytes(extension_2, &encoding).unwrap();
+            assert!(engine.pair_prepared(std::slice::from_ref(p), std::slice::from_ref(&prepared)).unwrap() == pairing_result);
+
+            // every point needs its own prepared twist point
+            assert!(engine.pair_prepared(&[p.clone(), p.clone()], std::slice::from_ref(&prepared)).is_none());
+            assert!(engine.pair_prepared(&[], std::slice::from_ref(&prepared)).is_none());
+
+            // multi-Miller loop over e(P, Q) * e(-P, Q)
+            let mut p_neg = p.clone();
+            p_neg.negate();
+            assert!(engine.pair_prepared(&[p.clone(), p_neg], &[prepared, engine.prepare(q)]).unwrap() == Fp12::one(extension_12));
+
+            // truncated encoding and a wrong number of line coefficients are rejected
+            assert!(super::PreparedTwistPoint::from_bytes(extension_2, &encoding[..encoding.len() - 1]).is_err());
+            let short = super::PreparedTwistPoint::from_bytes(extension_2, &encoding[..encoding.len() - 192]).unwrap();
+            assert!(engine.pair_prepared(std::slice::from_ref(p), &[short]).is_none());
+        });
+    }
+
+    #[test]
+    fn test_bn254_reference_pairing() {
+        with_bn254_engine(|engine, p, q| {
+            let extension_12 = engine.fp12_extension;
+            let modulus = crate::weierstrass::glv::biguint_from_limbs(engine.base_field.modulus().as_ref());
+            let pairing_result = engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+
+            // optimal ate over 6u + 2 + q - q^2 + q^3 agrees with the reference Tate pairing
+            // evaluated with untwisted Q = (x' * v, y' * v * w) as the Miller point
+            let group_order = BigUint::from_str_radix("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+            let reference = ReferencePairing::new(Fp12::zero(extension_12), Fp12::one(extension_12), modulus.clone(), group_order.clone(), 12).unwrap();
+
+            let (p_x_affine, p_y_affine) = p.into_xy();
+            let mut p_embedded = (Fp12::zero(extension_12), Fp12::zero(extension_12));
+            p_embedded.0.c0.c0.c0 = p_x_affine;
+            p_embedded.1.c0.c0.c0 = p_y_affine;
+
+            let (q_x_affine, q_y_affine) = q.into_xy();
+            let mut q_untwisted = (Fp12::zero(extension_12), Fp12::zero(extension_12));
+            q_untwisted.0.c0.c1 = q_x_affine;
+            q_untwisted.1.c1.c1 = q_y_affine;
+
+            let six_u_plus_2 = BigInt::from(4965661367192848881u64) * BigInt::from(6u64) + BigInt::from(2u64);
+            let exponent = reference.ate_exponent(&[six_u_plus_2, BigInt::one(), -BigInt::one(), BigInt::one()]).unwrap();
+            let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+            let optimal_ate = tate.pow(biguint_to_u64_vec(exponent));
+            assert!(format!("{}", optimal_ate.c0.c0.c0) == "0x12c70e90e12b7874510cd1707e8856f71bf7f61d72631e268fca81000db9a1f5");
+
+            // hard part of the final exponentiation computes it's power m = 2u(6u^2 + 3u + 1)
+            let (m, _) = hard_part_multiplier(engine);
+            assert!(optimal_ate.pow(biguint_to_u64_vec(m)) == pairing_result);
+
+            // Weil pairing is an r-th root of unity and is a ratio of Tate pairings
+            let weil = reference.weil(&p_embedded, &q_untwisted).unwrap();
+            assert!(weil != Fp12::one(extension_12));
+            assert!(weil.pow(biguint_to_u64_vec(group_order)) == Fp12::one(extension_12));
+            let mut ratio = reference.tate(&p_embedded, &q_untwisted).unwrap();
+            ratio.mul_assign(&tate.inverse().unwrap());
+            assert!(weil.pow(reference.final_exponent()) == ratio);
+        });
+    }
+
+    // hard part raises to m * (p^4 - p^2 + 1)/r with m = 2u(6u^2 + 3u + 1), returns
+    // the absolute value and the sign of m
+    fn hard_part_multiplier(
+        engine: &super::BnInstance<'_, U256Repr, crate::field::PrimeField<U256Repr>, U256Repr, crate::field::PrimeField<U256Repr>>
+    ) -> (BigUint, bool) {
+        let mut u = BigInt::from_biguint(num_bigint::Sign::Plus, crate::weierstrass::glv::biguint_from_limbs(&engine.u));
+        if engine.u_is_negative {
+            u = -u;
+        }
+
+        let m = BigInt::from(2u64) * &u * (BigInt::from(6u64) * &u * &u + BigInt::from(3u64) * &u + BigInt::one());
+        match m.to_biguint() {
+            Some(m) => (m, false),
+            None => ((-m).to_biguint().unwrap(), true)
+        }
+    }
+
+    fn check_hard_part(
+        engine: &super::BnInstance<'_, U256Repr, crate::field::PrimeField<U256Repr>, U256Repr, crate::field::PrimeField<U256Repr>>,
+        f: &Fp12<'_, U256Repr, crate::field::PrimeField<U256Repr>>
+    ) {
+        let p = crate::weierstrass::glv::biguint_from_limbs(engine.base_field.modulus().as_ref());
+        let r = crate::weierstrass::glv::biguint_from_limbs(engine.curve.scalar_field.modulus().as_ref());
+        let p_squared = &p * &p;
+        let exact = (&p_squared * &p_squared - &p_squared + BigUint::one()) / &r;
+
+        let (m, m_is_negative) = hard_part_multiplier(engine);
+        let mut expected = f.pow(biguint_to_u64_vec(exact * m));
+        if m_is_negative {
+            expected.conjugate();
+        }
+
+        assert!(expected != Fp12::one(engine.fp12_extension));
+        assert!(engine.final_exponentiation_hard_part(f) == expected);
+    }
+
+    fn pair_on_bn_with_negative_u(twist_type: super::TwistType, xi: (u64, u64), q: [&str; 4]) -> Vec<String> {
+        let modulus = BigUint::from_str_radix("16798108731015832284940804142231733909889187121439069848933715426072753864723", 10).unwrap();
+        let base_field = new_field::<U256Repr>("16798108731015832284940804142231733909889187121439069848933715426072753864723", 10).unwrap();
+        let scalar_field = new_field::<U256Repr>("16798108731015832284940804142231733909759579603404752749028378864165570215949", 10).unwrap();
+        let mut fp_non_residue = Fp::one(&base_field);
+        fp_non_residue.negate(); // non-residue is -1
+
+        let mut extension_2 = Extension2 {
+            field: &base_field,
+            non_residue: fp_non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field)]
         };
 
+        let coeffs = frobenius_calculator_fp2(&extension_2).unwrap();
+        extension_2.frobenius_coeffs_c1 = coeffs;
+
+        let mut fp2_non_residue = Fp2::zero(&extension_2);
+        fp2_non_residue.c0 = Fp::from_repr(&base_field, U256Repr::from(xi.0)).unwrap();
+        fp2_non_residue.c1 = Fp::from_repr(&base_field, U256Repr::from(xi.1)).unwrap();
+
+        let f_c1 = [Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2)];
+
+        let mut extension_6 = Extension3Over2 {
+            non_residue: fp2_non_residue.clone(),
+            field: &extension_2,
+            frobenius_coeffs_c1: f_c1.clone(),
+            frobenius_coeffs_c2: f_c1,
+        };
+
+        let (coeffs_c1, coeffs_c2) = frobenius_calculator_fp6_as_3_over_2(modulus.clone(), &extension_6).unwrap();
+
+        extension_6.frobenius_coeffs_c1 = coeffs_c1;
+        extension_6.frobenius_coeffs_c2 = coeffs_c2;
+
+        let f_c1 = [Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2)];
+
+        let mut extension_12 = Extension2Over3Over2 {
+            non_residue: Fp6::zero(&extension_6),
+            field: &extension_6,
+            frobenius_coeffs_c1: f_c1,
+        };
+
+        let coeffs = frobenius_calculator_fp12(modulus, &extension_12).unwrap();
+        extension_12.frobenius_coeffs_c1 = coeffs;
+
+        let b_fp = Fp::from_repr(&base_field, U256Repr::from(2)).unwrap();
+        // b/xi for D-type twist and b*xi for M-type twist
+        let mut b_fp2 = match twist_type {
+            super::TwistType::D => fp2_non_residue.inverse().unwrap(),
+            super::TwistType::M => fp2_non_residue.clone(),
+        };
+        b_fp2.mul_by_fp(&b_fp);
+
+        let a_fp = Fp::zero(&base_field);
+        let a_fp2 = Fp2::zero(&extension_2);
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let twist = WeierstrassCurveTwist::new(&scalar_field, &extension_2, a_fp2, b_fp2);
+
+        // (-1, 1)
+        let mut p_x = Fp::one(&base_field);
+        p_x.negate();
+        let p_y = Fp::one(&base_field);
+
+        let mut q_x = Fp2::zero(&extension_2);
+        q_x.c0 = Fp::from_be_bytes(&base_field, &BigUint::from_str_radix(q[0], 10).unwrap().to_bytes_be(), true).unwrap();
+        q_x.c1 = Fp::from_be_bytes(&base_field, &BigUint::from_str_radix(q[1], 10).unwrap().to_bytes_be(), true).unwrap();
+
+        let mut q_y = Fp2::zero(&extension_2);
+        q_y.c0 = Fp::from_be_bytes(&base_field, &BigUint::from_str_radix(q[2], 10).unwrap().to_bytes_be(), true).unwrap();
+        q_y.c1 = Fp::from_be_bytes(&base_field, &BigUint::from_str_radix(q[3], 10).unwrap().to_bytes_be(), true).unwrap();
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        let mut minus_one_over_2 = Fp::one(&base_field);
+        minus_one_over_2.negate();
+        let mut two = Fp::one(&base_field);
+        two.double();
+        let two_inv = two.inverse().unwrap();
+        minus_one_over_2.mul_assign(&two_inv);
+
+        let non_residue_in_p_minus_one_over_2 = fp2_non_residue.pow(minus_one_over_2.into_repr());
+
+        let engine = super::BnInstance {
+            u: vec![0x4080000000000001],
+            u_is_negative: true,
+            // |6u + 2|
+            six_u_plus_2: vec![0x8300000000000004, 0x1],
+            twist_type,
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &twist,
+            fp2_extension: &extension_2,
+            fp6_extension: &extension_6,
+            fp12_extension: &extension_12,
+            non_residue_in_p_minus_one_over_2,
+            gls: std::sync::OnceLock::new()
+        };
+
+        // psi acts as p on G2, 4-way multiplication agrees with double-and-add
+        crate::pairings::tests::check_g2_endomorphism(&q, |q| engine.psi(q), |q, k| engine.mul_g2(q, k), |points, scalars| engine.multiexp_g2(points, scalars));
+
+        // endomorphism based subgroup checks agree with multiplication by the order
+        assert_eq!(crate::pairings::tests::check_subgroup_membership(&p, &q, |p| engine.is_in_g1(p), |q| engine.is_in_g2(q)), 0);
+
+        let f = engine.final_exponentiation_easy_part(&engine.multi_miller_loop(&[(&p, &q)])).unwrap();
+        check_hard_part(&engine, &f);
+
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert_eq!(engine.pairing_check(&[(p.clone(), q.clone()), (p_neg, q.clone())]), Ok(true));
+        assert_eq!(engine.pairing_check(&[(p.clone(), q.clone())]), Ok(false));
+
         let pairing_result = engine.pair(&[p], &[q]).unwrap();
-        assert!(format!("{}", pairing_result.c0.c0.c0) == "0x12c70e90e12b7874510cd1707e8856f71bf7f61d72631e268fca81000db9a1f5");
+
+        let mut result = vec![];
+        for c in [&pairing_result.c0, &pairing_result.c1].iter() {
+            for c in [&c.c0, &c.c1, &c.c2].iter() {
+                result.push(format!("{}", c.c0));
+                result.push(format!("{}", c.c1));
+            }
+        }
+
+        result
     }
 
-    #[bench]
-    fn bench_bn254_pairing(b: &mut Bencher) {
+    #[test]
+    fn test_bn_negative_u_d_type_pairing() {
+        let result = pair_on_bn_with_negative_u(
+            super::TwistType::D,
+            (1, 1),
+            [
+                "13249056046919098729337009969087894330553351418359494679848331863981826282767",
+                "2170514747905059224750935412952626263321349511512506674621669657973939923922",
+                "9489751532011413714978175111481312509729949981980063352815607392104053528755",
+                "2291409472290965296924210604507858294164648270085791620163623272572895178521"
+            ]
+        );
+
+        let expected = [
+            "0x094db4c57672b186ca1886f23fc51d897142d5c4abc93c8aceced5dea007dc99",
+            "0x11e6e6b8f034cbb285a07d1332b4d5a3a8eaa75a2c0b38b6a30c9fae96bae5b8",
+            "0x085b66ce24e0c73d1bd0251abdaf06a70fe0c60ba62f9508d86dd90e75de01aa",
+            "0x1d5b9ce8aca1d2d1cc749c2aec7794fc52aac6f040d81b7391e1ae0e6490c012",
+            "0x183a51e288f411feba03754e7c39c8a910440587705184ef2d2adae1464a853f",
+            "0x0b0b752ab33dd65d58bf85dd6234e84250956bc3114531167fb76a171a0efb0e",
+            "0x0cdac2f9decb62157d2a8d1ad3d0f34c25dd05a0ef243ef990ea0086b155997e",
+            "0x1e17b43ca4d5ca1147ee07a9d4636a16c99888f5c6f1448339fe5eec73b666ad",
+            "0x116858d66e1b66d6460baebd2577a249bd821d0d7762b9e3a216ac79efad261b",
+            "0x182d4d4fe5467c18623669b71db2a9f74b5ddb134c6fecbd3d4f588a7dc85631",
+            "0x1096be6299260ab04fe6386eab011159aa49ef753a91558b5eb43bbbf8422468",
+            "0x03fa2fe0c18125264268e17b92d8cb89808fb1d9f9e88a14c8f133a87952b57c",
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_bn_negative_u_m_type_pairing() {
+        let result = pair_on_bn_with_negative_u(
+            super::TwistType::M,
+            (4, 2),
+            [
+                "8061414396285275722687589815180201943895119206661258783229178761004796111458",
+                "11411665217231182916115267041747207038646565342355296998109382289208472396521",
+                "11065301548399399726320997151858276735148354042220611880090279661126135897284",
+                "5664132802086772433273220641567360936959366370700067308497479635403483570454"
+            ]
+        );
+
+        let expected = [
+            "0x056939e992e321a1d16db47e01ba28a76e7587b01291d60143f03cd5c02c1f84",
+            "0x1f411f03b2a9db846e8b1c8a1242cf3daafe9cc5d9c28eed60d7951d5af20b2b",
+            "0x17bb42365d109b14334e18f3dc3f093dfcfc13c67312f532d2cc475be68e5d42",
+            "0x08aa0440911088877bf5986863b6fe436e6c368007ebea2d4f90d301360f933b",
+            "0x029471d5a03f8bf2820bed88f78ad1dc32e11a681279e0a5224972c9bffc0802",
+            "0x07fb775360052b5bc5e71e25228d5433dbfdd81995b189649b65d172efde93ad",
+            "0x058a6d4c0e8dc612b2ead33351239b45cdd6f673350e7838d5c92b5e40a1d1ee",
+            "0x162efd4b2caabcdb13cc4ad3c3d8cf6df2b5636ee928fcf1b9ec532012069427",
+            "0x10684b445c359090cbac7116aff49bc92750a373838fae7aafb2f0a31dabc766",
+            "0x1a94e1aae835a94986fb8b3ff4db36f4105b268e4c28be881f1e44966092bff2",
+            "0x1affb75ec3603b8ba0afe93bfad4d24a736a21a720dad674fc1cddec0b1ef9e2",
+            "0x178772744a62694ea6ede82c0343b2bde0dd1b050202b4d12132e6d6ffb5db09",
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    // engine, generators of G1 and G2 of BN254 for tests and benchmarks
+    fn with_bn254_engine<R, F>(f: F) -> R
+        where F: for<'a> FnOnce(&'a super::BnInstance<'a, U256Repr, crate::field::PrimeField<U256Repr>, U256Repr, crate::field::PrimeField<U256Repr>>, &CurvePoint<'a, U256Repr, crate::field::PrimeField<U256Repr>, U256Repr, crate::field::PrimeField<U256Repr>>, &TwistPoint<'a, U256Repr, crate::field::PrimeField<U256Repr>, U256Repr, crate::field::PrimeField<U256Repr>>) -> R
+    {
         let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
         let base_field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
         let scalar_field = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
@@ -808,14 +1590,156 @@ mod tests {
             fp2_extension: &extension_2,
             fp6_extension: &extension_6,
             fp12_extension: &extension_12,
-            non_residue_in_p_minus_one_over_2: non_residue_in_p_minus_one_over_2
+            non_residue_in_p_minus_one_over_2: non_residue_in_p_minus_one_over_2,
+            gls: std::sync::OnceLock::new()
         };
 
-        b.iter(|| {
-            engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+        f(&engine, &p, &q)
+    }
+
+    #[bench]
+    fn bench_bn254_pairing(b: &mut Bencher) {
+        with_bn254_engine(|engine, p, q| {
+            b.iter(|| {
+                engine.pair(std::slice::from_ref(p), std::slice::from_ref(q)).unwrap();
+            });
+        });
+    }
+
+    // a scalar of full length, for which the 4-way split is not trivial
+    fn g2_bench_scalar() -> Vec<u64> {
+        vec![0x92a3b4c5d6e7f809, 0x1a2b3c4d5e6f7081, 0x92a3b4c5d6e7f809, 0x1a2b3c4d5e6f7081]
+    }
+
+    #[bench]
+    fn bench_bn254_g2_mul(b: &mut Bencher) {
+        let scalar = g2_bench_scalar();
+        with_bn254_engine(|_, _, q| {
+            b.iter(|| q.mul(&scalar));
         });
     }
 
+    #[bench]
+    fn bench_bn254_g2_mul_gls(b: &mut Bencher) {
+        let scalar = g2_bench_scalar();
+        with_bn254_engine(|engine, _, q| {
+            b.iter(|| engine.mul_g2(q, &scalar));
+        });
+    }
+
+    #[bench]
+    fn bench_bn254_g2_multiexp_gls(b: &mut Bencher) {
+        let scalars = vec![g2_bench_scalar(); 4];
+        with_bn254_engine(|engine, _, q| {
+            let points: Vec<_> = (0..4).map(|i| q.mul([i as u64 + 1])).collect();
+            b.iter(|| engine.multiexp_g2(&points, &scalars).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_cyclotomic_exp() {
+        let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let base_field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+        let mut fp_non_residue = Fp::one(&base_field);
+        fp_non_residue.negate(); // non-residue is -1
+
+        let mut extension_2 = Extension2 {
+            field: &base_field,
+            non_residue: fp_non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp2(&extension_2).unwrap();
+        extension_2.frobenius_coeffs_c1 = coeffs;
+
+        let one = Fp::one(&base_field);
+        let one_fp2 = Fp2::one(&extension_2);
+
+        // non-residue is u+9
+        let mut fp2_non_residue = Fp2::zero(&extension_2);
+        let fp_9_repr = U256Repr::from(9u64);
+        let fp_9 = Fp::from_repr(&base_field, fp_9_repr).unwrap(); 
+        fp2_non_residue.c0 = fp_9.clone();
+        fp2_non_residue.c1 = one.clone();
+
+        let f_c1 = [Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2)];
+
+        let mut extension_6 = Extension3Over2 {
+            non_residue: fp2_non_residue.clone(),
+            field: &extension_2,
+            frobenius_coeffs_c1: f_c1.clone(),
+            frobenius_coeffs_c2: f_c1,
+        };
+
+        let (coeffs_c1, coeffs_c2) = frobenius_calculator_fp6_as_3_over_2(modulus.clone(), &extension_6).unwrap();
+
+        extension_6.frobenius_coeffs_c1 = coeffs_c1;
+        extension_6.frobenius_coeffs_c2 = coeffs_c2;
+
+        let f_c1 = [Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2)];
+
+        let mut extension_12 = Extension2Over3Over2 {
+            non_residue: Fp6::zero(&extension_6),
+            field: &extension_6,
+            frobenius_coeffs_c1: f_c1,
+        };
+
+        let coeffs = frobenius_calculator_fp12(modulus, &extension_12).unwrap();
+        extension_12.frobenius_coeffs_c1 = coeffs;
+
+        let mut fp12 = Fp12::zero(&extension_12);
+        fp12.c0.c0 = fp2_non_residue.clone();
+        fp12.c0.c1 = fp2_non_residue.clone();
+        fp12.c0.c2 = fp2_non_residue.clone();
+        fp12.c1.c0 = fp2_non_residue.clone();
+        fp12.c1.c1 = fp2_non_residue.clone();
+        fp12.c1.c2 = one_fp2.clone();
+
+        // f^((p^6 - 1)(p^2 + 1)) is in cyclotomic subgroup
+        let mut f = fp12.clone();
+        f.conjugate();
+        f.mul_assign(&fp12.inverse().unwrap());
+        let mut g = f.clone();
+        g.frobenius_map(2);
+        g.mul_assign(&f);
+
+        let mut compressed = g.clone();
+        let mut expected = g.clone();
+        for _ in 0..5 {
+            compressed.cyclotomic_square_compressed();
+            expected.cyclotomic_square();
+        }
+        assert!(compressed.cyclotomic_decompress().unwrap() == expected);
+
+        // batch decompression matches decompressing one by one
+        let mut powers = vec![];
+        let mut power = g.clone();
+        for _ in 0..4 {
+            power.cyclotomic_square_compressed();
+            powers.push(power.clone());
+        }
+        let batch = Fp12::cyclotomic_decompress_batch(&powers).unwrap();
+        assert_eq!(batch.len(), powers.len());
+        for (b, p) in batch.iter().zip(powers.iter()) {
+            assert!(*b == p.cyclotomic_decompress().unwrap());
+        }
+        assert!(Fp12::cyclotomic_decompress_batch(&powers[..0]).unwrap().is_empty());
+
+        // sparse exponents use compressed squarings, dense ones don't
+        for exp in [vec![0xd201000000010000], vec![0x8300000000000004, 0x1], vec![4965661367192848881]].iter() {
+            assert!(g.cyclotomic_exp(exp) == g.pow(exp));
+        }
+
+        // zero exponent has no digits at all
+        assert!(g.cyclotomic_exp([0u64]) == Fp12::one(&extension_12));
+        assert!(g.cyclotomic_exp(Vec::<u64>::new()) == Fp12::one(&extension_12));
+        assert!(g.cyclotomic_exp([1u64]) == g);
+    }
+
     #[test]
     fn test_final_exponentiation() {
         let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
@@ -943,7 +1867,8 @@ mod tests {
             fp2_extension: &extension_2,
             fp6_extension: &extension_6,
             fp12_extension: &extension_12,
-            non_residue_in_p_minus_one_over_2: non_residue_in_p_minus_one_over_2
+            non_residue_in_p_minus_one_over_2: non_residue_in_p_minus_one_over_2,
+            gls: std::sync::OnceLock::new()
         };
 
         let mut fp12 = Fp12::zero(&extension_12);
@@ -956,6 +1881,9 @@ mod tests {
 
         let final_exp = engine.final_exponentiation(&fp12).unwrap();
 
+        let f = engine.final_exponentiation_easy_part(&fp12).unwrap();
+        check_hard_part(&engine, &f);
+
         use crate::field::biguint_to_u64_vec;
         let one = BigUint::from(1u64);
         let mut power = BigUint::from(1u64);
@@ -964,7 +1892,11 @@ mod tests {
         }
         power = power - one;
         power = power / r;
-        let naive = fp12.pow(&biguint_to_u64_vec(power.clone()));
+        let (m, m_is_negative) = hard_part_multiplier(&engine);
+        let mut naive = fp12.pow(&biguint_to_u64_vec(power.clone() * m));
+        if m_is_negative {
+            naive.conjugate();
+        }
 
         assert!(naive == final_exp);
     }
@@ -0,0 +1,319 @@
+use crate::field::SizedPrimeField;
+use crate::representation::ElementRepr;
+use crate::traits::FieldElement;
+use crate::weierstrass::Group;
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::cubic_twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+use crate::pairings::{PairingEngine, into_ternary_wnaf, cyclotomic_multi_exp};
+use crate::pairings::cp::{CPExtension, ate_miller_loop};
+
+// Optimal ate pairing for the BW6 curves (Brezing-Weng with k = 6 over the base
+// field of BLS12 curve), see "Optimized and secure pairing-friendly elliptic curves
+// suitable for one layer proof composition" by El Housni and Guillevic. Second argument
+// is taken from the M-type twist (cubic twist from the Fp6 point of view), so the line
+// evaluation is shared with the CP6 engine
+
+pub struct BW6Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    pub x: Vec<u64>,
+    pub x_is_negative: bool,
+    // x + 1
+    pub ate_loop_count_1: Vec<u64>,
+    pub ate_loop_count_1_is_negative: bool,
+    // x^3 - x^2 - x
+    pub ate_loop_count_2: Vec<u64>,
+    pub ate_loop_count_2_is_negative: bool,
+    // coefficients of R0(x) and R1(x) (lowest degree first), such that
+    // R0(x) + q * R1(x) is a multiple of (q^2 - q + 1)/r coprime with r
+    pub hard_part_r0: Vec<i64>,
+    pub hard_part_r1: Vec<i64>,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+    pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
+    pub fp6_extension: &'a Extension2Over3<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> BW6Instance<'a, FE, F, GE, G>
+    where G: 'a
+{
+    fn miller_loop<'b, I>(&self, i: I) -> Fp6<'a, FE, F>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>,
+                &'b TwistPoint<'a, FE, F, GE, G>)
+        >
+    {
+        let mut f = Fp6::one(self.fp6_extension);
+        for (p, q) in i.into_iter() {
+            if p.is_zero() || q.is_zero() {
+                continue;
+            }
+            f.mul_assign(&self.ate_pairing_loop(p, q));
+        }
+
+        f
+    }
+
+    fn ate_pairing_loop(
+        &self,
+        point: &CurvePoint<'a, FE, F, GE, G>,
+        twist_point: &TwistPoint<'a, FE, F, GE, G>
+    ) -> Fp6<'a, FE, F> {
+        // f_{x+1,Q}(P) * f_{x^3-x^2-x,Q}(P)^q, the line through [x+1]Q and
+        // q-power Frobenius of [x^3-x^2-x]Q is vertical and is eliminated
+        // by the final exponentiation
+        let mut f_1 = self.miller_function(point, twist_point, &self.ate_loop_count_1);
+        if self.ate_loop_count_1_is_negative {
+            f_1.conjugate();
+        }
+
+        let mut f_2 = self.miller_function(point, twist_point, &self.ate_loop_count_2);
+        if self.ate_loop_count_2_is_negative {
+            f_2.conjugate();
+        }
+        f_2.frobenius_map(1);
+
+        f_1.mul_assign(&f_2);
+
+        f_1
+    }
+
+    fn miller_function(
+        &self,
+        point: &CurvePoint<'a, FE, F, GE, G>,
+        twist_point: &TwistPoint<'a, FE, F, GE, G>,
+        loop_count: &[u64]
+    ) -> Fp6<'a, FE, F> {
+        let digits = into_ternary_wnaf(loop_count).into_iter().rev().skip(1);
+
+        ate_miller_loop(self.fp6_extension, self.curve_twist, point, twist_point, digits)
+    }
+
+    fn exp_by_x(&self, f: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
+        let mut result = f.cyclotomic_exp(&self.x);
+        if self.x_is_negative {
+            result.conjugate();
+        }
+
+        result
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for BW6Instance<'a, FE, F, GE, G>
+    where G: 'a
+{
+    type PairingResult = Fp6<'a, FE, F>;
+    type G1 = CurvePoint<'a, FE, F, GE, G>;
+    type G2 = TwistPoint<'a, FE, F, GE, G>;
+
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &Fp6<'a, FE, F>) -> Option<Fp6<'a, FE, F>> {
+        let f_inv = f.inverse()?;
+
+        Some(<Extension2Over3<'a, FE, F> as CPExtension<'a, FE, F, GE, G>>::final_exponentiation_easy_part(f, &f_inv))
+    }
+
+    fn final_exponentiation_hard_part(&self, elt: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
+        // elt^(R0(x) + q * R1(x)) as a multi-exponentiation of elt^(x^i) and
+        // elt^(q * x^i) by the small coefficients with shared squarings
+        let degree = std::cmp::max(self.hard_part_r0.len(), self.hard_part_r1.len());
+
+        let mut powers = Vec::with_capacity(degree);
+        let mut power = elt.clone();
+        for i in 0..degree {
+            if i != 0 {
+                power = self.exp_by_x(&power);
+            }
+            powers.push(power.clone());
+        }
+
+        cyclotomic_multi_exp(
+            Fp6::one(self.fp6_extension),
+            &powers,
+            &[&self.hard_part_r0, &self.hard_part_r1]
+        )
+    }
+
+    fn target_one(&self) -> Fp6<'a, FE, F> {
+        Fp6::one(self.fp6_extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, BigInt, Sign};
+    use crate::field::{U384Repr, U768Repr, new_field, biguint_to_u64_vec};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement};
+    use crate::extension_towers::fp3::{Fp3, Extension3};
+    use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+    use num_traits::Num;
+    use crate::pairings::{frobenius_calculator_fp3, frobenius_calculator_fp6_as_2_over_3};
+    use crate::weierstrass::{Group};
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::cubic_twist::{TwistPoint, WeierstrassCurveTwist};
+    use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+    use num_integer::Integer;
+    use num_traits::Zero;
+
+    #[test]
+    fn test_bw6_761_pairing() {
+        let modulus = BigUint::from_str_radix("6891450384315732539396789682275657542479668912536150109513790160209623422243491736087683183289411687640864567753786613451161759120554247759349511699125301598951605099378508850372543631423596795951899700429969112842764913119068299", 10).unwrap();
+        let base_field = new_field::<U768Repr>("6891450384315732539396789682275657542479668912536150109513790160209623422243491736087683183289411687640864567753786613451161759120554247759349511699125301598951605099378508850372543631423596795951899700429969112842764913119068299", 10).unwrap();
+        let scalar_field = new_field::<U384Repr>("258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177", 10).unwrap();
+
+        // Fp3 is built with u^3 = -4
+        let mut fp_non_residue = Fp::from_repr(&base_field, U768Repr::from(4)).unwrap();
+        fp_non_residue.negate();
+
+        let mut extension_3 = Extension3 {
+            field: &base_field,
+            non_residue: fp_non_residue.clone(),
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)],
+            frobenius_coeffs_c2: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let (coeffs_1, coeffs_2) = frobenius_calculator_fp3(modulus.clone(), &extension_3).unwrap();
+        extension_3.frobenius_coeffs_c1 = coeffs_1;
+        extension_3.frobenius_coeffs_c2 = coeffs_2;
+
+        let one = Fp::one(&base_field);
+
+        // Fp6 is built with w^2 = u, the non-residue below is w^6 = u^3 = -4 and
+        // only goes into the Frobenius coefficients
+        let mut fp3_non_residue = Fp3::zero(&extension_3); // non-residue is -4 + 0*u + 0*u^2
+        fp3_non_residue.c0 = fp_non_residue;
+
+        let f_c1 = [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field),
+                    Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)];
+
+        let mut extension_6 = Extension2Over3 {
+            non_residue: fp3_non_residue,
+            field: &extension_3,
+            frobenius_coeffs_c1: f_c1
+        };
+
+        let [c0, c1, c2, c3, c4, c5] = frobenius_calculator_fp6_as_2_over_3(modulus.clone(), &extension_6).unwrap();
+        extension_6.frobenius_coeffs_c1 = [c0.c0, c1.c0, c2.c0, c3.c0, c4.c0, c5.c0];
+
+        // y^2 = x^3 - 1, M-type twist is y^2 = x^3 + b * u^3 = x^3 + 4
+        let a_fp = Fp::zero(&base_field);
+        let mut b_fp = one.clone();
+        b_fp.negate();
+
+        let mut twist = Fp3::zero(&extension_3);
+        twist.c1 = one.clone();
+
+        let mut twist_cubed = twist.clone();
+        twist_cubed.square();
+        twist_cubed.mul_assign(&twist);
+
+        let a_fp3 = Fp3::zero(&extension_3);
+        let mut b_fp3 = twist_cubed;
+        b_fp3.mul_by_fp(&b_fp);
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
+
+        let p_x = BigUint::from_str_radix("1600128751543602403684406568580076675745382532045319819726118252041972146648236774970846325923899572003951656372132989569744926767841151952720097474109542584152656229372717405651911350992965310534590868926069670189513554531721858", 10).unwrap().to_bytes_be();
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = BigUint::from_str_radix("1505274538357979907440623538925905536251951127657088145304630575453088367132658442538440868072437273552073155248213436854004467933096031099034715894818325041637009647359824500801988340328086119069747759418002313950312424983076870", 10).unwrap().to_bytes_be();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        // G2 is defined over Fp, so only c0 of the Fp3 coordinates is non-zero
+        let q_x = BigUint::from_str_radix("2092612359985575486241886036602304803582314879986775861837902399301074391850615246766009808568694043670737368354490965891893871511860563926332494715341180517171642868251573516350679677644797300484582807176654795038794183909444101", 10).unwrap().to_bytes_be();
+        let mut q_x_fp3 = Fp3::zero(&extension_3);
+        q_x_fp3.c0 = Fp::from_be_bytes(&base_field, &q_x, true).unwrap();
+        let q_y = BigUint::from_str_radix("4959716134957376305567736398907440149726601119051060603495936780653599241847817467104227192054315348912065630035283666943989438202973941517658804806171824862905610235353004215608954011463227688915817742699777330498641494605019463", 10).unwrap().to_bytes_be();
+        let mut q_y_fp3 = Fp3::zero(&extension_3);
+        q_y_fp3.c0 = Fp::from_be_bytes(&base_field, &q_y, true).unwrap();
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&curve_twist, q_x_fp3, q_y_fp3);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        // x is the BLS12-377 parameter
+        let engine = super::BW6Instance {
+            x: vec![0x8508c00000000001],
+            x_is_negative: false,
+            ate_loop_count_1: vec![0x8508c00000000002],
+            ate_loop_count_1_is_negative: false,
+            ate_loop_count_2: vec![0xffffffffffffffff, 0x8a442f991fffffff, 0x23ed1347970dec00],
+            ate_loop_count_2_is_negative: false,
+            hard_part_r0: vec![-220, -263, -73, -314, -197, 269, 70, -103],
+            hard_part_r1: vec![229, 34, -181, 452, -65, -445, 492, 77, -276, 103],
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &curve_twist,
+            fp6_extension: &extension_6,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        assert!(format!("{}", pairing_result.c0.c0) == "0x008d421e1aef97bcc34d668622d099b7adad8b4c341ca1935635d205283dcd5e03766602861365839ab11ae95325649d715c782d0829807de7e97020fe29ba9e138e2176a4089832b88f6a8eb823522e5abec220e2209e85d082062cd425ff82");
+        assert!(format!("{}", pairing_result.c0.c1) == "0x00e1353299cdef16ea63bf405b792dae2b0f216d0dd9110bd984c52d4f93aeb6329aea8a96a70a357d5e5788ce8d2723a5382cda4b5e425413ed2403bdcb58fe83453287b2f4c53636559a399332f591a1fd3bbb5e74bd41db3609e88d39f023");
+        assert!(format!("{}", pairing_result.c0.c2) == "0x007c2c760c89996c00e7309ade48311a2652f7841d58c82668ccff3a5622414c25a698e7d86b25b4a090f557fd742ff8ec39534166b776af1c33b74ba8e9c258005d25f955f27f2d756541e7c6a6aba0426d9be1dcd07c94f8ef74537870a434");
+        assert!(format!("{}", pairing_result.c1.c0) == "0x00c82afa36506bb5f1b748e9b513805539af5a6f875e71cd190f1c19ebda301082eb5b1bcb6dccffa176e9f2de24eff8323b711e6a32d49d24bff3742f3a1b3d32b076f94640f568c76cd4472c04dcc26fc96d120631a4f9d37ca4b4f02e2ab8");
+        assert!(format!("{}", pairing_result.c1.c1) == "0x00f7d3e2465fa6b3a053f7271bbab3c12d30aad788ecd05ababc69c4e0885074d4b33866f55c3827d7ac00abc2feb377dcedaea332481318111449452ecc736c32f981bbe1149fa4b9eed6a10addcabf19b0c46772fc69f82e50b5012f22f798");
+        assert!(format!("{}", pairing_result.c1.c2) == "0x0083ee4353c86c3390b7c5b9917d09a8b02ab7445a2334f8c45498483d29c201cc3a01795ebb5c7a40b2ea21f069a10b46b102b60e15cd9dcfcc9b424b08092b5dc1112f8f916a8722a3ae69f69870407da04ec3101e343ab6f25fe01b1e66eb");
+
+        // optimal ate over x + 1 + q * (x^3 - x^2 - x) agrees with the reference Tate pairing
+        // evaluated with untwisted Q = (x' / u, y' * w / u^2) as the Miller point, hard part
+        // of the final exponentiation raises it to (R0(x) + q * R1(x)) * r/(q^2 - q + 1)
+        let group_order = BigUint::from_str_radix("258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177", 10).unwrap();
+        let reference = ReferencePairing::new(Fp6::zero(&extension_6), Fp6::one(&extension_6), modulus.clone(), group_order.clone(), 6).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp6::zero(&extension_6), Fp6::zero(&extension_6));
+        p_embedded.0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0 = p_y_affine;
+
+        let twist_inv = twist.inverse().unwrap();
+        let mut twist_squared_inv = twist_inv.clone();
+        twist_squared_inv.square();
+
+        let (mut q_x_affine, mut q_y_affine) = q.into_xy();
+        q_x_affine.mul_assign(&twist_inv);
+        q_y_affine.mul_assign(&twist_squared_inv);
+        let mut q_untwisted = (Fp6::zero(&extension_6), Fp6::zero(&extension_6));
+        q_untwisted.0.c0 = q_x_affine;
+        q_untwisted.1.c1 = q_y_affine;
+
+        let x = BigInt::from(0x8508c00000000001u64);
+        let modulus = BigInt::from_biguint(Sign::Plus, modulus);
+        let group_order = BigInt::from_biguint(Sign::Plus, group_order);
+        let exponent = reference.ate_exponent(&[&x + 1, &x * &x * &x - &x * &x - &x]).unwrap();
+
+        let evaluate = |coeffs: &[i64]| coeffs.iter().rev().fold(BigInt::zero(), |acc, c| acc * &x + c);
+        let hard_part = evaluate(&engine.hard_part_r0) + &modulus * evaluate(&engine.hard_part_r1);
+        let (multiplier, rem) = hard_part.div_rem(&((&modulus * &modulus - &modulus + 1) / &group_order));
+        assert!(rem.is_zero());
+        let exponent = (BigInt::from_biguint(Sign::Plus, exponent) * multiplier).mod_floor(&group_order);
+
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent.to_biguint().unwrap())) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p.clone(), p_neg], &[q.clone(), q.clone()]).unwrap() == Fp6::one(&extension_6));
+    }
+}
@@ -1,38 +1,83 @@
 use crate::field::SizedPrimeField;
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator, MsbBitIterator};
+use crate::traits::{FieldElement, MsbBitIterator};
 use crate::weierstrass::Group;
 use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
-use crate::weierstrass::cubic_twist::{WeierstrassCurveTwist, TwistPoint};
-use crate::extension_towers::fp3::{Fp3, Extension3};
+use crate::weierstrass::{twist, cubic_twist};
+use crate::extension_towers::fp2::Fp2;
+use crate::extension_towers::fp3::Fp3;
+use crate::extension_towers::fp4_as_2_over_2::Fp4;
 use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+use crate::extension_towers::fp8_as_2_over_4::{Fp8, Extension2Over4};
+use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
 use crate::pairings::PairingEngine;
 
-pub struct CPInstance6<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+// Ate pairing for the curves generated by the Cocks-Pinch method. Miller loop is
+// done in affine coordinates over the twist, so the only thing that depends on the
+// embedding degree is how the line is placed into the full extension and the easy
+// part of the final exponentiation. Hard part is given as Phi_k(q)/r = sum(w_i * q^i)
+
+/// Extension tower of the embedding degree k together with the twist of the 
+/// curve that is used for the second argument of the pairing
+pub trait CPExtension<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    type TwistField: FieldElement;
+    type TwistCurve: 'a;
+    type TwistPoint: Group;
+    type Target: FieldElement;
+
+    fn target_one(&'a self) -> Self::Target;
+
+    fn twist_curve_a(curve: &Self::TwistCurve) -> &Self::TwistField;
+
+    fn twist_point_xy(point: &Self::TwistPoint) -> (Self::TwistField, Self::TwistField);
+
+    /// Multiplies `f` by the line with a slope `gamma` going through the point (`x`, `y`) 
+    /// of the twist and evaluated at the point (`px`, `py`) of the curve
+    fn mul_by_line(
+        f: &mut Self::Target,
+        gamma: &Self::TwistField,
+        x: &Self::TwistField,
+        y: &Self::TwistField,
+        px: &Fp<'a, FE, F>,
+        py: &Fp<'a, FE, F>
+    );
+
+    /// Raises to the power (q^k - 1)/Phi_k(q)
+    fn final_exponentiation_easy_part(f: &Self::Target, f_inv: &Self::Target) -> Self::Target;
+
+    fn cyclotomic_exp(f: &Self::Target, exp: &[u64]) -> Self::Target;
+}
+
+pub struct CPInstance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>, T: CPExtension<'a, FE, F, GE, G>> {
     pub x: Vec<u64>,
     pub x_is_negative: bool,
-    pub exp_w0: Vec<u64>,
-    pub exp_w1: Vec<u64>,
-    pub exp_w0_is_negative: bool,
+    // (w_i, w_i is negative)
+    pub exp_w: Vec<(Vec<u64>, bool)>,
     pub base_field: &'a F,
     pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
-    pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
-    pub twist: Fp3<'a, FE, F>,
-    fp3_extension: &'a Extension3<'a, FE, F>,
-    fp6_extension: &'a Extension2Over3<'a, FE, F>,
+    pub curve_twist: &'a T::TwistCurve,
+    extension: &'a T,
 }
 
-impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> CPInstance6<'a, FE, F, GE, G> {
-    fn miller_loop<'b, I>(&self, i: I) -> Fp6<'a, FE, F>
+pub type CPInstance6<'a, FE, F, GE, G> = CPInstance<'a, FE, F, GE, G, Extension2Over3<'a, FE, F>>;
+pub type CPInstance8<'a, FE, F, GE, G> = CPInstance<'a, FE, F, GE, G, Extension2Over4<'a, FE, F>>;
+pub type CPInstance12<'a, FE, F, GE, G> = CPInstance<'a, FE, F, GE, G, Extension2Over3Over2<'a, FE, F>>;
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>, T: CPExtension<'a, FE, F, GE, G>> CPInstance<'a, FE, F, GE, G, T> {
+    fn miller_loop<'b, I>(&self, i: I) -> T::Target
     where 'a: 'b,
+        T::TwistPoint: 'b,
         I: IntoIterator<
             Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
-                &'b TwistPoint<'a, FE, F, GE, G>)
+                &'b T::TwistPoint)
         >
     {
-        let mut f = Fp6::one(self.fp6_extension);
+        let mut f = self.extension.target_one();
         for (p, q) in i.into_iter() {
+            if p.is_zero() || q.is_zero() {
+                continue;
+            }
             f.mul_assign(&self.ate_pairing_loop(p, q));
         }
 
@@ -42,161 +87,177 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     fn ate_pairing_loop(
         &self, 
         point: &CurvePoint<'a, FE, F, GE, G>, 
-        twist_point: &TwistPoint<'a, FE, F, GE, G> 
-    ) -> Fp6<'a, FE, F> {
-        debug_assert!(point.is_normalized());
-        debug_assert!(twist_point.is_normalized());
+        twist_point: &T::TwistPoint 
+    ) -> T::Target {
+        // The for loop is executed for all bits (EXCEPT the MSB itself) of
+        // the loop parameter (skipping leading zeros) in MSB to LSB order
+        let digits = MsbBitIterator::new(&self.x).skip(1).map(|bit| bit as i64);
+        let mut f = ate_miller_loop(self.extension, self.curve_twist, point, twist_point, digits);
 
-        let px = point.x.clone();
-        let py = point.y.clone();
-        let qx = twist_point.x.clone();
-        let qy = twist_point.y.clone();
-        let mut py_twist_squared = self.twist.clone();
-        py_twist_squared.square();
-        py_twist_squared.mul_by_fp(&py);
+        // f_{-x} = 1/(f_x * v_x) and vertical line is eliminated by the final exponentiation
+        if self.x_is_negative {
+            f.conjugate();
+        }
 
-        let mut old_rx;
-        let mut old_ry;
-        let mut rx = qx.clone();
-        let mut ry = qy.clone();
+        f
+    }
+}
 
-        let mut f = Fp6::one(self.fp6_extension);
-
-        // The for loop is executed for all bits (EXCEPT the MSB itself) of
-        // sw6_param_p (skipping leading zeros) in MSB to LSB order
-        // let mut found_one = false;
-        for bit in MsbBitIterator::new(&self.x).skip(1) {
-        // for bit in BitIterator::new(&self.x) {
-        //     if !found_one && bit {
-        //         found_one = true;
-        //         continue;
-        //     } else if !found_one {
-        //         continue;
-        //     }
-
-            old_rx = rx;
-            old_ry = ry;
-
-            let mut old_rx_square = old_rx.clone();
-            old_rx_square.square();
-            let mut old_rx_square_3 = old_rx_square.clone();
-            old_rx_square_3.double();
-            old_rx_square_3.add_assign(&old_rx_square);
-            let mut old_rx_square_3_a = old_rx_square_3.clone();
-            old_rx_square_3_a.add_assign(&self.curve_twist.a);
-            let mut old_ry_double_inverse = old_ry.clone();
-            old_ry_double_inverse.double();
-            let old_ry_double_inverse = old_ry_double_inverse.inverse().unwrap();
-
-            let mut gamma = old_rx_square_3_a.clone();
-            gamma.mul_assign(&old_ry_double_inverse);
-
-            let mut gamma_twist = gamma.clone();
-            gamma_twist.mul_assign(&self.twist);
-
-            let mut gamma_old_rx = gamma.clone();
-            gamma_old_rx.mul_assign(&old_rx);
-
-            let mut gamma_twist_px = gamma_twist.clone();
-            gamma_twist_px.mul_by_fp(&px);
-
-            let x = py_twist_squared.clone();
-
-            let mut y = gamma_old_rx.clone();
-            y.sub_assign(&old_ry);
-            y.sub_assign(&gamma_twist_px);
-
-            let ell_rr_at_p = Fp6 {
-                c0: x,
-                c1: y,
-                extension_field: self.fp6_extension
-            };
+/// Computes the Miller function f_{n, Q}(P) in affine coordinates over the twist, where 
+/// `digits` are signed digits of n in MSB to LSB order with the leading one skipped
+pub(crate) fn ate_miller_loop<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>, T: CPExtension<'a, FE, F, GE, G>, I: IntoIterator<Item = i64>>(
+    extension: &'a T,
+    curve_twist: &T::TwistCurve,
+    point: &CurvePoint<'a, FE, F, GE, G>, 
+    twist_point: &T::TwistPoint,
+    digits: I
+) -> T::Target {
+    debug_assert!(point.is_normalized());
+
+    let px = point.x.clone();
+    let py = point.y.clone();
+    let (qx, qy) = T::twist_point_xy(twist_point);
+    let twist_a = T::twist_curve_a(curve_twist);
+    let mut qy_negated = qy.clone();
+    qy_negated.negate();
+
+    let mut old_rx;
+    let mut old_ry;
+    let mut rx = qx.clone();
+    let mut ry = qy.clone();
+
+    let mut f = extension.target_one();
+
+    for digit in digits {
+        old_rx = rx;
+        old_ry = ry;
+
+        let mut old_rx_square = old_rx.clone();
+        old_rx_square.square();
+        let mut old_rx_square_3 = old_rx_square.clone();
+        old_rx_square_3.double();
+        old_rx_square_3.add_assign(&old_rx_square);
+        let mut old_rx_square_3_a = old_rx_square_3.clone();
+        old_rx_square_3_a.add_assign(twist_a);
+        let mut old_ry_double_inverse = old_ry.clone();
+        old_ry_double_inverse.double();
+        let old_ry_double_inverse = old_ry_double_inverse.inverse().unwrap();
+
+        let mut gamma = old_rx_square_3_a.clone();
+        gamma.mul_assign(&old_ry_double_inverse);
+
+        rx = gamma.clone();
+        rx.square();
+        let mut t0 = old_rx.clone();
+        t0.double();
+        rx.sub_assign(&t0);
+
+        let mut t0 = old_rx.clone();
+        t0.sub_assign(&rx);
+
+        ry = gamma.clone();
+        ry.mul_assign(&t0);
+        ry.sub_assign(&old_ry);
+
+        f.square();
+        T::mul_by_line(&mut f, &gamma, &old_rx, &old_ry, &px, &py);
+
+        if digit != 0 {
+            // negative digit adds -Q = (qx, -qy)
+            let qy = if digit > 0 { &qy } else { &qy_negated };
+
+            old_rx = rx.clone();
+            old_ry = ry.clone();
+
+            let mut t0 = old_ry.clone();
+            t0.sub_assign(qy);
+
+            let mut t1 = old_rx.clone();
+            t1.sub_assign(&qx);
+            let t1 = t1.inverse().unwrap();
+
+            let mut gamma = t0;
+            gamma.mul_assign(&t1);
 
             rx = gamma.clone();
             rx.square();
-            let mut t0 = old_rx.clone();
-            t0.double();
-            rx.sub_assign(&t0);
-
-            let mut t0 = old_rx.clone();
-            t0.sub_assign(&rx);
+            rx.sub_assign(&old_rx);
+            rx.sub_assign(&qx);
 
-            ry = gamma.clone();
-            ry.mul_assign(&t0);
+            ry = old_rx.clone();
+            ry.sub_assign(&rx);
+            ry.mul_assign(&gamma);
             ry.sub_assign(&old_ry);
 
-            f.square();
-            f.mul_assign(&ell_rr_at_p);
-
-            if bit {
-                old_rx = rx.clone();
-                old_ry = ry.clone();
-
-                let mut t0 = old_ry.clone();
-                t0.sub_assign(&qy);
-
-                let mut t1 = old_rx.clone();
-                t1.sub_assign(&qx);
-                let t1 = t1.inverse().unwrap();
-
-                let mut gamma = t0;
-                gamma.mul_assign(&t1);
-                let mut gamma_twist = gamma.clone();
-                gamma_twist.mul_assign(&self.twist);
-                let mut gamma_qx = gamma.clone();
-                gamma_qx.mul_assign(&qx);
-                let mut gamma_twist_px = gamma_twist.clone();
-                gamma_twist_px.mul_by_fp(&px);
-
-                let x = py_twist_squared.clone();
-                let mut y = gamma_qx.clone();
-                y.sub_assign(&qy);
-                y.sub_assign(&gamma_twist_px);
-
-                let ell_rq_at_p = Fp6 {
-                    c0: x,
-                    c1: y,
-                    extension_field: self.fp6_extension
-                };
-
-                rx = gamma.clone();
-                rx.square();
-                rx.sub_assign(&old_rx);
-                rx.sub_assign(&qx);
-
-                ry = old_rx.clone();
-                ry.sub_assign(&rx);
-                ry.mul_assign(&gamma);
-                ry.sub_assign(&old_ry);
-
-                f.mul_assign(&ell_rq_at_p);
-            }
+            T::mul_by_line(&mut f, &gamma, &qx, qy, &px, &py);
         }
+    }
 
-        f
+    f
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> CPExtension<'a, FE, F, GE, G> for Extension2Over3<'a, FE, F>
+    where G: 'a
+{
+    type TwistField = Fp3<'a, FE, F>;
+    type TwistCurve = cubic_twist::WeierstrassCurveTwist<'a, FE, F, GE, G>;
+    type TwistPoint = cubic_twist::TwistPoint<'a, FE, F, GE, G>;
+    type Target = Fp6<'a, FE, F>;
+
+    fn target_one(&'a self) -> Self::Target {
+        Fp6::one(self)
     }
 
-    fn final_exponentiation(&self, f: &Fp6<'a, FE, F>) -> Option<Fp6<'a, FE, F>> {
-        let value_inv = f.inverse();
-        if value_inv.is_none() {
-            return None;
-        }
-        let value_inv = value_inv.unwrap();
-        let value_to_first_chunk = self.final_exponentiation_part_one(f, &value_inv);
-        let value_inv_to_first_chunk = self.final_exponentiation_part_one(&value_inv, f);
-        
-        Some(self.final_exponentiation_part_two(&value_to_first_chunk, &value_inv_to_first_chunk))
+    fn twist_curve_a(curve: &Self::TwistCurve) -> &Self::TwistField {
+        &curve.a
     }
 
-    fn final_exponentiation_part_one(&self, elt: &Fp6<'a, FE, F>, elt_inv: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
+    fn twist_point_xy(point: &Self::TwistPoint) -> (Self::TwistField, Self::TwistField) {
+        debug_assert!(point.is_normalized());
+
+        (point.x.clone(), point.y.clone())
+    }
+
+    fn mul_by_line(
+        f: &mut Self::Target,
+        gamma: &Self::TwistField,
+        x: &Self::TwistField,
+        y: &Self::TwistField,
+        px: &Fp<'a, FE, F>,
+        py: &Fp<'a, FE, F>
+    ) {
+        // twist is u from Fp3 (w^2 = u), so the line is 
+        // py * u^2 + (gamma * x - y - gamma * u * px) * w
+        let mut py_twist_squared = Fp3::zero(f.extension_field.field);
+        py_twist_squared.c2 = py.clone();
+
+        let mut gamma_twist_px = gamma.clone();
+        gamma_twist_px.mul_by_nonresidue(f.extension_field);
+        gamma_twist_px.mul_by_fp(px);
+
+        let mut c1 = gamma.clone();
+        c1.mul_assign(x);
+        c1.sub_assign(y);
+        c1.sub_assign(&gamma_twist_px);
+
+        let ell = Fp6 {
+            c0: py_twist_squared,
+            c1,
+            extension_field: f.extension_field
+        };
+
+        f.mul_assign(&ell);
+    }
+
+    fn final_exponentiation_easy_part(f: &Self::Target, f_inv: &Self::Target) -> Self::Target {
         // (q^3-1)*(q+1)
 
         // elt_q3 = elt^(q^3)
-        let mut elt_q3 = elt.clone();
+        let mut elt_q3 = f.clone();
         elt_q3.frobenius_map(3);
         // elt_q3_over_elt = elt^(q^3-1)
         let mut elt_q3_over_elt = elt_q3;
-        elt_q3_over_elt.mul_assign(&elt_inv);
+        elt_q3_over_elt.mul_assign(f_inv);
         // alpha = elt^((q^3-1) * q)
         let mut alpha = elt_q3_over_elt.clone();
         alpha.frobenius_map(1);
@@ -206,57 +267,210 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         alpha
     }
 
-    fn final_exponentiation_part_two(&self, elt: &Fp6<'a, FE, F>, elt_inv: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
-        let mut elt_q = elt.clone();
-        elt_q.frobenius_map(1);
+    fn cyclotomic_exp(f: &Self::Target, exp: &[u64]) -> Self::Target {
+        f.cyclotomic_exp(exp)
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> CPExtension<'a, FE, F, GE, G> for Extension2Over4<'a, FE, F>
+    where G: 'a
+{
+    type TwistField = Fp2<'a, FE, F>;
+    type TwistCurve = twist::WeierstrassCurveTwist<'a, FE, F, GE, G>;
+    type TwistPoint = twist::TwistPoint<'a, FE, F, GE, G>;
+    type Target = Fp8<'a, FE, F>;
+
+    fn target_one(&'a self) -> Self::Target {
+        Fp8::one(self)
+    }
+
+    fn twist_curve_a(curve: &Self::TwistCurve) -> &Self::TwistField {
+        &curve.a
+    }
+
+    fn twist_point_xy(point: &Self::TwistPoint) -> (Self::TwistField, Self::TwistField) {
+        debug_assert!(point.is_normalized());
+
+        (point.x.clone(), point.y.clone())
+    }
 
-        let mut w1_part = elt_q.cyclotomic_exp(&self.exp_w1);
-        let w0_part = match self.exp_w0_is_negative {
-            true => elt_inv.cyclotomic_exp(&self.exp_w0),
-            false => elt.cyclotomic_exp(&self.exp_w0),
+    fn mul_by_line(
+        f: &mut Self::Target,
+        gamma: &Self::TwistField,
+        x: &Self::TwistField,
+        y: &Self::TwistField,
+        px: &Fp<'a, FE, F>,
+        py: &Fp<'a, FE, F>
+    ) {
+        // quartic twist of D type with w^4 = u, point of the twist is mapped 
+        // as (x, y) -> (x * w^2, y * w^3), so the line is 
+        // py - gamma * px * w + (gamma * x - y) * w^3
+        let fp4_extension = f.extension_field.field;
+
+        let mut c0 = Fp4::zero(fp4_extension);
+        c0.c0.c0 = py.clone();
+
+        let mut c1 = Fp4::zero(fp4_extension);
+        c1.c0 = gamma.clone();
+        c1.c0.mul_by_fp(px);
+        c1.c0.negate();
+
+        c1.c1 = gamma.clone();
+        c1.c1.mul_assign(x);
+        c1.c1.sub_assign(y);
+
+        let ell = Fp8 {
+            c0,
+            c1,
+            extension_field: f.extension_field
         };
 
-        w1_part.mul_assign(&w0_part);
+        f.mul_assign(&ell);
+    }
+
+    fn final_exponentiation_easy_part(f: &Self::Target, f_inv: &Self::Target) -> Self::Target {
+        // q^4 - 1
+        let mut elt_q4_over_elt = f.clone();
+        elt_q4_over_elt.conjugate();
+        elt_q4_over_elt.mul_assign(f_inv);
+
+        elt_q4_over_elt
+    }
 
-        w1_part
+    fn cyclotomic_exp(f: &Self::Target, exp: &[u64]) -> Self::Target {
+        f.cyclotomic_exp(exp)
     }
 }
 
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> CPExtension<'a, FE, F, GE, G> for Extension2Over3Over2<'a, FE, F>
+    where G: 'a
+{
+    type TwistField = Fp2<'a, FE, F>;
+    type TwistCurve = twist::WeierstrassCurveTwist<'a, FE, F, GE, G>;
+    type TwistPoint = twist::TwistPoint<'a, FE, F, GE, G>;
+    type Target = Fp12<'a, FE, F>;
+
+    fn target_one(&'a self) -> Self::Target {
+        Fp12::one(self)
+    }
+
+    fn twist_curve_a(curve: &Self::TwistCurve) -> &Self::TwistField {
+        &curve.a
+    }
+
+    fn twist_point_xy(point: &Self::TwistPoint) -> (Self::TwistField, Self::TwistField) {
+        debug_assert!(point.is_normalized());
+
+        (point.x.clone(), point.y.clone())
+    }
+
+    fn mul_by_line(
+        f: &mut Self::Target,
+        gamma: &Self::TwistField,
+        x: &Self::TwistField,
+        y: &Self::TwistField,
+        px: &Fp<'a, FE, F>,
+        py: &Fp<'a, FE, F>
+    ) {
+        // sextic twist of D type with w^6 = xi, point of the twist is mapped 
+        // as (x, y) -> (x * w^2, y * w^3), so the line is 
+        // py - gamma * px * w + (gamma * x - y) * v * w
+        let mut c0 = Fp2::zero(f.extension_field.field.field);
+        c0.c0 = py.clone();
+
+        let mut c3 = gamma.clone();
+        c3.mul_by_fp(px);
+        c3.negate();
+
+        let mut c4 = gamma.clone();
+        c4.mul_assign(x);
+        c4.sub_assign(y);
+
+        f.mul_by_034(&c0, &c3, &c4);
+    }
+
+    fn final_exponentiation_easy_part(f: &Self::Target, f_inv: &Self::Target) -> Self::Target {
+        // (q^6-1)*(q^2+1)
+        let mut elt_q6_over_elt = f.clone();
+        elt_q6_over_elt.conjugate();
+        elt_q6_over_elt.mul_assign(f_inv);
+
+        let mut alpha = elt_q6_over_elt.clone();
+        alpha.frobenius_map(2);
+        alpha.mul_assign(&elt_q6_over_elt);
+
+        alpha
+    }
+
+    fn cyclotomic_exp(f: &Self::Target, exp: &[u64]) -> Self::Target {
+        f.cyclotomic_exp(exp)
+    }
+}
 
-impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for CPInstance6<'a, FE, F, GE, G> {
-    type PairingResult = Fp6<'a, FE, F>;
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>, T: CPExtension<'a, FE, F, GE, G>> PairingEngine for CPInstance<'a, FE, F, GE, G, T> {
+    type PairingResult = T::Target;
     type G1 = CurvePoint<'a, FE, F, GE, G>;
-    type G2 = TwistPoint<'a, FE, F, GE, G>;
+    type G2 = T::TwistPoint;
 
-    fn pair<'b>
-        (&self, points: &'b [CurvePoint<'a, FE, F, GE, G>], twists: &'b [TwistPoint<'a, FE, F, GE, G>]) -> Option<Self::PairingResult> {
-            let mut pairs = vec![];
-            for (p, q) in points.iter().zip(twists.iter()) {
-                pairs.push((p, q));
-            }
-            let loop_result = self.miller_loop(&pairs[..]);
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &T::Target) -> Option<T::Target> {
+        let f_inv = f.inverse()?;
+
+        Some(T::final_exponentiation_easy_part(f, &f_inv))
+    }
 
-            self.final_exponentiation(&loop_result)
-        }   
+    fn final_exponentiation_hard_part(&self, elt: &T::Target) -> T::Target {
+        // elt is in the cyclotomic subgroup, so inverse is just a conjugate
+        let mut elt_inv = elt.clone();
+        elt_inv.conjugate();
+
+        let mut res = self.extension.target_one();
+        for (i, (w, is_negative)) in self.exp_w.iter().enumerate() {
+            let mut w_part = match is_negative {
+                true => T::cyclotomic_exp(&elt_inv, w),
+                false => T::cyclotomic_exp(elt, w),
+            };
+            w_part.frobenius_map(i);
+
+            res.mul_assign(&w_part);
+        }
+
+        res
+    }
+
+    fn target_one(&self) -> T::Target {
+        self.extension.target_one()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use num_bigint::BigUint;
-    use num_traits::FromPrimitive;
-    use num_integer::Integer;
-    use num_traits::Zero;
-    use crate::field::{U832Repr, new_field, biguint_to_u64_vec};
+    use crate::field::{U256Repr, U448Repr, U832Repr, new_field, biguint_to_u64_vec};
     use crate::fp::Fp;
     use crate::traits::{FieldElement};
+    use crate::extension_towers::fp2::{Fp2, Extension2};
     use crate::extension_towers::fp3::{Fp3, Extension3};
-    use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+    use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+    use crate::extension_towers::fp6_as_2_over_3::Extension2Over3;
+    use crate::extension_towers::fp6_as_3_over_2::{Fp6, Extension3Over2};
+    use crate::extension_towers::fp8_as_2_over_4::{Fp8, Extension2Over4};
+    use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
     use num_traits::Num;
-    use crate::pairings::{frobenius_calculator_fp3, frobenius_calculator_fp6_as_2_over_3};
+    use crate::pairings::{frobenius_calculator_fp2, frobenius_calculator_fp3, frobenius_calculator_fp4_as_2_over_2};
+    use crate::pairings::{frobenius_calculator_fp6_as_2_over_3, frobenius_calculator_fp6_as_3_over_2};
+    use crate::pairings::{frobenius_calculator_fp8_as_2_over_4, frobenius_calculator_fp12};
     use crate::weierstrass::{Group};
     use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
-    use crate::weierstrass::cubic_twist::{TwistPoint, WeierstrassCurveTwist};
+    use crate::weierstrass::cubic_twist;
+    use crate::weierstrass::twist::{TwistPoint, WeierstrassCurveTwist};
     use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+    use crate::extension_towers::fp6_as_2_over_3::Fp6 as Fp6_2_over_3;
+    use num_bigint::{BigInt, Sign};
     use rust_test::Bencher;
 
     #[test]
@@ -291,7 +505,7 @@ mod tests {
             frobenius_coeffs_c1: f_c1
         };
 
-        let [c0, c1, c2, c3, c4, c5] = frobenius_calculator_fp6_as_2_over_3(modulus, &extension_6).unwrap();
+        let [c0, c1, c2, c3, c4, c5] = frobenius_calculator_fp6_as_2_over_3(modulus.clone(), &extension_6).unwrap();
         extension_6.frobenius_coeffs_c1 = [c0.c0, c1.c0, c2.c0, c3.c0, c4.c0, c5.c0];
 
         let b_fp = BigUint::from_str_radix("17764315118651679038286329069295091506801468118146712649886336045535808055361274148466772191243305528312843236347777260247138934336850548243151534538734724191505953341403463040067571652261229308333392040104884438208594329793895206056414", 10).unwrap().to_bytes_be();
@@ -321,7 +535,7 @@ mod tests {
         let scalar_field = new_field::<U832Repr>("22369874298875696930346742206501054934775599465297184582183496627646774052458024540232479018147881220178054575403841904557897715222633333372134756426301062487682326574958588001132586331462553235407484089304633076250782629492557320825577", 10).unwrap();
 
         let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
-        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
+        let curve_twist = cubic_twist::WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
 
         let p_x = BigUint::from_str_radix("5511163824921585887915590525772884263960974614921003940645351443740084257508990841338974915037175497689287870585840954231884082785026301437744745393958283053278991955159266640440849940136976927372133743626748847559939620888818486853646", 10).unwrap().to_bytes_be();
         let p_y = BigUint::from_str_radix("7913123550914612057135582061699117755797758113868200992327595317370485234417808273674357776714522052694559358668442301647906991623400754234679697332299689255516547752391831738454121261248793568285885897998257357202903170202349380518443", 10).unwrap().to_bytes_be();
@@ -356,7 +570,7 @@ mod tests {
         q_y.c2 = q_y_2;
 
         let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
-        let q = TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
+        let q = cubic_twist::TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
 
         // let x = BigUint::from_str_radix("506464946133393486072777102926336625944849939610982267859828541006717966526573193706126370441346337661774335955699621", 10).unwrap();
         // println!("X len = {}", biguint_to_u64_vec(x.clone()).len());
@@ -384,20 +598,16 @@ mod tests {
         assert!(q.check_on_curve());
 
         let engine = super::CPInstance6 {
-            x: x,
+            x: x.clone(),
             x_is_negative: false,
-            exp_w0: biguint_to_u64_vec(w0),
-            exp_w1: biguint_to_u64_vec(w1),
-            exp_w0_is_negative: true,
+            exp_w: vec![(biguint_to_u64_vec(w0), true), (biguint_to_u64_vec(w1), false)],
             base_field: &base_field,
             curve: &curve,
             curve_twist: &curve_twist,
-            twist: twist,
-            fp3_extension: &extension_3,
-            fp6_extension: &extension_6,
+            extension: &extension_6,
         };
 
-        let pairing_result = engine.pair(&[p], &[q]).unwrap();
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
 
         // // let expected_c0_c0_c0 = BigUint::from_str_radix("2819105605953691245277803056322684086884703000473961065716485506033588504203831029066448642358042597501014294104502", 10).unwrap();
         
@@ -406,6 +616,359 @@ mod tests {
         // println!("Res = {}", pairing_result);
 
         assert!(format!("{}", pairing_result.c0.c0) == "0x0000000000003621057ef03d9de232637cd9d965a98d2670da76793d38546678fb8f5f148ddc5b1cbcd74c66f8d8462197406e42e9713aa158c0a4b02e4f26c785f73a0b9027c2ff50282278d2e91afbfa6dfa584f55987f960ce39228cf56ab169ba8932adc28df");
+
+        // ate pairing over x = q mod r agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' / u, y' * w / u^2) as the Miller point
+        let group_order = BigUint::from_str_radix("258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177", 10).unwrap();
+        let mut a_embedded = Fp6_2_over_3::zero(&extension_6);
+        a_embedded.c0.c0 = curve.a.clone();
+        let reference = ReferencePairing::new(a_embedded, Fp6_2_over_3::one(&extension_6), modulus.clone(), group_order.clone(), 6).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp6_2_over_3::zero(&extension_6), Fp6_2_over_3::zero(&extension_6));
+        p_embedded.0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0 = p_y_affine;
+
+        let twist_inv = twist.inverse().unwrap();
+        let mut twist_squared_inv = twist_inv.clone();
+        twist_squared_inv.square();
+
+        let (mut q_x_affine, mut q_y_affine) = q.into_xy();
+        q_x_affine.mul_assign(&twist_inv);
+        q_y_affine.mul_assign(&twist_squared_inv);
+        let mut q_untwisted = (Fp6_2_over_3::zero(&extension_6), Fp6_2_over_3::zero(&extension_6));
+        q_untwisted.0.c0 = q_x_affine;
+        q_untwisted.1.c1 = q_y_affine;
+
+        let x = BigInt::from_biguint(Sign::Plus, x.iter().rev().fold(BigUint::from(0u64), |acc, limb| (acc << 64) + BigUint::from(*limb)));
+        let exponent = reference.ate_exponent(&[x, -BigInt::from(1u64)]).unwrap();
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+
+        // torus compression of the pairing output into Fp3 (T2) or a pair of Fp (T6)
+        let m = pairing_result.torus_t2_compress().unwrap();
+        assert!(Fp6_2_over_3::torus_t2_decompress(&extension_6, &m).unwrap() == pairing_result);
+        let (m1, m2) = pairing_result.torus_t6_compress().unwrap();
+        assert!(Fp6_2_over_3::torus_t6_decompress(&extension_6, &m1, &m2).unwrap() == pairing_result);
+
+        let exp = [0x1234567890abcdefu64, 0xfedcba0987654321u64];
+        let m_exp = Fp6_2_over_3::torus_t2_exp(&extension_6, &m, exp).unwrap();
+        assert!(Fp6_2_over_3::torus_t2_decompress(&extension_6, &m_exp).unwrap() == pairing_result.cyclotomic_exp(exp));
+        assert!(Fp6_2_over_3::torus_t2_exp(&extension_6, &m, biguint_to_u64_vec(group_order)).is_none());
+    }
+
+    #[test]
+    fn test_cp8_pairing() {
+        let modulus = BigUint::from_str_radix("657276641091094722418677757557356481225793239656825063123299569487749415843451339241929648068041733552349333988637969737713", 10).unwrap();
+        let base_field = new_field::<U448Repr>("657276641091094722418677757557356481225793239656825063123299569487749415843451339241929648068041733552349333988637969737713", 10).unwrap();
+        let scalar_field = new_field::<U256Repr>("1153018746595109758349602925808448616478549588112854602089049", 10).unwrap();
+        let fp_non_residue = Fp::from_repr(&base_field, U448Repr::from(3)).unwrap();
+
+        let mut extension_2 = Extension2 {
+            field: &base_field,
+            non_residue: fp_non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp2(&extension_2).unwrap();
+        extension_2.frobenius_coeffs_c1 = coeffs;
+
+        let one = Fp::one(&base_field);
+
+        let mut fp2_non_residue = Fp2::zero(&extension_2); // non-residue is 0 + 1*u
+        fp2_non_residue.c1 = one.clone();
+
+        let mut extension_4 = Extension2Over2 {
+            non_residue: fp2_non_residue,
+            field: &extension_2,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp4_as_2_over_2(modulus.clone(), &extension_4).unwrap();
+        extension_4.frobenius_coeffs_c1 = coeffs;
+
+        let mut fp4_non_residue = Fp4::zero(&extension_4); // non-residue is 0 + 1*v
+        fp4_non_residue.c1 = Fp2::one(&extension_2);
+
+        let mut extension_8 = Extension2Over4 {
+            non_residue: fp4_non_residue,
+            field: &extension_4,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field),
+                                  Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp8_as_2_over_4(modulus.clone(), &extension_8).unwrap();
+        extension_8.frobenius_coeffs_c1 = coeffs;
+
+        // y^2 = x^3 + a*x, quartic twist is y^2 = x^3 + a/u * x
+        let a_fp = Fp::from_repr(&base_field, U448Repr::from(6)).unwrap();
+        let b_fp = Fp::zero(&base_field);
+
+        let mut a_fp2 = Fp2::zero(&extension_2);
+        a_fp2.c1 = a_fp.clone();
+        a_fp2.mul_by_fp(&extension_2.non_residue.inverse().unwrap());
+        let b_fp2 = Fp2::zero(&extension_2);
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_2, a_fp2, b_fp2);
+
+        let p_x = BigUint::from_str_radix("14209430604916198859727894773878920253382811713461378884712850269723010610417436235335080234294104266158943408072995854420", 10).unwrap().to_bytes_be();
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = BigUint::from_str_radix("343517381509853279137233310708309165614769381603161485400035660153876904021251174744818865410685281591087909778047109760796", 10).unwrap().to_bytes_be();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        let q_x_0 = BigUint::from_str_radix("73901127630013477128965068034034076064659861290550701148502999253806076356053279514860287896813756230799944761157786774012", 10).unwrap().to_bytes_be();
+        let q_x_0 = Fp::from_be_bytes(&base_field, &q_x_0, true).unwrap();
+        let q_x_1 = BigUint::from_str_radix("374945120169678859604743110828886683869296399720794658770790655707933733576464958773297636426464925329889465710565456412435", 10).unwrap().to_bytes_be();
+        let q_x_1 = Fp::from_be_bytes(&base_field, &q_x_1, true).unwrap();
+        let mut q_x = Fp2::zero(&extension_2);
+        q_x.c0 = q_x_0;
+        q_x.c1 = q_x_1;
+        let q_y_0 = BigUint::from_str_radix("451083866670777261375269692578984251941543156328936562432619239403519941686326417404103505821413157023333392505905540793335", 10).unwrap().to_bytes_be();
+        let q_y_0 = Fp::from_be_bytes(&base_field, &q_y_0, true).unwrap();
+        let q_y_1 = BigUint::from_str_radix("356700437152268960430751648918692229788550791510086906556594735913664925944844654172594363875284497316041521782708891549690", 10).unwrap().to_bytes_be();
+        let q_y_1 = Fp::from_be_bytes(&base_field, &q_y_1, true).unwrap();
+        let mut q_y = Fp2::zero(&extension_2);
+        q_y.c0 = q_y_0;
+        q_y.c1 = q_y_1;
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        // ate loop count is t - 1, hard part of the final exponentiation is (q^4 + 1)/r = sum(w_i * q^i)
+        let x = BigUint::from_str_radix("27162025523980504787917865560243255576610081845264235077852903", 10).unwrap();
+        let w0 = BigUint::from_str_radix("263479464955823499622887629760266578707422634849738711430130176235900022220368341954645717629978714455329406811964592745536", 10).unwrap();
+        let w1 = BigUint::from_str_radix("78055428566141865008187640524211504872182208371395339762794457739602243262916948301220530463197258137283084558047196396472", 10).unwrap();
+        let w2 = BigUint::from_str_radix("290966675440941200432595079777596842644579230782708362141107380806538074979335597448949126910140367197086568337458002832568", 10).unwrap();
+        let w3 = BigUint::from_str_radix("570048529594204255655982535926971621948999606801594400401009714", 10).unwrap();
+
+        let engine = super::CPInstance8 {
+            x: biguint_to_u64_vec(x.clone()),
+            x_is_negative: false,
+            exp_w: vec![(biguint_to_u64_vec(w0), false), (biguint_to_u64_vec(w1), false), (biguint_to_u64_vec(w2), true), (biguint_to_u64_vec(w3), false)],
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &curve_twist,
+            extension: &extension_8,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        assert!(format!("{}", pairing_result.c0.c0.c0) == "0x000000000098ab2773da10e08dcea6ec4e67954d8ef67376d6820dcd8839169cc45cf79f911caa48284c6bf0c71f510a913d27f801abf99d");
+        assert!(format!("{}", pairing_result.c0.c0.c1) == "0x00000000000dba44b7db8adc0c7ca6c0ea539d31122f6cc0f5a8db8715003400da5c0105249c56d36a52b4524f3de9127f0b4f8d4acaee77");
+        assert!(format!("{}", pairing_result.c0.c1.c0) == "0x00000000006920bfe735bf901dcbfb07407818ce655b94eb6867034a24663e0bb7797bdfd12f0f048ffc9c4e5fa2998b1b275972b34d58c5");
+        assert!(format!("{}", pairing_result.c0.c1.c1) == "0x000000000046db4a5c617e5181d521fc12e7fc0964d50f34ab34754b8de7544c9319fbcf4fcd3fba3096b4dee528ac4841f9db9d3544b5be");
+        assert!(format!("{}", pairing_result.c1.c0.c0) == "0x0000000000889df2630ddcdb649896135ac4ef323ac4cdeaa2c9ed3f4a74b10090eb0faea7a39979294f90362250a50564c87f2c0ea68754");
+        assert!(format!("{}", pairing_result.c1.c0.c1) == "0x00000000003a243af41206be09965e0bf690102f0869dfc2db3affb6a4e55bbf2d0e46cd718da3761a85f3b2ce91de22d2a7dddea00274d2");
+        assert!(format!("{}", pairing_result.c1.c1.c0) == "0x000000000026598d78e2fa7abddbe9859c9ac2b2e3754608e98842ebac324122e9aca41d58b4dbb2e818719ee4e94d8593de866859ed229f");
+        assert!(format!("{}", pairing_result.c1.c1.c1) == "0x00000000008e566d05901727ee595f5783a0ef0600de8dc13db74c7e83b877d18170d27097b075e8ff27f2518c7245add04ea96b0e8d6fe4");
+
+        // ate pairing over x = q mod r agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' * w^2, y' * w^3) as the Miller point
+        let group_order = BigUint::from_str_radix("1153018746595109758349602925808448616478549588112854602089049", 10).unwrap();
+        let mut a_embedded = Fp8::zero(&extension_8);
+        a_embedded.c0.c0.c0 = curve.a.clone();
+        let reference = ReferencePairing::new(a_embedded, Fp8::one(&extension_8), modulus, group_order, 8).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp8::zero(&extension_8), Fp8::zero(&extension_8));
+        p_embedded.0.c0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0.c0 = p_y_affine;
+
+        let (q_x_affine, q_y_affine) = q.into_xy();
+        let mut q_untwisted = (Fp8::zero(&extension_8), Fp8::zero(&extension_8));
+        q_untwisted.0.c0.c1 = q_x_affine;
+        q_untwisted.1.c1.c1 = q_y_affine;
+
+        let exponent = reference.ate_exponent(&[BigInt::from_biguint(Sign::Plus, x), -BigInt::from(1u64)]).unwrap();
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p.clone(), p_neg], &[q.clone(), q.clone()]).unwrap() == Fp8::one(&extension_8));
+    }
+
+    #[test]
+    fn test_cp12_pairing() {
+        let modulus = BigUint::from_str_radix("879744534825721197704533028584414947702885489327913164644463702365230367200886770188411406131619666511371321695059076887797", 10).unwrap();
+        let base_field = new_field::<U448Repr>("879744534825721197704533028584414947702885489327913164644463702365230367200886770188411406131619666511371321695059076887797", 10).unwrap();
+        let scalar_field = new_field::<U256Repr>("1225426419550923852878434537339560595691043009355587630861661", 10).unwrap();
+        let fp_non_residue = Fp::from_repr(&base_field, U448Repr::from(2)).unwrap();
+
+        let mut extension_2 = Extension2 {
+            field: &base_field,
+            non_residue: fp_non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp2(&extension_2).unwrap();
+        extension_2.frobenius_coeffs_c1 = coeffs;
+
+        let one = Fp::one(&base_field);
+
+        // non-residue is u+8
+        let mut fp2_non_residue = Fp2::zero(&extension_2);
+        fp2_non_residue.c0 = Fp::from_repr(&base_field, U448Repr::from(8)).unwrap();
+        fp2_non_residue.c1 = one.clone();
+
+        let f_c1 = [Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2)];
+
+        let mut extension_6 = Extension3Over2 {
+            non_residue: fp2_non_residue.clone(),
+            field: &extension_2,
+            frobenius_coeffs_c1: f_c1.clone(),
+            frobenius_coeffs_c2: f_c1,
+        };
+
+        let (coeffs_c1, coeffs_c2) = frobenius_calculator_fp6_as_3_over_2(modulus.clone(), &extension_6).unwrap();
+        extension_6.frobenius_coeffs_c1 = coeffs_c1;
+        extension_6.frobenius_coeffs_c2 = coeffs_c2;
+
+        let f_c1 = [Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2),
+                    Fp2::zero(&extension_2), Fp2::zero(&extension_2), Fp2::zero(&extension_2)];
+
+        let mut extension_12 = Extension2Over3Over2 {
+            non_residue: Fp6::zero(&extension_6),
+            field: &extension_6,
+            frobenius_coeffs_c1: f_c1,
+        };
+
+        let coeffs = frobenius_calculator_fp12(modulus.clone(), &extension_12).unwrap();
+        extension_12.frobenius_coeffs_c1 = coeffs;
+
+        // y^2 = x^3 + b, sextic twist is y^2 = x^3 + b/(u+8)
+        let a_fp = Fp::zero(&base_field);
+        let b_fp = Fp::from_repr(&base_field, U448Repr::from(1)).unwrap();
+
+        let a_fp2 = Fp2::zero(&extension_2);
+        let mut b_fp2 = fp2_non_residue.inverse().unwrap();
+        b_fp2.mul_by_fp(&b_fp);
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_2, a_fp2, b_fp2);
+
+        let p_x = BigUint::from_str_radix("201294717600494211734014891737714793618101349819750833754546826359506953552152237029437212615338477668270615849505376912429", 10).unwrap().to_bytes_be();
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = BigUint::from_str_radix("857088407325124054445040323971409848546779041237384610403779480107111651208334726940769575248223487719713236637297108536842", 10).unwrap().to_bytes_be();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        let q_x_0 = BigUint::from_str_radix("761251956584552020389317529723402382557497981849342894068977621057861761062932946433573706974637008106855165937119461226397", 10).unwrap().to_bytes_be();
+        let q_x_0 = Fp::from_be_bytes(&base_field, &q_x_0, true).unwrap();
+        let q_x_1 = BigUint::from_str_radix("378450839457912260189499438981339343977465993382856492599055470397348410329496588297620817475297719745433257059354246779621", 10).unwrap().to_bytes_be();
+        let q_x_1 = Fp::from_be_bytes(&base_field, &q_x_1, true).unwrap();
+        let mut q_x = Fp2::zero(&extension_2);
+        q_x.c0 = q_x_0;
+        q_x.c1 = q_x_1;
+        let q_y_0 = BigUint::from_str_radix("449388939402607876081897915906310078202366413162525158001857336007617714995081692591596561819448648615000462387884151872652", 10).unwrap().to_bytes_be();
+        let q_y_0 = Fp::from_be_bytes(&base_field, &q_y_0, true).unwrap();
+        let q_y_1 = BigUint::from_str_radix("849789175128530823070707075878144964736108232596082008662360953411562871954042307017081055269145416615055231130855056574489", 10).unwrap().to_bytes_be();
+        let q_y_1 = Fp::from_be_bytes(&base_field, &q_y_1, true).unwrap();
+        let mut q_y = Fp2::zero(&extension_2);
+        q_y.c0 = q_y_0;
+        q_y.c1 = q_y_1;
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        // ate loop count is t - 1, hard part of the final exponentiation is (q^4 - q^2 + 1)/r = sum(w_i * q^i)
+        let x = BigUint::from_str_radix("21532786730408035758328072735119007518908391604973999737129945", 10).unwrap();
+        let w0 = BigUint::from_str_radix("297975031571217745249631700886251760326391886376492176251348335413464127686795329016274261858702817198886594873214720482576", 10).unwrap();
+        let w1 = BigUint::from_str_radix("337463297548897778989194114246983774411180120419571702991563288410638604242293258852942020833278728017658678333418982750649", 10).unwrap();
+        let w2 = BigUint::from_str_radix("376822357300343342212457764814103155037343889687692094206882633989390323064940830555347663295738893963842968509888648081126", 10).unwrap();
+        let w3 = BigUint::from_str_radix("717908901579025006829799477015267888050988560910787082883702750", 10).unwrap();
+
+        let engine = super::CPInstance12 {
+            x: biguint_to_u64_vec(x.clone()),
+            x_is_negative: false,
+            exp_w: vec![(biguint_to_u64_vec(w0), true), (biguint_to_u64_vec(w1), false), (biguint_to_u64_vec(w2), true), (biguint_to_u64_vec(w3), false)],
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &curve_twist,
+            extension: &extension_12,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        assert!(format!("{}", pairing_result.c0.c0.c0) == "0x000000000096d9bfa719c344e861e3f18ebc63f5b7a612ea4fa2b1698883a2466baee819b115b492c26913f19a776c2a67fb32006ba6a300");
+        assert!(format!("{}", pairing_result.c0.c0.c1) == "0x00000000002d2ed6703e7c9181400d970b5bbc88825160fb94774e7204a597619a7182d8bac921307d1beb437a0e24acee6922936433e579");
+        assert!(format!("{}", pairing_result.c0.c1.c0) == "0x0000000000069cacfa7da7e686aba795dcdd2e12e0e0492ca001ec4c340e154a7d6311554effe7cd6dfb59fc5bfec4092e96818b5c0e7b1c");
+        assert!(format!("{}", pairing_result.c0.c1.c1) == "0x0000000000199a12c4e062e9135f6c6229dea4598f2058069a88eaba871c5c9fbe10e5492db4ccdfb2c9f4c3a3a13a5b75eb6d944fcfebd5");
+        assert!(format!("{}", pairing_result.c0.c2.c0) == "0x000000000078e2a1334e329678ca60183ed060965b68b08f019964878ed6f9f78c835bc3ce887a3fe3f64c0216d0eaa575bac3199207b15a");
+        assert!(format!("{}", pairing_result.c0.c2.c1) == "0x0000000001164c1d950343a35c21693295603c1e13004dbf1b0f059452abe493aabc2b7f5d93a85908c3ac222b2d87f8aba55bb8fe49e5f1");
+        assert!(format!("{}", pairing_result.c1.c0.c0) == "0x00000000007d4643bb60f324a1d77ba3c201d08a8d35c370bf2269ecfbab0d28137134f415c3aaee82a459a6302f326a8436779f0eb32ee7");
+        assert!(format!("{}", pairing_result.c1.c0.c1) == "0x0000000001202c9c65ad53f7d2ff62ee83eda72f9576abfe6817bc4ff8db5225cf6811da721d502ceab28638665b72a08e597d81eda48b9f");
+        assert!(format!("{}", pairing_result.c1.c1.c0) == "0x0000000000eacf8966da965980df008a0f4a51d52fde6630529dd6b5e2100430ac93df0879932f622276c612592986017272586416b1ca59");
+        assert!(format!("{}", pairing_result.c1.c1.c1) == "0x00000000012f4c7d369fdd533ead4afc5d377bacce694c8b32645c64850a8766397fbf19c82b28c5c6e7dbef3ae4448726d6aa31917ca3d9");
+        assert!(format!("{}", pairing_result.c1.c2.c0) == "0x00000000010e6c646f3083ec67e60627f0e3144377f6244f3afbb8e2b89019921926912dfab0c97b65687034d43071a4ffe900560c2c31fa");
+        assert!(format!("{}", pairing_result.c1.c2.c1) == "0x00000000001a074e72d5873ddb6288b8fd3156632733892ae93224ee8952258a6a6286ec57be288ed55cf9597e96ae81932ff6580220dbde");
+
+        // ate pairing over x = q mod r agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' * w^2, y' * w^3) as the Miller point
+        let group_order = BigUint::from_str_radix("1225426419550923852878434537339560595691043009355587630861661", 10).unwrap();
+        let reference = ReferencePairing::new(Fp12::zero(&extension_12), Fp12::one(&extension_12), modulus, group_order, 12).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp12::zero(&extension_12), Fp12::zero(&extension_12));
+        p_embedded.0.c0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0.c0 = p_y_affine;
+
+        let (q_x_affine, q_y_affine) = q.into_xy();
+        let mut q_untwisted = (Fp12::zero(&extension_12), Fp12::zero(&extension_12));
+        q_untwisted.0.c0.c1 = q_x_affine;
+        q_untwisted.1.c1.c1 = q_y_affine;
+
+        let exponent = reference.ate_exponent(&[BigInt::from_biguint(Sign::Plus, x), -BigInt::from(1u64)]).unwrap();
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p.clone(), p_neg], &[q.clone(), q.clone()]).unwrap() == Fp12::one(&extension_12));
+
+        // negative loop parameter gives the inverse
+        let engine = super::CPInstance12 {
+            x_is_negative: true,
+            ..engine
+        };
+        let mut expected = pairing_result.clone();
+        expected.conjugate();
+        assert!(engine.pair(&[p], &[q]).unwrap() == expected);
     }
 
     #[bench]
@@ -470,7 +1033,7 @@ mod tests {
         let scalar_field = new_field::<U832Repr>("22369874298875696930346742206501054934775599465297184582183496627646774052458024540232479018147881220178054575403841904557897715222633333372134756426301062487682326574958588001132586331462553235407484089304633076250782629492557320825577", 10).unwrap();
 
         let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
-        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
+        let curve_twist = cubic_twist::WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
 
         let p_x = BigUint::from_str_radix("5511163824921585887915590525772884263960974614921003940645351443740084257508990841338974915037175497689287870585840954231884082785026301437744745393958283053278991955159266640440849940136976927372133743626748847559939620888818486853646", 10).unwrap().to_bytes_be();
         let p_y = BigUint::from_str_radix("7913123550914612057135582061699117755797758113868200992327595317370485234417808273674357776714522052694559358668442301647906991623400754234679697332299689255516547752391831738454121261248793568285885897998257357202903170202349380518443", 10).unwrap().to_bytes_be();
@@ -505,7 +1068,7 @@ mod tests {
         q_y.c2 = q_y_2;
 
         let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
-        let q = TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
+        let q = cubic_twist::TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
 
         // let x = BigUint::from_str_radix("506464946133393486072777102926336625944849939610982267859828541006717966526573193706126370441346337661774335955699621", 10).unwrap();
         // println!("X len = {}", biguint_to_u64_vec(x.clone()).len());
@@ -535,19 +1098,15 @@ mod tests {
         let engine = super::CPInstance6 {
             x: x,
             x_is_negative: false,
-            exp_w0: biguint_to_u64_vec(w0),
-            exp_w1: biguint_to_u64_vec(w1),
-            exp_w0_is_negative: true,
+            exp_w: vec![(biguint_to_u64_vec(w0), true), (biguint_to_u64_vec(w1), false)],
             base_field: &base_field,
             curve: &curve,
             curve_twist: &curve_twist,
-            twist: twist,
-            fp3_extension: &extension_3,
-            fp6_extension: &extension_6,
+            extension: &extension_6,
         };
 
         b.iter(|| {
-            engine.pair(&[p.clone()], &[q.clone()]).unwrap();
+            engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
         });
     }
 }
\ No newline at end of file
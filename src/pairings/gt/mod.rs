@@ -0,0 +1,180 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::FieldElement;
+use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
+
+// Target group of the pairings with values in Fp12 as 2 over 3 over 2 (BN and BLS12
+// engines), that is the subgroup of order r of the cyclotomic subgroup. Elements
+// are either produced by the engine or decoded with a membership check, so inverse
+// is a conjugation and exponentiation uses cyclotomic squarings
+
+pub struct Gt<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    value: Fp12<'a, FE, F>,
+    scalar_field: &'a G,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> std::fmt::Display for Gt<'a, FE, F, GE, G> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Gt({})", self.value)
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> std::fmt::Debug for Gt<'a, FE, F, GE, G> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Gt({})", self.value)
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Clone for Gt<'a, FE, F, GE, G> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            scalar_field: self.scalar_field
+        }
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PartialEq for Gt<'a, FE, F, GE, G> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Eq for Gt<'a, FE, F, GE, G> {
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Gt<'a, FE, F, GE, G> {
+    pub fn one(extension: &'a Extension2Over3Over2<'a, FE, F>, scalar_field: &'a G) -> Self {
+        Self {
+            value: Fp12::one(extension),
+            scalar_field
+        }
+    }
+
+    /// Wraps the value that is known to be in the target group, e.g. the output
+    /// of the final exponentiation
+    pub(crate) fn from_fp12_unchecked(value: Fp12<'a, FE, F>, scalar_field: &'a G) -> Self {
+        Self {
+            value,
+            scalar_field
+        }
+    }
+
+    /// Wraps the value after checking that it's in the subgroup of order r
+    pub fn from_fp12(value: Fp12<'a, FE, F>, scalar_field: &'a G) -> Option<Self> {
+        if !Self::is_in_subgroup(&value, scalar_field) {
+            return None;
+        }
+
+        Some(Self::from_fp12_unchecked(value, scalar_field))
+    }
+
+    /// Checks that the value is in the cyclotomic subgroup, so x^(q^6 + 1) = 1 and
+    /// x^(q^4 - q^2 + 1) = 1, and that it's order divides r
+    pub fn is_in_subgroup(value: &Fp12<'a, FE, F>, scalar_field: &'a G) -> bool {
+        if value.is_zero() {
+            return false;
+        }
+
+        let one = Fp12::one(value.extension_field);
+
+        let mut t0 = value.clone();
+        t0.conjugate();
+        t0.mul_assign(value);
+        if t0 != one {
+            return false;
+        }
+
+        let mut t0 = value.clone();
+        t0.frobenius_map(4);
+        t0.mul_assign(value);
+        let mut t1 = value.clone();
+        t1.frobenius_map(2);
+        if t0 != t1 {
+            return false;
+        }
+
+        value.cyclotomic_exp(scalar_field.modulus()) == one
+    }
+
+    pub fn as_fp12(&self) -> &Fp12<'a, FE, F> {
+        &self.value
+    }
+
+    pub fn into_fp12(self) -> Fp12<'a, FE, F> {
+        self.value
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.value == Fp12::one(self.value.extension_field)
+    }
+
+    pub fn mul_assign(&mut self, other: &Self) {
+        self.value.mul_assign(&other.value);
+    }
+
+    pub fn square(&mut self) {
+        self.value.cyclotomic_square();
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut value = self.value.clone();
+        value.conjugate();
+
+        Self::from_fp12_unchecked(value, self.scalar_field)
+    }
+
+    pub fn pow(&self, scalar: &Fp<'a, GE, G>) -> Self {
+        let value = self.value.cyclotomic_exp(scalar.into_repr());
+
+        Self::from_fp12_unchecked(value, self.scalar_field)
+    }
+
+    /// Encodes coefficients of c0.c0.c0, c0.c0.c1, c0.c1.c0, ..., c1.c2.c1 in this
+    /// order, each as a big endian integer of the full representation length
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = vec![];
+        for c in [&self.value.c0, &self.value.c1].iter() {
+            for c in [&c.c0, &c.c1, &c.c2].iter() {
+                // writing into a vector never fails
+                c.c0.into_repr().write_be(&mut result).unwrap();
+                c.c1.into_repr().write_be(&mut result).unwrap();
+            }
+        }
+
+        result
+    }
+
+    /// Decodes the encoding produced by `to_bytes`, rejects the non-reduced
+    /// coefficients and values that are not in the target group
+    pub fn from_bytes(
+        extension: &'a Extension2Over3Over2<'a, FE, F>,
+        scalar_field: &'a G,
+        bytes: &[u8]
+    ) -> Result<Self, ()> {
+        let element_len = FE::default().as_ref().len() * 8;
+        if bytes.len() != element_len * 12 {
+            return Err(());
+        }
+
+        let base_field = extension.field.field.field;
+        let mut coeffs = vec![];
+        for chunk in bytes.chunks(element_len) {
+            coeffs.push(Fp::from_be_bytes(base_field, chunk, false).map_err(|_| ())?);
+        }
+
+        let mut value = Fp12::zero(extension);
+        let mut coeffs = coeffs.into_iter();
+        for c in [&mut value.c0, &mut value.c1].iter_mut() {
+            for c in [&mut c.c0, &mut c.c1, &mut c.c2].iter_mut() {
+                c.c0 = coeffs.next().unwrap();
+                c.c1 = coeffs.next().unwrap();
+            }
+        }
+
+        Self::from_fp12(value, scalar_field).ok_or(())
+    }
+}
@@ -0,0 +1,652 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::FieldElement;
+use crate::weierstrass::Group;
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::cubic_twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::extension_towers::fp3::{Fp3, Extension3};
+use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+use crate::extension_towers::fp18_as_3_over_6::{Fp18, Extension3Over6};
+use crate::pairings::{PairingEngine, into_ternary_wnaf, cyclotomic_multi_exp};
+use crate::pairings::bls12::TwistType;
+
+// Optimal ate pairing for the KSS18 curves, see "Implementing Pairings at the 192-bit
+// Security Level" by Aranha et al. Tower is Fp18 = Fp6[w]/(w^3 - z), Fp6 = Fp3[z]/(z^2 - v)
+// and the second argument is taken from the sextic twist defined over Fp3.
+// As x + 3q - q^4 = 0 mod r the Miller function is
+// f_{x,Q}(P) * f_{3,Q}(P)^q * l_{[x]Q, pi([3]Q)}(P)
+
+pub struct PreparedTwistPoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> {
+    is_infinity: bool,
+    pub ell_coeffs: Vec<(Fp3<'a, FE, F>, Fp3<'a, FE, F>, Fp3<'a, FE, F>)>
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>> PreparedTwistPoint<'a, FE, F> {
+    pub fn is_zero(&self) -> bool {
+        self.is_infinity
+    }
+}
+
+pub struct Kss18Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    pub x: Vec<u64>,
+    pub x_is_negative: bool,
+    pub twist_type: TwistType,
+    // coefficients of lambda_i(x) (lowest degree first) for i = 0..6, such that
+    // sum(lambda_i(x) * q^i) is a multiple of (q^6 - q^3 + 1)/r coprime with r
+    pub hard_part_lambdas: Vec<Vec<i64>>,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+    pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
+    fp3_extension: &'a Extension3<'a, FE, F>,
+    fp6_extension: &'a Extension2Over3<'a, FE, F>,
+    fp18_extension: &'a Extension3Over6<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Kss18Instance<'a, FE, F, GE, G> {
+    fn ell(
+        &self,
+        f: &mut Fp18<'a, FE, F>,
+        coeffs: &(Fp3<'a, FE, F>, Fp3<'a, FE, F>, Fp3<'a, FE, F>),
+        p: & CurvePoint<'a, FE, F, GE, G>,
+    ) {
+        debug_assert!(p.is_normalized());
+        let mut c0 = coeffs.0.clone();
+        let mut c1 = coeffs.1.clone();
+        let mut c2 = coeffs.2.clone();
+
+        // Fp18 element is sum(a_i * w^i) for a_i in Fp3, where a_i is placed
+        // into the Fp6 coefficient i % 3 as it's c0 (i < 3) or c1 (i >= 3) part
+        let zero = Fp3::zero(self.fp3_extension);
+        match self.twist_type {
+            TwistType::M => {
+                // line has non-zero coefficients at 1, w^2 and w^3
+                c2.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                let a0 = Fp6 { c0, c1: c2, extension_field: self.fp6_extension };
+                let a2 = Fp6 { c0: c1, c1: zero, extension_field: self.fp6_extension };
+                f.mul_by_02(&a0, &a2);
+            },
+            TwistType::D => {
+                // line has non-zero coefficients at 1, w and w^3
+                c0.mul_by_fp(&p.y);
+                c1.mul_by_fp(&p.x);
+                let a0 = Fp6 { c0, c1: c2, extension_field: self.fp6_extension };
+                let a1 = Fp6 { c0: c1, c1: zero, extension_field: self.fp6_extension };
+                f.mul_by_01(&a0, &a1);
+            },
+        }
+    }
+
+    fn exp_by_x(&self, f: &Fp18<'a, FE, F>) -> Fp18<'a, FE, F> {
+        let mut result = f.cyclotomic_exp(&self.x);
+        if self.x_is_negative {
+            result.conjugate();
+        }
+
+        result
+    }
+
+
+    fn doubling_step(
+        &self,
+        r: &mut TwistPoint<'a, FE, F, GE, G>,
+        two_inv: &Fp<'a, FE, F>,
+    ) -> (Fp3<'a, FE, F>, Fp3<'a, FE, F>, Fp3<'a, FE, F>) {
+        // Use adapted formulas from ZEXE instead
+        let mut a = r.x.clone();
+        a.mul_assign(&r.y);
+        a.mul_by_fp(two_inv);
+        let mut b = r.y.clone();
+        b.square();
+        let mut c = r.z.clone();
+        c.square();
+
+        let mut e = self.curve_twist.b.clone();
+        let mut t0 = c.clone();
+        t0.double();
+        t0.add_assign(&c);
+
+        e.mul_assign(&t0);
+
+        let mut f = e.clone();
+        f.double();
+        f.add_assign(&e);
+
+        let mut g = b.clone();
+        g.add_assign(&f);
+        g.mul_by_fp(two_inv);
+
+        let mut h = r.y.clone();
+        h.add_assign(&r.z);
+        h.square();
+
+        let mut t1 = b.clone();
+        t1.add_assign(&c);
+
+        h.sub_assign(&t1);
+
+        let mut i = e.clone();
+        i.sub_assign(&b);
+
+        let mut j = r.x.clone();
+        j.square();
+
+        let mut e_square = e.clone();
+        e_square.square();
+
+        r.x = b.clone();
+        r.x.sub_assign(&f);
+        r.x.mul_assign(&a);
+
+        let mut e_square_by_3 = e_square.clone();
+        e_square_by_3.double();
+        e_square_by_3.add_assign(&e_square);
+
+        r.y = g;
+        r.y.square();
+        r.y.sub_assign(&e_square_by_3);
+
+        r.z = b.clone();
+        r.z.mul_assign(&h);
+
+        let mut j_by_three = j.clone();
+        j_by_three.double();
+        j_by_three.add_assign(&j);
+        h.negate();
+
+        match self.twist_type {
+            TwistType::M => {
+                (i, j_by_three, h)
+            },
+            TwistType::D => {
+                (h, j_by_three, i)
+            },
+        }
+    }
+
+    fn addition_step(
+        &self,
+        r: &mut TwistPoint<'a, FE, F, GE, G>,
+        q: &TwistPoint<'a, FE, F, GE, G>,
+    ) -> (Fp3<'a, FE, F>, Fp3<'a, FE, F>, Fp3<'a, FE, F>) {
+        debug_assert!(q.is_normalized());
+        // use adapted zexe formulas too instead of ones from pairing crate
+        let mut theta = q.y.clone();
+        theta.mul_assign(&r.z);
+        theta.negate();
+        theta.add_assign(&r.y);
+
+        let mut lambda = q.x.clone();
+        lambda.mul_assign(&r.z);
+        lambda.negate();
+        lambda.add_assign(&r.x);
+
+        let mut c = theta.clone();
+        c.square();
+        let mut d = lambda.clone();
+        d.square();
+        let mut e = lambda.clone();
+        e.mul_assign(&d);
+        let mut f = r.z.clone();
+        f.mul_assign(&c);
+        let mut g = r.x.clone();
+        g.mul_assign(&d);
+
+        let mut h = g.clone();
+        h.double();
+        h.negate();
+        h.add_assign(&e);
+        h.add_assign(&f);
+        
+
+        r.x = lambda.clone();
+        r.x.mul_assign(&h);
+
+        let mut t0 = g.clone();
+        t0.sub_assign(&h);
+        t0.mul_assign(&theta);
+
+        r.y.mul_assign(&e);
+        r.y.negate();
+        r.y.add_assign(&t0);
+
+        r.z.mul_assign(&e);
+
+        let mut t1 = lambda.clone();
+        t1.mul_assign(&q.y);
+        
+        let mut j = theta.clone();
+        j.mul_assign(&q.x);
+        j.sub_assign(&t1);
+
+        theta.negate();
+        match self.twist_type {
+            TwistType::M => (j, theta, lambda),
+            TwistType::D => (lambda, theta, j),
+        }
+    }
+
+
+    pub fn prepare(&self, twist_point: & TwistPoint<'a, FE, F, GE, G>) -> PreparedTwistPoint<'a, FE, F> {
+        debug_assert!(twist_point.is_normalized());
+
+        let mut two_inv = Fp::one(self.base_field);
+        two_inv.double();
+        let two_inv = two_inv.inverse().unwrap();
+
+        if twist_point.is_zero() {
+            return PreparedTwistPoint {
+                ell_coeffs: vec![],
+                is_infinity:   true,
+            };
+        }
+
+        let mut ell_coeffs = vec![];
+        let mut r = TwistPoint::point_from_xy(self.curve_twist, twist_point.x.clone(), twist_point.y.clone());
+
+        let mut minus_q = twist_point.clone();
+        minus_q.negate();
+
+        for &digit in into_ternary_wnaf(&self.x).iter().rev().skip(1) {
+            ell_coeffs.push(self.doubling_step(&mut r, &two_inv));
+
+            if digit == 1 {
+                ell_coeffs.push(self.addition_step(&mut r, twist_point));
+            } else if digit == -1 {
+                ell_coeffs.push(self.addition_step(&mut r, &minus_q));
+            }
+        }
+
+        if self.x_is_negative {
+            r.negate();
+        }
+
+        // lines of f_{3,Q}, that are tangent at Q and the line through [2]Q and Q
+        let mut t = TwistPoint::point_from_xy(self.curve_twist, twist_point.x.clone(), twist_point.y.clone());
+        ell_coeffs.push(self.doubling_step(&mut t, &two_inv));
+        ell_coeffs.push(self.addition_step(&mut t, twist_point));
+
+        // pi([3]Q) = (x^q * w^(2(q-1)), y^q * w^(3(q-1))) for D-type twist
+        // and with inverse coefficients for M-type twist
+        let mut y_coeff = self.fp18_extension.frobenius_coeffs_c1[1].clone();
+        y_coeff.mul_assign(&self.fp18_extension.frobenius_coeffs_c2[1]);
+        let (x_coeff, y_coeff) = match self.twist_type {
+            TwistType::D => (
+                self.fp18_extension.frobenius_coeffs_c2[1].clone(),
+                y_coeff
+            ),
+            TwistType::M => (
+                self.fp18_extension.frobenius_coeffs_c2[1].inverse().unwrap(),
+                y_coeff.inverse().unwrap()
+            ),
+        };
+
+        // doubling and addition steps work in homogenious coordinates
+        let (mut x, mut y) = t.into_xy_from_homogenious();
+        x.frobenius_map(1);
+        x.mul_assign(&x_coeff);
+        y.frobenius_map(1);
+        y.mul_assign(&y_coeff);
+        let pi_t = TwistPoint::point_from_xy(self.curve_twist, x, y);
+
+        ell_coeffs.push(self.addition_step(&mut r, &pi_t));
+
+        PreparedTwistPoint {
+            ell_coeffs,
+            is_infinity: false,
+        }
+    }
+
+    fn miller_loop<'b, I>(&self, i: I) -> Fp18<'a, FE, F>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
+                &'b TwistPoint<'a, FE, F, GE, G>)
+        >
+    {
+        let mut g1_references = vec![];
+        let mut prepared_coeffs = vec![];
+
+        for (p, q) in i.into_iter() {
+            if !p.is_zero() && !q.is_zero() {
+                let coeffs = self.prepare(q);
+                let ell_coeffs = coeffs.ell_coeffs;
+                prepared_coeffs.push(ell_coeffs);
+                g1_references.push(p);
+            }
+        }
+
+        let mut prepared_coeffs: Vec<_> = prepared_coeffs.into_iter().map(|el| el.into_iter()).collect();
+
+        let mut f = Fp18::one(self.fp18_extension);
+
+        for &digit in into_ternary_wnaf(&self.x).iter().rev().skip(1) {
+            f.square();
+
+            for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+                self.ell(&mut f, &coeffs.next().unwrap(), p);
+            }
+
+            if digit != 0 {
+                for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+                    self.ell(&mut f, &coeffs.next().unwrap(), p);
+                }
+            }
+        }
+
+        if self.x_is_negative {
+            f.conjugate();
+        }
+
+        // f_{3,Q}(P)^q
+        let mut g = Fp18::one(self.fp18_extension);
+        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+            self.ell(&mut g, &coeffs.next().unwrap(), p);
+            self.ell(&mut g, &coeffs.next().unwrap(), p);
+        }
+        g.frobenius_map(1);
+        f.mul_assign(&g);
+
+        for (p, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+            self.ell(&mut f, &coeffs.next().unwrap(), p);
+        }
+
+        for (_, coeffs) in g1_references.iter().zip(prepared_coeffs.iter_mut()) {
+            debug_assert!(coeffs.next().is_none());
+        }
+
+        f
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for Kss18Instance<'a, FE, F, GE, G> {
+    type PairingResult = Fp18<'a, FE, F>;
+    type G1 = CurvePoint<'a, FE, F, GE, G>;
+    type G2 = TwistPoint<'a, FE, F, GE, G>;
+
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &Fp18<'a, FE, F>) -> Option<Fp18<'a, FE, F>> {
+        // easy part is (q^9 - 1)*(q^3 + 1)
+
+        // f1 = f^(q^9)
+        let mut f1 = f.clone();
+        f1.conjugate();
+
+        let f2 = f.inverse()?;
+
+        // r = f^(q^9 - 1)
+        let mut r = f1;
+        r.mul_assign(&f2);
+
+        // r = f^((q^9 - 1)(q^3 + 1))
+        let mut f2 = r.clone();
+        f2.frobenius_map(3);
+        r.mul_assign(&f2);
+
+        Some(r)
+    }
+
+    fn final_exponentiation_hard_part(&self, r: &Fp18<'a, FE, F>) -> Fp18<'a, FE, F> {
+        // hard part (q^6 - q^3 + 1)/r is computed as a multi-exponentiation of
+        // r^(x^j * q^i) by the coefficients of lambda_i(x) that requires seven
+        // exponentiations by x for KSS18
+        let degree = self.hard_part_lambdas.iter().map(|l| l.len()).max().unwrap_or(0);
+
+        let mut powers = Vec::with_capacity(degree);
+        let mut power = r.clone();
+        for i in 0..degree {
+            if i != 0 {
+                power = self.exp_by_x(&power);
+            }
+            powers.push(power.clone());
+        }
+
+        let lambdas: Vec<&[i64]> = self.hard_part_lambdas.iter().map(|l| &l[..]).collect();
+
+        cyclotomic_multi_exp(Fp18::one(self.fp18_extension), &powers, &lambdas)
+    }
+
+    fn target_one(&self) -> Fp18<'a, FE, F> {
+        Fp18::one(self.fp18_extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, BigInt, Sign};
+    use crate::field::{U384Repr, U512Repr, new_field, biguint_to_u64_vec};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement};
+    use crate::extension_towers::fp3::{Fp3, Extension3};
+    use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+    use crate::extension_towers::fp18_as_3_over_6::{Fp18, Extension3Over6};
+    use num_traits::Num;
+    use crate::pairings::{frobenius_calculator_fp3, frobenius_calculator_fp6_as_2_over_3, frobenius_calculator_fp18};
+    use crate::pairings::bls12::TwistType;
+    use crate::weierstrass::{Group};
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::cubic_twist::{TwistPoint, WeierstrassCurveTwist};
+    use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+    use num_integer::Integer;
+    use num_traits::Zero;
+
+    fn fp3_from_decimal<'a>(extension_3: &'a Extension3<'a, U512Repr, crate::field::PrimeField<U512Repr>>, coeffs: [&str; 3]) -> Fp3<'a, U512Repr, crate::field::PrimeField<U512Repr>> {
+        let base_field = extension_3.field;
+        let mut els = coeffs.iter().map(|c| {
+            let c = BigUint::from_str_radix(c, 10).unwrap().to_bytes_be();
+            Fp::from_be_bytes(base_field, &c, true).unwrap()
+        });
+
+        let mut el = Fp3::zero(extension_3);
+        el.c0 = els.next().unwrap();
+        el.c1 = els.next().unwrap();
+        el.c2 = els.next().unwrap();
+
+        el
+    }
+
+    #[test]
+    fn test_kss18_508_pairing() {
+        let modulus = BigUint::from_str_radix("639071312847174427285848912311753158158870483014393813840514407461331523641468938848397711573118531448868502119605932532664206464872389494020633119169309", 10).unwrap();
+        let base_field = new_field::<U512Repr>("639071312847174427285848912311753158158870483014393813840514407461331523641468938848397711573118531448868502119605932532664206464872389494020633119169309", 10).unwrap();
+        let scalar_field = new_field::<U384Repr>("114956185171424860315487103857992999808136385851884795612146756339980520332570865214732421395442158844820687159297", 10).unwrap();
+
+        // Fp3 is built with v^3 = 2
+        let fp_non_residue = Fp::from_repr(&base_field, U512Repr::from(2)).unwrap();
+
+        let mut extension_3 = Extension3 {
+            field: &base_field,
+            non_residue: fp_non_residue.clone(),
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)],
+            frobenius_coeffs_c2: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let (coeffs_1, coeffs_2) = frobenius_calculator_fp3(modulus.clone(), &extension_3).unwrap();
+        extension_3.frobenius_coeffs_c1 = coeffs_1;
+        extension_3.frobenius_coeffs_c2 = coeffs_2;
+
+        let one = Fp::one(&base_field);
+
+        // Fp6 is built with z^2 = v, so z^6 = 2
+        let mut fp3_non_residue = Fp3::zero(&extension_3); // non-residue is 2 + 0*v + 0*v^2
+        fp3_non_residue.c0 = fp_non_residue;
+
+        let f_c1 = [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field),
+                    Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)];
+
+        let mut extension_6 = Extension2Over3 {
+            non_residue: fp3_non_residue,
+            field: &extension_3,
+            frobenius_coeffs_c1: f_c1
+        };
+
+        let [c0, c1, c2, c3, c4, c5] = frobenius_calculator_fp6_as_2_over_3(modulus.clone(), &extension_6).unwrap();
+        extension_6.frobenius_coeffs_c1 = [c0.c0, c1.c0, c2.c0, c3.c0, c4.c0, c5.c0];
+
+        // Fp18 is built with w^3 = z
+        let mut fp6_non_residue = Fp6::zero(&extension_6); // non-residue is 0 + 1*z
+        fp6_non_residue.c1 = Fp3::one(&extension_3);
+
+        let f_c1 = [Fp3::zero(&extension_3), Fp3::zero(&extension_3), Fp3::zero(&extension_3),
+                    Fp3::zero(&extension_3), Fp3::zero(&extension_3), Fp3::zero(&extension_3),
+                    Fp3::zero(&extension_3), Fp3::zero(&extension_3), Fp3::zero(&extension_3),
+                    Fp3::zero(&extension_3), Fp3::zero(&extension_3), Fp3::zero(&extension_3),
+                    Fp3::zero(&extension_3), Fp3::zero(&extension_3), Fp3::zero(&extension_3),
+                    Fp3::zero(&extension_3), Fp3::zero(&extension_3), Fp3::zero(&extension_3)];
+
+        let mut extension_18 = Extension3Over6 {
+            non_residue: fp6_non_residue,
+            field: &extension_6,
+            frobenius_coeffs_c1: f_c1.clone(),
+            frobenius_coeffs_c2: f_c1,
+        };
+
+        let (coeffs_c1, coeffs_c2) = frobenius_calculator_fp18(modulus.clone(), &extension_18).unwrap();
+        extension_18.frobenius_coeffs_c1 = coeffs_c1;
+        extension_18.frobenius_coeffs_c2 = coeffs_c2;
+
+        // y^2 = x^3 + 2, D-type twist is y^2 = x^3 + 2/v = x^3 + v^2
+        let mut b_fp = one.clone();
+        b_fp.double();
+        let a_fp = Fp::zero(&base_field);
+
+        let mut b_fp3 = Fp3::zero(&extension_3);
+        b_fp3.c2 = one.clone();
+        let a_fp3 = Fp3::zero(&extension_3);
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let twist = WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
+
+        let p_x = BigUint::from_str_radix("503714828120319434040189539144944372059305113433122078054021327392937190342878704885365130664010199000588455985321583112460675840490046359697558248043907", 10).unwrap().to_bytes_be();
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = BigUint::from_str_radix("130814164621978849860397613729573148108251392292889172608133010339303228627219872894294068615762329490001906100877670429718267505249948784250912538830473", 10).unwrap().to_bytes_be();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        let q_x = fp3_from_decimal(&extension_3, [
+            "419586426437966961212333141296996200014902566232749815491476309896903423201956803665702451774914818702446038018328389832174369821212096050095358151293139",
+            "499437007339389811104035270073487017203978657606978627314067013808225048428056488034611834845013705269420224604447207961937601064830674539914986952986335",
+            "27762894608524625457335034658638848658167073497703097734562991301119782760982204085736321572116390617714224647426771495377303385661657816949847191013416"
+        ]);
+        let q_y = fp3_from_decimal(&extension_3, [
+            "522531028856157190285630088459628788510197517792432603615805116124332079734001812528689225617063910754049505495638603971496267968343900862394541463496727",
+            "448924577730483541537360310683519639397251702837274216245669420035617480762321159633940425420542164288759287099917285959789197029460111395740322502637664",
+            "15101683241885157950864754312070516460545463069620238441252364386797015180802451258030134202180907865017009812010109568680561870663046427819472921708639"
+        ]);
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        // x = -(2^64 + 2^51 - 2^46 - 2^12)
+        let engine = super::Kss18Instance {
+            x: vec![0x0007bffffffff000, 0x1],
+            x_is_negative: true,
+            twist_type: TwistType::D,
+            hard_part_lambdas: vec![
+                vec![3, 0, -434, -319, -62, -21, -15, -3],
+                vec![0, 1911, 1407, 273, 98, 70, 14],
+                vec![-6517, -4802, -931, -343, -245, -49],
+                vec![54, 0, -609, -450, -87, -35, -25, -5],
+                vec![0, 784, 581, 112, 49, 35, 7],
+                vec![343, 245, 49],
+            ],
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &twist,
+            fp3_extension: &extension_3,
+            fp6_extension: &extension_6,
+            fp18_extension: &extension_18,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        let expected = [
+            "0x024cf252ddf0e245e7c1fe3663d6adb2587b0e92b35ca6adbbc8c32071b7f896f6366e292c7b2360582999f9820c82ba3aec5807469d67675ea7874ebf1d6a6e",
+            "0x0c28df7ccdd582e1ea7ccca84812ee69b500207d8720c1cbb6ca45d9285db4d9d14362e97653a5787d9c8036211f75c96887c059b524532fc439593fff0a711a",
+            "0x032f9b28469f82217f9b07e15259d063c140708de4f71ae112ccc54cc7276c834c8908548fc0f2bc039bb4ec1478f6f6ef4bbc24d3eefe4a9a7c8fcf32690c30",
+            "0x0193d77cea90547546cbb902f585fdf6b0a8c99d0960d54fad40bc16e30adf00e68723824160b392cc11b69321b24124bbdf6dd42f94af41861b453f1e8368b6",
+            "0x0981aae012225901e7843783f9795af46036d2040338f42ecfac5eb476ffa9c38baf6d872dae2f21f755fafe0b83b408c5397a8d9f14c9fb621768292b1519aa",
+            "0x00425b019c4293b114e2e1b38f7c7888978db43257f1c1a06ae9166c01c8ea962ca5df87520bedeea994a3bbab94c0c57e69a94b3f24bfe5013591896af0319d",
+            "0x0a372ae38af570d10b6289aa1c4b5c02893d25debfd51d5fd8287c4de3d1f28687f5fa35ad52bf0cafa68af291fe37598370722e9e7292d04b26940e27b5c172",
+            "0x061b1877a88c41a4652b5d9de5a682c74e472f4ef8e457aaec32fa844886eaddb6765b61c498d3762dbf450fc8b1605217d2b57590adab1ab5cd50e03d14bab6",
+            "0x0bc1f9cfd264e1374a23dd8897256e324ad1180d8fa149003d8904414afeb04f12311d9e404bd4257a1d9c2545c52a6a634474a9d2590cad4325d0a4b5f91dea",
+            "0x07f522a02947eeb57fbc8301f065623c6310fc17a666128eab0ede5f297efbd713de90fc7a28e47c059b4fdd9440371d27a7a0c1cac73fd20c8f1e34e1407ecd",
+            "0x071e09222c9235d0509ed73da99529655709145aaa708c8aa7c4d869ff3a0ff18a7dff83cb506d2bc65664b8783ac10c16f9f00b6be34dcd4b30e3d1b657eb18",
+            "0x08ca599fe3ee166a2e8e959ec32cfa33cf353308545a415ee202e1466d093b4e34735e4c528bc6bd1637f61c097d9cd0c9c471282c52a46559b0db33d66ef70f",
+            "0x0469675dbf26fb2a21c70c7df2f7ddaee49aea30cc5fc7e71cf112d089aa726426b27c853c5450a48bde8f8f333d703cd76abede13a7216b98792a14c4fd7c45",
+            "0x00c152a5b865d7d13f3fb7d18d66c7f9254650f01b5e2b37b2cf802915133196c9a208ebc0020b303905e9511e1bd634b063ae6c2eb3786f9dfde46b3ff10aaf",
+            "0x07fad777d66abaa8fc871100fdc98959d9f97a8e54150e749774e2093a65638d42d0093a84fdfddca7dd171c6045a41b35ed96628cd2958b86a11ea1a04a838d",
+            "0x061cd6f525df383c81e399391c2333f800ead5cac6c768ca05241523807b7353d25965b68794a0e59ed387af0cc1a1a92a8942db097aba1c2cb98fd8a71f5a3f",
+            "0x052f53960c8c0741e71528eaad7e9f3397560fd7520c77ce17f54ba1b3e8f7fc32dcf3398bdefea18a5d471c74309fc2331ae773dc08dcf54cebd8783ac594f1",
+            "0x05e9d83e85a59cf59fd3270c884243b3dc49949f59789657ac9597fea8b3bf8761de37bf9ea6dafb5bf23a2c775c642bef6dfe0cdc72a9fce06eca9fb10dd131",
+        ];
+
+        let mut coeffs = vec![];
+        for fp6 in [&pairing_result.c0, &pairing_result.c1, &pairing_result.c2].iter() {
+            for fp3 in [&fp6.c0, &fp6.c1].iter() {
+                coeffs.push(format!("{}", fp3.c0));
+                coeffs.push(format!("{}", fp3.c1));
+                coeffs.push(format!("{}", fp3.c2));
+            }
+        }
+
+        assert_eq!(coeffs, expected);
+
+        // optimal ate over x + 3q - q^4 agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' * w^2, y' * w^3) as the Miller point, hard part of the
+        // final exponentiation raises it to sum(lambda_i(x) * q^i) * r/(q^6 - q^3 + 1)
+        let group_order = BigUint::from_str_radix("114956185171424860315487103857992999808136385851884795612146756339980520332570865214732421395442158844820687159297", 10).unwrap();
+        let reference = ReferencePairing::new(Fp18::zero(&extension_18), Fp18::one(&extension_18), modulus.clone(), group_order.clone(), 18).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp18::zero(&extension_18), Fp18::zero(&extension_18));
+        p_embedded.0.c0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0.c0 = p_y_affine;
+
+        let (q_x_affine, q_y_affine) = q.into_xy();
+        let mut q_untwisted = (Fp18::zero(&extension_18), Fp18::zero(&extension_18));
+        q_untwisted.0.c2.c0 = q_x_affine;
+        q_untwisted.1.c0.c1 = q_y_affine;
+
+        let x = -((BigInt::from(1u64) << 64) + BigInt::from(0x0007bffffffff000u64));
+        let modulus = BigInt::from_biguint(Sign::Plus, modulus);
+        let group_order = BigInt::from_biguint(Sign::Plus, group_order);
+        let exponent = reference.ate_exponent(&[x.clone(), BigInt::from(3u64), BigInt::zero(), BigInt::zero(), -BigInt::from(1u64)]).unwrap();
+
+        let mut hard_part = BigInt::zero();
+        for lambda in engine.hard_part_lambdas.iter().rev() {
+            hard_part = hard_part * &modulus + lambda.iter().rev().fold(BigInt::zero(), |acc, c| acc * &x + c);
+        }
+        let q_cubed = &modulus * &modulus * &modulus;
+        let (multiplier, rem) = hard_part.div_rem(&((&q_cubed * &q_cubed - &q_cubed + 1) / &group_order));
+        assert!(rem.is_zero());
+        let exponent = (BigInt::from_biguint(Sign::Plus, exponent) * multiplier).mod_floor(&group_order);
+
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent.to_biguint().unwrap())) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p.clone(), p_neg], &[q.clone(), q.clone()]).unwrap() == Fp18::one(&extension_18));
+    }
+}
@@ -0,0 +1,401 @@
+use crate::field::SizedPrimeField;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, MsbBitIterator};
+use crate::weierstrass::Group;
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::extension_towers::fp2::{Fp2, Extension2};
+use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+use crate::pairings::PairingEngine;
+
+pub struct MNT4Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    pub x: Vec<u64>,
+    pub x_is_negative: bool,
+    pub exp_w0: Vec<u64>,
+    pub exp_w1: Vec<u64>,
+    pub exp_w0_is_negative: bool,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+    pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
+    pub twist: Fp2<'a, FE, F>,
+    fp2_extension: &'a Extension2<'a, FE, F>,
+    fp4_extension: &'a Extension2Over2<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> MNT4Instance<'a, FE, F, GE, G> {
+    fn miller_loop<'b, I>(&self, i: I) -> Fp4<'a, FE, F>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
+                &'b TwistPoint<'a, FE, F, GE, G>)
+        >
+    {
+        let mut f = Fp4::one(self.fp4_extension);
+        for (p, q) in i.into_iter() {
+            if p.is_zero() || q.is_zero() {
+                continue;
+            }
+            f.mul_assign(&self.ate_pairing_loop(p, q));
+        }
+
+        f
+    }
+
+    fn ate_pairing_loop(
+        &self, 
+        point: &CurvePoint<'a, FE, F, GE, G>, 
+        twist_point: &TwistPoint<'a, FE, F, GE, G> 
+    ) -> Fp4<'a, FE, F> {
+        debug_assert!(point.is_normalized());
+        debug_assert!(twist_point.is_normalized());
+
+        let px = point.x.clone();
+        let py = point.y.clone();
+        let qx = twist_point.x.clone();
+        let qy = twist_point.y.clone();
+        let mut py_twist_squared = self.twist.clone();
+        py_twist_squared.square();
+        py_twist_squared.mul_by_fp(&py);
+
+        let mut old_rx;
+        let mut old_ry;
+        let mut rx = qx.clone();
+        let mut ry = qy.clone();
+
+        let mut f = Fp4::one(self.fp4_extension);
+
+        // The for loop is executed for all bits (EXCEPT the MSB itself) of
+        // ate loop count (skipping leading zeros) in MSB to LSB order
+        for bit in MsbBitIterator::new(&self.x).skip(1) {
+
+            old_rx = rx;
+            old_ry = ry;
+
+            let mut old_rx_square = old_rx.clone();
+            old_rx_square.square();
+            let mut old_rx_square_3 = old_rx_square.clone();
+            old_rx_square_3.double();
+            old_rx_square_3.add_assign(&old_rx_square);
+            let mut old_rx_square_3_a = old_rx_square_3.clone();
+            old_rx_square_3_a.add_assign(&self.curve_twist.a);
+            let mut old_ry_double_inverse = old_ry.clone();
+            old_ry_double_inverse.double();
+            let old_ry_double_inverse = old_ry_double_inverse.inverse().unwrap();
+
+            let mut gamma = old_rx_square_3_a.clone();
+            gamma.mul_assign(&old_ry_double_inverse);
+
+            let mut gamma_twist = gamma.clone();
+            gamma_twist.mul_assign(&self.twist);
+
+            let mut gamma_old_rx = gamma.clone();
+            gamma_old_rx.mul_assign(&old_rx);
+
+            let mut gamma_twist_px = gamma_twist.clone();
+            gamma_twist_px.mul_by_fp(&px);
+
+            let x = py_twist_squared.clone();
+
+            let mut y = gamma_old_rx.clone();
+            y.sub_assign(&old_ry);
+            y.sub_assign(&gamma_twist_px);
+
+            let ell_rr_at_p = Fp4 {
+                c0: x,
+                c1: y,
+                extension_field: self.fp4_extension
+            };
+
+            rx = gamma.clone();
+            rx.square();
+            let mut t0 = old_rx.clone();
+            t0.double();
+            rx.sub_assign(&t0);
+
+            let mut t0 = old_rx.clone();
+            t0.sub_assign(&rx);
+
+            ry = gamma.clone();
+            ry.mul_assign(&t0);
+            ry.sub_assign(&old_ry);
+
+            f.square();
+            f.mul_assign(&ell_rr_at_p);
+
+            if bit {
+                old_rx = rx.clone();
+                old_ry = ry.clone();
+
+                let mut t0 = old_ry.clone();
+                t0.sub_assign(&qy);
+
+                let mut t1 = old_rx.clone();
+                t1.sub_assign(&qx);
+                let t1 = t1.inverse().unwrap();
+
+                let mut gamma = t0;
+                gamma.mul_assign(&t1);
+                let mut gamma_twist = gamma.clone();
+                gamma_twist.mul_assign(&self.twist);
+                let mut gamma_qx = gamma.clone();
+                gamma_qx.mul_assign(&qx);
+                let mut gamma_twist_px = gamma_twist.clone();
+                gamma_twist_px.mul_by_fp(&px);
+
+                let x = py_twist_squared.clone();
+                let mut y = gamma_qx.clone();
+                y.sub_assign(&qy);
+                y.sub_assign(&gamma_twist_px);
+
+                let ell_rq_at_p = Fp4 {
+                    c0: x,
+                    c1: y,
+                    extension_field: self.fp4_extension
+                };
+
+                rx = gamma.clone();
+                rx.square();
+                rx.sub_assign(&old_rx);
+                rx.sub_assign(&qx);
+
+                ry = old_rx.clone();
+                ry.sub_assign(&rx);
+                ry.mul_assign(&gamma);
+                ry.sub_assign(&old_ry);
+
+                f.mul_assign(&ell_rq_at_p);
+            }
+        }
+
+        if self.x_is_negative {
+            // f^(q^2) = f^(-1) after the final exponentiation
+            f.conjugate();
+        }
+
+        f
+    }
+
+    fn final_exponentiation_part_one(&self, elt: &Fp4<'a, FE, F>, elt_inv: &Fp4<'a, FE, F>) -> Fp4<'a, FE, F> {
+        // (q^2-1)
+
+        // elt_q2 = elt^(q^2)
+        let mut elt_q2 = elt.clone();
+        elt_q2.conjugate();
+        // elt_q2_over_elt = elt^(q^2-1)
+        elt_q2.mul_assign(elt_inv);
+
+        elt_q2
+    }
+
+    fn final_exponentiation_part_two(&self, elt: &Fp4<'a, FE, F>, elt_inv: &Fp4<'a, FE, F>) -> Fp4<'a, FE, F> {
+        let mut elt_q = elt.clone();
+        elt_q.frobenius_map(1);
+
+        let mut w1_part = elt_q.cyclotomic_exp(&self.exp_w1);
+        let w0_part = match self.exp_w0_is_negative {
+            true => elt_inv.cyclotomic_exp(&self.exp_w0),
+            false => elt.cyclotomic_exp(&self.exp_w0),
+        };
+
+        w1_part.mul_assign(&w0_part);
+
+        w1_part
+    }
+}
+
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for MNT4Instance<'a, FE, F, GE, G> {
+    type PairingResult = Fp4<'a, FE, F>;
+    type G1 = CurvePoint<'a, FE, F, GE, G>;
+    type G2 = TwistPoint<'a, FE, F, GE, G>;
+
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &Fp4<'a, FE, F>) -> Option<Fp4<'a, FE, F>> {
+        let value_inv = f.inverse()?;
+
+        Some(self.final_exponentiation_part_one(f, &value_inv))
+    }
+
+    fn final_exponentiation_hard_part(&self, elt: &Fp4<'a, FE, F>) -> Fp4<'a, FE, F> {
+        // elt is in the cyclotomic subgroup, so inverse is just a conjugate
+        let mut elt_inv = elt.clone();
+        elt_inv.conjugate();
+
+        self.final_exponentiation_part_two(elt, &elt_inv)
+    }
+
+    fn target_one(&self) -> Fp4<'a, FE, F> {
+        Fp4::one(self.fp4_extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, BigInt, Sign};
+    use crate::field::{U320Repr, new_field, biguint_to_u64_vec};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement};
+    use crate::extension_towers::fp2::{Fp2, Extension2};
+    use crate::extension_towers::fp4_as_2_over_2::{Fp4, Extension2Over2};
+    use num_traits::Num;
+    use crate::pairings::{frobenius_calculator_fp2, frobenius_calculator_fp4_as_2_over_2};
+    use crate::weierstrass::{Group};
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::twist::{TwistPoint, WeierstrassCurveTwist};
+    use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+
+    #[test]
+    fn test_mnt4_298_pairing() {
+        let modulus = BigUint::from_str_radix("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081", 10).unwrap();
+        let base_field = new_field::<U320Repr>("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081", 10).unwrap();
+        let nonres_repr = U320Repr::from(17);
+        let fp_non_residue = Fp::from_repr(&base_field, nonres_repr).unwrap();
+
+        let mut extension_2 = Extension2 {
+            field: &base_field,
+            non_residue: fp_non_residue,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp2(&extension_2).unwrap();
+        extension_2.frobenius_coeffs_c1 = coeffs;
+
+        let one = Fp::one(&base_field);
+
+        let mut fp2_non_residue = Fp2::zero(&extension_2); // non-residue is 0 + 1*u
+        fp2_non_residue.c1 = one.clone();
+
+        let mut extension_4 = Extension2Over2 {
+            non_residue: fp2_non_residue,
+            field: &extension_2,
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let coeffs = frobenius_calculator_fp4_as_2_over_2(modulus.clone(), &extension_4).unwrap();
+        extension_4.frobenius_coeffs_c1 = coeffs;
+
+        let b_fp = BigUint::from_str_radix("423894536526684178289416011533888240029318103673896002803341544124054745019340795360841685", 10).unwrap().to_bytes_be();
+        let b_fp = Fp::from_be_bytes(&base_field, &b_fp, true).unwrap();
+
+        let a_fp = Fp::from_repr(&base_field, U320Repr::from(2)).unwrap();
+
+        let mut twist = Fp2::zero(&extension_2);
+        twist.c1 = one.clone();
+
+        let mut twist_squared = twist.clone();
+        twist_squared.square();
+
+        let mut twist_cubed = twist_squared.clone();
+        twist_cubed.mul_assign(&twist);
+
+        let mut a_fp2 = twist_squared.clone();
+        a_fp2.mul_by_fp(&a_fp);
+
+        let mut b_fp2 = twist_cubed.clone();
+        b_fp2.mul_by_fp(&b_fp);
+
+        let scalar_field = new_field::<U320Repr>("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137", 10).unwrap();
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_2, a_fp2, b_fp2);
+
+        let p_x = BigUint::from_str_radix("60760244141852568949126569781626075788424196370144486719385562369396875346601926534016838", 10).unwrap().to_bytes_be();
+        let p_y = BigUint::from_str_radix("363732850702582978263902770815145784459747722357071843971107674179038674942891694705904306", 10).unwrap().to_bytes_be();
+
+        let q_x_0 = BigUint::from_str_radix("438374926219350099854919100077809681842783509163790991847867546339851681564223481322252708", 10).unwrap().to_bytes_be();
+        let q_x_1 = BigUint::from_str_radix("37620953615500480110935514360923278605464476459712393277679280819942849043649216370485641", 10).unwrap().to_bytes_be();
+        let q_y_0 = BigUint::from_str_radix("37437409008528968268352521034936931842973546441370663118543015118291998305624025037512482", 10).unwrap().to_bytes_be();
+        let q_y_1 = BigUint::from_str_radix("424621479598893882672393190337420680597584695892317197646113820787463109735345923009077489", 10).unwrap().to_bytes_be();
+
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        let mut q_x = Fp2::zero(&extension_2);
+        q_x.c0 = Fp::from_be_bytes(&base_field, &q_x_0, true).unwrap();
+        q_x.c1 = Fp::from_be_bytes(&base_field, &q_x_1, true).unwrap();
+
+        let mut q_y = Fp2::zero(&extension_2);
+        q_y.c0 = Fp::from_be_bytes(&base_field, &q_y_0, true).unwrap();
+        q_y.c1 = Fp::from_be_bytes(&base_field, &q_y_1, true).unwrap();
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        // ate loop count is t - 1, final exponentiation is (q^2 + 1)/r = w1 * q + w0
+        let x = BigUint::from_str_radix("689871209842287392837045615510547309923794944", 10).unwrap();
+        let w0 = BigUint::from_str_radix("689871209842287392837045615510547309923794945", 10).unwrap();
+        let w1 = BigUint::from_str_radix("1", 10).unwrap();
+
+        let engine = super::MNT4Instance {
+            x: biguint_to_u64_vec(x.clone()),
+            x_is_negative: false,
+            exp_w0: biguint_to_u64_vec(w0),
+            exp_w1: biguint_to_u64_vec(w1),
+            exp_w0_is_negative: false,
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &curve_twist,
+            twist,
+            fp2_extension: &extension_2,
+            fp4_extension: &extension_4,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        assert!(format!("{}", pairing_result.c0.c0) == "0x000003653498b90d54a52c420cc4a73ad1882feb23bf2ae451037a96e17babd70402dd237238b101");
+        assert!(format!("{}", pairing_result.c0.c1) == "0x000000cd0a4994729a71440144fedc4378511a7febdf4cdb0499253bcbea9e023c6cfa9cf9682784");
+        assert!(format!("{}", pairing_result.c1.c0) == "0x000002532341e5b711a9f8f7049a99af28177e51d7a0c384d19cb7547352a7e65c44417babfe0089");
+        assert!(format!("{}", pairing_result.c1.c1) == "0x0000030c15f867b221786f818a8e96ffa041ea4366fee9bdc9b2d845d6a9b9aded3d34d24b1a34b8");
+
+        // ate pairing over x = q mod r agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' / u, y' * w / u^2) as the Miller point
+        let group_order = BigUint::from_str_radix("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137", 10).unwrap();
+        let mut a_embedded = Fp4::zero(&extension_4);
+        a_embedded.c0.c0 = curve.a.clone();
+        let reference = ReferencePairing::new(a_embedded, Fp4::one(&extension_4), modulus, group_order, 4).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp4::zero(&extension_4), Fp4::zero(&extension_4));
+        p_embedded.0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0 = p_y_affine;
+
+        let twist_inv = engine.twist.inverse().unwrap();
+        let mut twist_squared_inv = twist_inv.clone();
+        twist_squared_inv.square();
+
+        let (mut q_x_affine, mut q_y_affine) = q.into_xy();
+        q_x_affine.mul_assign(&twist_inv);
+        q_y_affine.mul_assign(&twist_squared_inv);
+        let mut q_untwisted = (Fp4::zero(&extension_4), Fp4::zero(&extension_4));
+        q_untwisted.0.c0 = q_x_affine;
+        q_untwisted.1.c1 = q_y_affine;
+
+        let exponent = reference.ate_exponent(&[BigInt::from_biguint(Sign::Plus, x), -BigInt::from(1u64)]).unwrap();
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p, p_neg], &[q.clone(), q]).unwrap() == Fp4::one(&extension_4));
+    }
+}
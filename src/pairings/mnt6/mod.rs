@@ -0,0 +1,420 @@
+use crate::field::SizedPrimeField;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, MsbBitIterator};
+use crate::weierstrass::Group;
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use crate::weierstrass::cubic_twist::{WeierstrassCurveTwist, TwistPoint};
+use crate::extension_towers::fp3::{Fp3, Extension3};
+use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+use crate::pairings::PairingEngine;
+
+pub struct MNT6Instance<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    pub x: Vec<u64>,
+    pub x_is_negative: bool,
+    pub exp_w0: Vec<u64>,
+    pub exp_w1: Vec<u64>,
+    pub exp_w0_is_negative: bool,
+    pub base_field: &'a F,
+    pub curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+    pub curve_twist: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>,
+    pub twist: Fp3<'a, FE, F>,
+    fp3_extension: &'a Extension3<'a, FE, F>,
+    fp6_extension: &'a Extension2Over3<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> MNT6Instance<'a, FE, F, GE, G> {
+    fn miller_loop<'b, I>(&self, i: I) -> Fp6<'a, FE, F>
+    where 'a: 'b,
+        I: IntoIterator<
+            Item = &'b (&'b CurvePoint<'a, FE, F, GE, G>, 
+                &'b TwistPoint<'a, FE, F, GE, G>)
+        >
+    {
+        let mut f = Fp6::one(self.fp6_extension);
+        for (p, q) in i.into_iter() {
+            if p.is_zero() || q.is_zero() {
+                continue;
+            }
+            f.mul_assign(&self.ate_pairing_loop(p, q));
+        }
+
+        f
+    }
+
+    fn ate_pairing_loop(
+        &self, 
+        point: &CurvePoint<'a, FE, F, GE, G>, 
+        twist_point: &TwistPoint<'a, FE, F, GE, G> 
+    ) -> Fp6<'a, FE, F> {
+        debug_assert!(point.is_normalized());
+        debug_assert!(twist_point.is_normalized());
+
+        let px = point.x.clone();
+        let py = point.y.clone();
+        let qx = twist_point.x.clone();
+        let qy = twist_point.y.clone();
+        let mut py_twist_squared = self.twist.clone();
+        py_twist_squared.square();
+        py_twist_squared.mul_by_fp(&py);
+
+        let mut old_rx;
+        let mut old_ry;
+        let mut rx = qx.clone();
+        let mut ry = qy.clone();
+
+        let mut f = Fp6::one(self.fp6_extension);
+
+        // The for loop is executed for all bits (EXCEPT the MSB itself) of
+        // ate loop count (skipping leading zeros) in MSB to LSB order
+        for bit in MsbBitIterator::new(&self.x).skip(1) {
+
+            old_rx = rx;
+            old_ry = ry;
+
+            let mut old_rx_square = old_rx.clone();
+            old_rx_square.square();
+            let mut old_rx_square_3 = old_rx_square.clone();
+            old_rx_square_3.double();
+            old_rx_square_3.add_assign(&old_rx_square);
+            let mut old_rx_square_3_a = old_rx_square_3.clone();
+            old_rx_square_3_a.add_assign(&self.curve_twist.a);
+            let mut old_ry_double_inverse = old_ry.clone();
+            old_ry_double_inverse.double();
+            let old_ry_double_inverse = old_ry_double_inverse.inverse().unwrap();
+
+            let mut gamma = old_rx_square_3_a.clone();
+            gamma.mul_assign(&old_ry_double_inverse);
+
+            let mut gamma_twist = gamma.clone();
+            gamma_twist.mul_assign(&self.twist);
+
+            let mut gamma_old_rx = gamma.clone();
+            gamma_old_rx.mul_assign(&old_rx);
+
+            let mut gamma_twist_px = gamma_twist.clone();
+            gamma_twist_px.mul_by_fp(&px);
+
+            let x = py_twist_squared.clone();
+
+            let mut y = gamma_old_rx.clone();
+            y.sub_assign(&old_ry);
+            y.sub_assign(&gamma_twist_px);
+
+            let ell_rr_at_p = Fp6 {
+                c0: x,
+                c1: y,
+                extension_field: self.fp6_extension
+            };
+
+            rx = gamma.clone();
+            rx.square();
+            let mut t0 = old_rx.clone();
+            t0.double();
+            rx.sub_assign(&t0);
+
+            let mut t0 = old_rx.clone();
+            t0.sub_assign(&rx);
+
+            ry = gamma.clone();
+            ry.mul_assign(&t0);
+            ry.sub_assign(&old_ry);
+
+            f.square();
+            f.mul_assign(&ell_rr_at_p);
+
+            if bit {
+                old_rx = rx.clone();
+                old_ry = ry.clone();
+
+                let mut t0 = old_ry.clone();
+                t0.sub_assign(&qy);
+
+                let mut t1 = old_rx.clone();
+                t1.sub_assign(&qx);
+                let t1 = t1.inverse().unwrap();
+
+                let mut gamma = t0;
+                gamma.mul_assign(&t1);
+                let mut gamma_twist = gamma.clone();
+                gamma_twist.mul_assign(&self.twist);
+                let mut gamma_qx = gamma.clone();
+                gamma_qx.mul_assign(&qx);
+                let mut gamma_twist_px = gamma_twist.clone();
+                gamma_twist_px.mul_by_fp(&px);
+
+                let x = py_twist_squared.clone();
+                let mut y = gamma_qx.clone();
+                y.sub_assign(&qy);
+                y.sub_assign(&gamma_twist_px);
+
+                let ell_rq_at_p = Fp6 {
+                    c0: x,
+                    c1: y,
+                    extension_field: self.fp6_extension
+                };
+
+                rx = gamma.clone();
+                rx.square();
+                rx.sub_assign(&old_rx);
+                rx.sub_assign(&qx);
+
+                ry = old_rx.clone();
+                ry.sub_assign(&rx);
+                ry.mul_assign(&gamma);
+                ry.sub_assign(&old_ry);
+
+                f.mul_assign(&ell_rq_at_p);
+            }
+        }
+
+        if self.x_is_negative {
+            // f^(q^3) = f^(-1) after the final exponentiation
+            f.conjugate();
+        }
+
+        f
+    }
+
+    fn final_exponentiation_part_one(&self, elt: &Fp6<'a, FE, F>, elt_inv: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
+        // (q^3-1)*(q+1)
+
+        // elt_q3 = elt^(q^3)
+        let mut elt_q3 = elt.clone();
+        elt_q3.frobenius_map(3);
+        // elt_q3_over_elt = elt^(q^3-1)
+        let mut elt_q3_over_elt = elt_q3;
+        elt_q3_over_elt.mul_assign(elt_inv);
+        // alpha = elt^((q^3-1) * q)
+        let mut alpha = elt_q3_over_elt.clone();
+        alpha.frobenius_map(1);
+        // beta = elt^((q^3-1)*(q+1)
+        alpha.mul_assign(&elt_q3_over_elt);
+
+        alpha
+    }
+
+    fn final_exponentiation_part_two(&self, elt: &Fp6<'a, FE, F>, elt_inv: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
+        let mut elt_q = elt.clone();
+        elt_q.frobenius_map(1);
+
+        let mut w1_part = elt_q.cyclotomic_exp(&self.exp_w1);
+        let w0_part = match self.exp_w0_is_negative {
+            true => elt_inv.cyclotomic_exp(&self.exp_w0),
+            false => elt.cyclotomic_exp(&self.exp_w0),
+        };
+
+        w1_part.mul_assign(&w0_part);
+
+        w1_part
+    }
+}
+
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> PairingEngine for MNT6Instance<'a, FE, F, GE, G> {
+    type PairingResult = Fp6<'a, FE, F>;
+    type G1 = CurvePoint<'a, FE, F, GE, G>;
+    type G2 = TwistPoint<'a, FE, F, GE, G>;
+
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult {
+        self.miller_loop(pairs)
+    }
+
+    fn final_exponentiation_easy_part(&self, f: &Fp6<'a, FE, F>) -> Option<Fp6<'a, FE, F>> {
+        let value_inv = f.inverse()?;
+
+        Some(self.final_exponentiation_part_one(f, &value_inv))
+    }
+
+    fn final_exponentiation_hard_part(&self, elt: &Fp6<'a, FE, F>) -> Fp6<'a, FE, F> {
+        // elt is in the cyclotomic subgroup, so inverse is just a conjugate
+        let mut elt_inv = elt.clone();
+        elt_inv.conjugate();
+
+        self.final_exponentiation_part_two(elt, &elt_inv)
+    }
+
+    fn target_one(&self) -> Fp6<'a, FE, F> {
+        Fp6::one(self.fp6_extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigUint, BigInt, Sign};
+    use crate::field::{U320Repr, new_field, biguint_to_u64_vec};
+    use crate::fp::Fp;
+    use crate::traits::{FieldElement};
+    use crate::extension_towers::fp3::{Fp3, Extension3};
+    use crate::extension_towers::fp6_as_2_over_3::{Fp6, Extension2Over3};
+    use num_traits::Num;
+    use crate::pairings::{frobenius_calculator_fp3, frobenius_calculator_fp6_as_2_over_3};
+    use crate::weierstrass::{Group};
+    use crate::weierstrass::curve::{CurvePoint, WeierstrassCurve};
+    use crate::weierstrass::cubic_twist::{TwistPoint, WeierstrassCurveTwist};
+    use crate::pairings::{PairingEngine};
+    use crate::pairings::reference::ReferencePairing;
+
+    #[test]
+    fn test_mnt6_298_pairing() {
+        let modulus = BigUint::from_str_radix("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137", 10).unwrap();
+        let base_field = new_field::<U320Repr>("475922286169261325753349249653048451545124878552823515553267735739164647307408490559963137", 10).unwrap();
+        let nonres_repr = U320Repr::from(5);
+        let fp_non_residue = Fp::from_repr(&base_field, nonres_repr).unwrap();
+
+        let mut extension_3 = Extension3 {
+            field: &base_field,
+            non_residue: fp_non_residue.clone(),
+            frobenius_coeffs_c1: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)],
+            frobenius_coeffs_c2: [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)]
+        };
+
+        let (coeffs_1, coeffs_2) = frobenius_calculator_fp3(modulus.clone(), &extension_3).unwrap();
+        extension_3.frobenius_coeffs_c1 = coeffs_1;
+        extension_3.frobenius_coeffs_c2 = coeffs_2;
+
+        let one = Fp::one(&base_field);
+
+        let mut fp3_non_residue = Fp3::zero(&extension_3); // non-residue is 5 + 0*u + 0*u^2
+        fp3_non_residue.c0 = fp_non_residue;
+
+        let f_c1 = [Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field),
+                    Fp::zero(&base_field), Fp::zero(&base_field), Fp::zero(&base_field)];
+
+        let mut extension_6 = Extension2Over3 {
+            non_residue: fp3_non_residue,
+            field: &extension_3,
+            frobenius_coeffs_c1: f_c1
+        };
+
+        let [c0, c1, c2, c3, c4, c5] = frobenius_calculator_fp6_as_2_over_3(modulus.clone(), &extension_6).unwrap();
+        extension_6.frobenius_coeffs_c1 = [c0.c0, c1.c0, c2.c0, c3.c0, c4.c0, c5.c0];
+
+        let b_fp = BigUint::from_str_radix("106700080510851735677967319632585352256454251201367587890185989362936000262606668469523074", 10).unwrap().to_bytes_be();
+        let b_fp = Fp::from_be_bytes(&base_field, &b_fp, true).unwrap();
+
+        let a_fp = Fp::from_repr(&base_field, U320Repr::from(11)).unwrap();
+
+        let mut twist = Fp3::zero(&extension_3);
+        twist.c1 = one.clone();
+
+        let mut twist_squared = twist.clone();
+        twist_squared.square();
+
+        let mut twist_cubed = twist_squared.clone();
+        twist_cubed.mul_assign(&twist);
+
+        let mut a_fp3 = twist_squared.clone();
+        a_fp3.mul_by_fp(&a_fp);
+
+        let mut b_fp3 = twist_cubed.clone();
+        b_fp3.mul_by_fp(&b_fp);
+
+        let scalar_field = new_field::<U320Repr>("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081", 10).unwrap();
+
+        let curve = WeierstrassCurve::new(&scalar_field, a_fp, b_fp);
+        let curve_twist = WeierstrassCurveTwist::new(&scalar_field, &extension_3, a_fp3, b_fp3);
+
+        let p_x = BigUint::from_str_radix("336685752883082228109289846353937104185698209371404178342968838739115829740084426881123453", 10).unwrap().to_bytes_be();
+        let p_y = BigUint::from_str_radix("402596290139780989709332707716568920777622032073762749862342374583908837063963736098549800", 10).unwrap().to_bytes_be();
+
+        let q_x_0 = BigUint::from_str_radix("421456435772811846256826561593908322288509115489119907560382401870203318738334702321297427", 10).unwrap().to_bytes_be();
+        let q_x_1 = BigUint::from_str_radix("103072927438548502463527009961344915021167584706439945404959058962657261178393635706405114", 10).unwrap().to_bytes_be();
+        let q_x_2 = BigUint::from_str_radix("143029172143731852627002926324735183809768363301149009204849580478324784395590388826052558", 10).unwrap().to_bytes_be();
+
+        let q_y_0 = BigUint::from_str_radix("464673596668689463130099227575639512541218133445388869383893594087634649237515554342751377", 10).unwrap().to_bytes_be();
+        let q_y_1 = BigUint::from_str_radix("100642907501977375184575075967118071807821117960152743335603284583254620685343989304941678", 10).unwrap().to_bytes_be();
+        let q_y_2 = BigUint::from_str_radix("123019855502969896026940545715841181300275180157288044663051565390506010149881373807142903", 10).unwrap().to_bytes_be();
+
+        let p_x = Fp::from_be_bytes(&base_field, &p_x, true).unwrap();
+        let p_y = Fp::from_be_bytes(&base_field, &p_y, true).unwrap();
+
+        let mut q_x = Fp3::zero(&extension_3);
+        q_x.c0 = Fp::from_be_bytes(&base_field, &q_x_0, true).unwrap();
+        q_x.c1 = Fp::from_be_bytes(&base_field, &q_x_1, true).unwrap();
+        q_x.c2 = Fp::from_be_bytes(&base_field, &q_x_2, true).unwrap();
+
+        let mut q_y = Fp3::zero(&extension_3);
+        q_y.c0 = Fp::from_be_bytes(&base_field, &q_y_0, true).unwrap();
+        q_y.c1 = Fp::from_be_bytes(&base_field, &q_y_1, true).unwrap();
+        q_y.c2 = Fp::from_be_bytes(&base_field, &q_y_2, true).unwrap();
+
+        let p = CurvePoint::point_from_xy(&curve, p_x, p_y);
+        let q = TwistPoint::point_from_xy(&curve_twist, q_x, q_y);
+
+        assert!(p.check_on_curve());
+        assert!(q.check_on_curve());
+
+        // ate loop count is t - 1 and it's negative for MNT6-298,
+        // final exponentiation is (q^2 - q + 1)/r = w1 * q + w0
+        let x = BigUint::from_str_radix("689871209842287392837045615510547309923794944", 10).unwrap();
+        let w0 = BigUint::from_str_radix("689871209842287392837045615510547309923794944", 10).unwrap();
+        let w1 = BigUint::from_str_radix("1", 10).unwrap();
+
+        let engine = super::MNT6Instance {
+            x: biguint_to_u64_vec(x.clone()),
+            x_is_negative: true,
+            exp_w0: biguint_to_u64_vec(w0),
+            exp_w1: biguint_to_u64_vec(w1),
+            exp_w0_is_negative: true,
+            base_field: &base_field,
+            curve: &curve,
+            curve_twist: &curve_twist,
+            twist,
+            fp3_extension: &extension_3,
+            fp6_extension: &extension_6,
+        };
+
+        let pairing_result = engine.pair(std::slice::from_ref(&p), std::slice::from_ref(&q)).unwrap();
+
+        assert!(format!("{}", pairing_result.c0.c0) == "0x0000014ac12149eebffe74a1c75a7225deb91ca243c49eef01392080ff519ab6209431f81b50ec03");
+        assert!(format!("{}", pairing_result.c0.c1) == "0x000001ba8ab5bc93186b5bc2b1936fee360528228ab953fbce3c7b84f71d6c0e87b293d0de36eb93");
+        assert!(format!("{}", pairing_result.c0.c2) == "0x00000323a5728ce32f5a04635ca9f84857882e9c13a2b415a021985921c79f303f1f0b69557c5c3d");
+        assert!(format!("{}", pairing_result.c1.c0) == "0x0000032e067f62de41a786c2a43da960855694f3e0da14a964377a32ddad42cf9dd6b80bdc8d4300");
+        assert!(format!("{}", pairing_result.c1.c1) == "0x000000bf02fd56dcd4f6b1d132c8b56a9f8801696d77cdb911a35335360f07eba30bc3083ecaa394");
+        assert!(format!("{}", pairing_result.c1.c2) == "0x0000028a449b7699751b6bf17003c141307311241614b886c0fb6ffaf5b39896e182bddd85859e9c");
+
+        // ate pairing over -x = q mod r agrees with the reference Tate pairing evaluated
+        // with untwisted Q = (x' / u, y' * w / u^2) as the Miller point
+        let group_order = BigUint::from_str_radix("475922286169261325753349249653048451545124879242694725395555128576210262817955800483758081", 10).unwrap();
+        let mut a_embedded = Fp6::zero(&extension_6);
+        a_embedded.c0.c0 = curve.a.clone();
+        let reference = ReferencePairing::new(a_embedded, Fp6::one(&extension_6), modulus, group_order, 6).unwrap();
+
+        let (p_x_affine, p_y_affine) = p.into_xy();
+        let mut p_embedded = (Fp6::zero(&extension_6), Fp6::zero(&extension_6));
+        p_embedded.0.c0.c0 = p_x_affine;
+        p_embedded.1.c0.c0 = p_y_affine;
+
+        let twist_inv = engine.twist.inverse().unwrap();
+        let mut twist_squared_inv = twist_inv.clone();
+        twist_squared_inv.square();
+
+        let (mut q_x_affine, mut q_y_affine) = q.into_xy();
+        q_x_affine.mul_assign(&twist_inv);
+        q_y_affine.mul_assign(&twist_squared_inv);
+        let mut q_untwisted = (Fp6::zero(&extension_6), Fp6::zero(&extension_6));
+        q_untwisted.0.c0 = q_x_affine;
+        q_untwisted.1.c1 = q_y_affine;
+
+        let exponent = reference.ate_exponent(&[BigInt::from_biguint(Sign::Minus, x), -BigInt::from(1u64)]).unwrap();
+        let tate = reference.tate(&q_untwisted, &p_embedded).unwrap();
+        assert!(tate.pow(biguint_to_u64_vec(exponent)) == pairing_result);
+
+        // e(2P, Q) = e(P, 2Q) = e(P, Q)^2
+        let mut p2 = p.mul([2u64]);
+        p2.normalize();
+        let mut q2 = q.mul([2u64]);
+        q2.normalize();
+
+        let mut expected = pairing_result.clone();
+        expected.square();
+
+        assert!(engine.pair(&[p2], std::slice::from_ref(&q)).unwrap() == expected);
+        assert!(engine.pair(std::slice::from_ref(&p), &[q2]).unwrap() == expected);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let mut p_neg = p.clone();
+        p_neg.negate();
+        assert!(engine.pair(&[p, p_neg], &[q.clone(), q]).unwrap() == Fp6::one(&extension_6));
+    }
+}
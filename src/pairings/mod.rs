@@ -8,24 +8,84 @@ use crate::weierstrass::curve::CurvePoint;
 use crate::weierstrass::twist::TwistPoint;
 use crate::extension_towers::{fp2::Fp2, fp2::Extension2};
 use crate::extension_towers::{fp3::Fp3, fp3::Extension3};
+use crate::extension_towers::fp4_as_2_over_2::{self, Fp4};
 use crate::extension_towers::fp6_as_2_over_3;
 use crate::extension_towers::fp6_as_3_over_2;
+use crate::extension_towers::fp8_as_2_over_4;
 use crate::extension_towers::{fp12_as_2_over3_over_2::Fp12, fp12_as_2_over3_over_2::Extension2Over3Over2};
+use crate::extension_towers::fp12_as_3_over_4;
+use crate::extension_towers::fp18_as_3_over_6;
+use crate::extension_towers::fp24_as_2_over3_over_4;
 use num_bigint::BigUint;
 use num_traits::FromPrimitive;
 use num_integer::Integer;
 use num_traits::Zero;
 
 pub mod bls12;
+pub mod bls24;
 pub mod bn;
+pub mod bw6;
 pub mod cp;
+pub mod gt;
+pub mod kss18;
+pub mod mnt4;
+pub mod mnt6;
+pub mod reference;
 
 pub trait PairingEngine: Sized {
     type PairingResult: FieldElement;
     type G1: Group;
     type G2: Group;
 
-    fn pair<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2]) -> Option<Self::PairingResult>;
+    /// Product of the Miller loops over all the pairs, pairs with a point at
+    /// infinity are skipped
+    fn multi_miller_loop(&self, pairs: &[(&Self::G1, &Self::G2)]) -> Self::PairingResult;
+
+    /// Raises to the power (q^k - 1)/Phi_k(q), so the result is in the cyclotomic
+    /// subgroup. Returns `None` if `f` is not invertible
+    fn final_exponentiation_easy_part(&self, f: &Self::PairingResult) -> Option<Self::PairingResult>;
+
+    /// Raises an element of the cyclotomic subgroup to the power Phi_k(q)/r
+    /// or to it's multiple that is coprime with r
+    fn final_exponentiation_hard_part(&self, f: &Self::PairingResult) -> Self::PairingResult;
+
+    fn target_one(&self) -> Self::PairingResult;
+
+    fn final_exponentiation(&self, f: &Self::PairingResult) -> Option<Self::PairingResult> {
+        let f = self.final_exponentiation_easy_part(f)?;
+
+        Some(self.final_exponentiation_hard_part(&f))
+    }
+
+    /// Product of pairings of `points[i]` and `twists[i]`, returns `None`
+    /// if the slices have different lengths
+    fn pair<'b> (&self, points: &'b [Self::G1], twists: &'b [Self::G2]) -> Option<Self::PairingResult> {
+        if points.len() != twists.len() {
+            return None;
+        }
+
+        let pairs: Vec<_> = points.iter().zip(twists.iter()).collect();
+        let loop_result = self.multi_miller_loop(&pairs[..]);
+
+        self.final_exponentiation(&loop_result)
+    }
+
+    /// Checks that the product of pairings is one using a single final exponentiation.
+    /// Pairs with a point at infinity are skipped, so an empty product is one. Returns
+    /// an error if the Miller loop result is not invertible
+    fn pairing_check(&self, pairs: &[(Self::G1, Self::G2)]) -> Result<bool, ()> {
+        let pairs: Vec<_> = pairs.iter().map(|(p, q)| (p, q)).collect();
+        let loop_result = self.multi_miller_loop(&pairs[..]);
+        let f = self.final_exponentiation_easy_part(&loop_result).ok_or(())?;
+
+        // hard part maps one to one, so there is no need to compute it
+        let one = self.target_one();
+        if f == one {
+            return Ok(true);
+        }
+
+        Ok(self.final_exponentiation_hard_part(&f) == one)
+    }
 }
 
 pub fn frobenius_calculator_fp2<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
@@ -100,6 +160,48 @@ pub fn frobenius_calculator_fp3<'a, FE: ElementRepr, F: SizedPrimeField<Repr = F
         Ok(([f_0, f_1, f_2], [f_0_c2, f_1_c2, f_2_c2]))
 }
 
+pub fn frobenius_calculator_fp4_as_2_over_2<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+        modulus: BigUint,
+        extension: &fp4_as_2_over_2::Extension2Over2<'a, FE, F>
+    ) -> Result<[Fp<'a, FE, F>; 4], ()> {
+        use crate::field::biguint_to_u64_vec;
+
+        let one = BigUint::from_u64(1).unwrap();
+        let divisor = BigUint::from_u64(4).unwrap();
+
+        // non-residue for Fp4 is u, so (u)^((q^i - 1)/2) = FP2_NON_RESIDUE^((q^i - 1)/4)
+        // that lies in the base field
+        let non_residue = extension.field.non_residue.clone();
+
+        // FP2_NON_RESIDUE**(((q^0) - 1) / 4)
+        let f_0 = Fp::one(extension.field.field);
+
+        // FP2_NON_RESIDUE**(((q^1) - 1) / 4)
+        let mut q_power = modulus.clone();
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        if !rem.is_zero() {
+            return Err(());
+        }
+        let f_1 = non_residue.pow(&biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^2) - 1) / 4)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_2 = non_residue.pow(&biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^3) - 1) / 4)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_3 = non_residue.pow(&biguint_to_u64_vec(power));
+
+        Ok([f_0, f_1, f_2, f_3])
+}
+
 pub fn frobenius_calculator_fp6_as_2_over_3<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
         modulus: BigUint,
         extension: &fp6_as_2_over_3::Extension2Over3<'a, FE, F>
@@ -231,6 +333,76 @@ pub fn frobenius_calculator_fp6_as_3_over_2<'a, FE: ElementRepr, F: SizedPrimeFi
         Ok(([f_0, f_1, f_2, f_3, f_4, f_5], [f_0_c2, f_1_c2, f_2_c2, f_3_c2, f_4_c2, f_5_c2]))
 }
 
+pub fn frobenius_calculator_fp8_as_2_over_4<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+        modulus: BigUint,
+        extension: &fp8_as_2_over_4::Extension2Over4<'a, FE, F>
+    ) -> Result<[Fp<'a, FE, F>; 8], ()> {
+        use crate::field::biguint_to_u64_vec;
+
+        let one = BigUint::from_u64(1).unwrap();
+        let divisor = BigUint::from_u64(8).unwrap();
+
+        // non-residue for Fp8 is v with v^2 = u and u^2 = FP2_NON_RESIDUE, 
+        // so (v)^((q^i - 1)/2) = FP2_NON_RESIDUE^((q^i - 1)/8) that lies in the base field
+        let non_residue = extension.field.field.non_residue.clone();
+
+        // FP2_NON_RESIDUE**(((q^0) - 1) / 8)
+        let f_0 = Fp::one(extension.field.field.field);
+
+        // FP2_NON_RESIDUE**(((q^1) - 1) / 8)
+        let mut q_power = modulus.clone();
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        if !rem.is_zero() {
+            return Err(());
+        }
+        let f_1 = non_residue.pow(biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^2) - 1) / 8)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_2 = non_residue.pow(biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^3) - 1) / 8)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_3 = non_residue.pow(biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^4) - 1) / 8)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_4 = non_residue.pow(biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^5) - 1) / 8)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_5 = non_residue.pow(biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^6) - 1) / 8)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_6 = non_residue.pow(biguint_to_u64_vec(power));
+
+        // FP2_NON_RESIDUE**(((q^7) - 1) / 8)
+        q_power *= &modulus;
+        let power = q_power.clone() - &one;
+        let (power, rem) = power.div_rem(&divisor);
+        debug_assert!(rem.is_zero());
+        let f_7 = non_residue.pow(biguint_to_u64_vec(power));
+
+        Ok([f_0, f_1, f_2, f_3, f_4, f_5, f_6, f_7])
+}
+
 pub fn frobenius_calculator_fp12<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
         modulus: BigUint,
         extension: &Extension2Over3Over2<'a, FE, F>
@@ -314,6 +486,222 @@ pub fn frobenius_calculator_fp12<'a, FE: ElementRepr, F: SizedPrimeField<Repr =
         Ok([f_0, f_1, f_2, f_3, f_4, f_5, f_6, f_7, f_8, f_9, f_10, f_11])
 }
 
+pub fn frobenius_calculator_fp12_as_3_over_4<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+        modulus: BigUint,
+        extension: &fp12_as_3_over_4::Extension3Over4<'a, FE, F>
+    ) -> Result<([Fp4<'a, FE, F>; 12], [Fp4<'a, FE, F>; 12]), ()> {
+        use crate::field::biguint_to_u64_vec;
+        use std::convert::TryInto;
+
+        let one = BigUint::from_u64(1).unwrap();
+        let three = BigUint::from_u64(3).unwrap();
+
+        // NON_RESIDUE**(((q^i) - 1) / 3) and it's square for i = 0..12
+        let non_residue = extension.non_residue.clone();
+        let mut coeffs_c1 = vec![Fp4::one(extension.field)];
+        let mut coeffs_c2 = vec![Fp4::one(extension.field)];
+
+        let mut q_power = modulus.clone();
+        for _ in 1..12 {
+            let power = q_power.clone() - &one;
+            let (power, rem) = power.div_rem(&three);
+            if !rem.is_zero() {
+                return Err(());
+            }
+            let f_c1 = non_residue.pow(biguint_to_u64_vec(power));
+            let mut f_c2 = f_c1.clone();
+            f_c2.square();
+
+            coeffs_c1.push(f_c1);
+            coeffs_c2.push(f_c2);
+            q_power *= &modulus;
+        }
+
+        let coeffs_c1 = coeffs_c1.try_into().map_err(|_| ())?;
+        let coeffs_c2 = coeffs_c2.try_into().map_err(|_| ())?;
+
+        Ok((coeffs_c1, coeffs_c2))
+}
+
+pub fn frobenius_calculator_fp18<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+        modulus: BigUint,
+        extension: &fp18_as_3_over_6::Extension3Over6<'a, FE, F>
+    ) -> Result<([Fp3<'a, FE, F>; 18], [Fp3<'a, FE, F>; 18]), ()> {
+        use crate::field::biguint_to_u64_vec;
+        use std::convert::TryInto;
+
+        let one = BigUint::from_u64(1).unwrap();
+        let six = BigUint::from_u64(6).unwrap();
+
+        // w^6 = v, so coefficients are v**(((q^i) - 1) / 6) and it's square for i = 0..18,
+        // those lie in Fp3, but not in the base field in general
+        let mut v = Fp3::zero(extension.field.field);
+        v.c1 = Fp::one(extension.field.field.field);
+        let mut coeffs_c1 = vec![Fp3::one(extension.field.field)];
+        let mut coeffs_c2 = vec![Fp3::one(extension.field.field)];
+
+        let mut q_power = modulus.clone();
+        for _ in 1..18 {
+            let power = q_power.clone() - &one;
+            let (power, rem) = power.div_rem(&six);
+            if !rem.is_zero() {
+                return Err(());
+            }
+            let f_c1 = v.pow(biguint_to_u64_vec(power));
+            let mut f_c2 = f_c1.clone();
+            f_c2.square();
+
+            coeffs_c1.push(f_c1);
+            coeffs_c2.push(f_c2);
+            q_power *= &modulus;
+        }
+
+        let coeffs_c1 = coeffs_c1.try_into().map_err(|_| ())?;
+        let coeffs_c2 = coeffs_c2.try_into().map_err(|_| ())?;
+
+        Ok((coeffs_c1, coeffs_c2))
+}
+
+pub fn frobenius_calculator_fp24<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+        modulus: BigUint,
+        extension: &fp24_as_2_over3_over_4::Extension2Over3Over4<'a, FE, F>
+    ) -> Result<[Fp4<'a, FE, F>; 24], ()> {
+        use crate::field::biguint_to_u64_vec;
+        use std::convert::TryInto;
+
+        let one = BigUint::from_u64(1).unwrap();
+        let six = BigUint::from_u64(6).unwrap();
+
+        // z^6 = xi, so coefficients are FP12_NON_RESIDUE**(((q^i) - 1) / 6) for i = 0..24
+        let non_residue = extension.field.non_residue.clone();
+        let mut coeffs = vec![Fp4::one(extension.field.field)];
+
+        let mut q_power = modulus.clone();
+        for _ in 1..24 {
+            let power = q_power.clone() - &one;
+            let (power, rem) = power.div_rem(&six);
+            if !rem.is_zero() {
+                return Err(());
+            }
+            coeffs.push(non_residue.pow(biguint_to_u64_vec(power)));
+            q_power *= &modulus;
+        }
+
+        coeffs.try_into().map_err(|_| ())
+}
+
+// Computes prod_i (prod_j powers_j^lambdas_i[j])^(q^i) where powers are elements
+// of the cyclotomic subgroup (so inverse is a conjugate) and lambdas are small,
+// as a multi-exponentiation over signed digit representations with shared squarings.
+// Used for final exponentiation hard parts written as sum(lambda_i(x) * q^i)
+// with powers_j = f^(x^j)
+pub(crate) fn cyclotomic_multi_exp<FE: FieldElement>(
+    one: FE,
+    powers: &[FE],
+    lambdas: &[&[i64]]
+) -> FE {
+    // (base, base inverse, NAF of the coefficient)
+    let mut bases = vec![];
+    for (frobenius_power, coeffs) in lambdas.iter().enumerate() {
+        for (power, &coeff) in powers.iter().zip(coeffs.iter()) {
+            if coeff == 0 {
+                continue;
+            }
+
+            let mut base = power.clone();
+            base.frobenius_map(frobenius_power);
+            let mut base_inv = base.clone();
+            base_inv.conjugate();
+            if coeff < 0 {
+                std::mem::swap(&mut base, &mut base_inv);
+            }
+            let naf = into_ternary_wnaf(&[coeff.unsigned_abs()]);
+
+            bases.push((base, base_inv, naf));
+        }
+    }
+
+    let max_len = bases.iter().map(|(_, _, naf)| naf.len()).max().unwrap_or(0);
+
+    let mut res = one;
+    for i in (0..max_len).rev() {
+        res.square();
+        for (base, base_inv, naf) in bases.iter() {
+            match naf.get(i) {
+                Some(1) => res.mul_assign(base),
+                Some(-1) => res.mul_assign(base_inv),
+                _ => {}
+            }
+        }
+    }
+
+    res
+}
+
+// Encoding of the line coefficients of the prepared twist point over Fp2 is a flag
+// byte (1 for the point at infinity) followed by the coefficients, each Fp element
+// written as a big endian integer of the full representation length
+pub(crate) fn encode_fp2_ell_coeffs<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    is_infinity: bool,
+    ell_coeffs: &[(Fp2<'a, FE, F>, Fp2<'a, FE, F>, Fp2<'a, FE, F>)]
+) -> Vec<u8> {
+    let mut result = vec![is_infinity as u8];
+    for (c0, c1, c2) in ell_coeffs.iter() {
+        for c in [c0, c1, c2].iter() {
+            // writing into a vector never fails
+            c.c0.into_repr().write_be(&mut result).unwrap();
+            c.c1.into_repr().write_be(&mut result).unwrap();
+        }
+    }
+
+    result
+}
+
+pub(crate) fn decode_fp2_ell_coeffs<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    extension: &'a Extension2<'a, FE, F>,
+    bytes: &[u8]
+) -> Result<(bool, Vec<(Fp2<'a, FE, F>, Fp2<'a, FE, F>, Fp2<'a, FE, F>)>), ()> {
+    if bytes.is_empty() {
+        return Err(());
+    }
+    let (flag, mut rest) = bytes.split_at(1);
+    let is_infinity = match flag[0] {
+        0 => false,
+        1 => true,
+        _ => return Err(()),
+    };
+
+    let element_len = FE::default().as_ref().len() * 8;
+    if rest.len() % (element_len * 6) != 0 {
+        return Err(());
+    }
+    if is_infinity && !rest.is_empty() {
+        return Err(());
+    }
+
+    let decode_fp2 = |rest: &mut &[u8]| -> Result<Fp2<'a, FE, F>, ()> {
+        let (c0, tail) = rest.split_at(element_len);
+        let (c1, tail) = tail.split_at(element_len);
+        *rest = tail;
+
+        let mut el = Fp2::zero(extension);
+        el.c0 = Fp::from_be_bytes(extension.field, c0, false).map_err(|_| ())?;
+        el.c1 = Fp::from_be_bytes(extension.field, c1, false).map_err(|_| ())?;
+
+        Ok(el)
+    };
+
+    let mut ell_coeffs = vec![];
+    while !rest.is_empty() {
+        let c0 = decode_fp2(&mut rest)?;
+        let c1 = decode_fp2(&mut rest)?;
+        let c2 = decode_fp2(&mut rest)?;
+        ell_coeffs.push((c0, c1, c2));
+    }
+
+    Ok((is_infinity, ell_coeffs))
+}
+
 pub fn into_ternary_wnaf(repr: &[u64]) -> Vec<i64> {
     fn is_zero(repr: &[u64]) -> bool {
 
@@ -344,6 +732,17 @@ pub fn into_ternary_wnaf(repr: &[u64]) -> Vec<i64> {
         }
     }
 
+    fn add1(repr: &mut Vec<u64>) {
+        for i in repr.iter_mut() {
+            let (sum, carry) = i.overflowing_add(1);
+            *i = sum;
+            if !carry {
+                return;
+            }
+        }
+        repr.push(1);
+    }
+
     let mut res = vec![];
     let mut e = repr.to_vec();
     while !is_zero(&e) {
@@ -353,7 +752,8 @@ pub fn into_ternary_wnaf(repr: &[u64]) -> Vec<i64> {
             if z >= 0 {
                 e[0] -= z as u64;
             } else {
-                e[0] += -z as u64;
+                // carry may propagate through the limbs
+                add1(&mut e);
             }
         } else {
             z = 0;
@@ -380,6 +780,12 @@ mod tests {
     use crate::extension_towers::fp6_as_3_over_2;
     use crate::extension_towers::fp12_as_2_over3_over_2::{Fp12, Extension2Over3Over2};
     use num_traits::Num;
+    use crate::field::{SizedPrimeField, biguint_to_u64_vec};
+    use crate::representation::ElementRepr;
+    use crate::weierstrass::Group;
+    use crate::weierstrass::twist::TwistPoint;
+    use crate::weierstrass::curve::CurvePoint;
+    use crate::weierstrass::glv::biguint_from_limbs;
 
     #[test]
     fn test_bls12_381_frob_fp2() {
@@ -584,4 +990,176 @@ mod tests {
         println!("C_10 = {}", coeffs[10]);
     }
 
+    fn same_twist_point<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>>(
+        a: &TwistPoint<'a, FE, F, GE, G>,
+        b: &TwistPoint<'a, FE, F, GE, G>
+    ) -> bool {
+        a.is_zero() == b.is_zero() && (a.is_zero() || a.into_xy() == b.into_xy())
+    }
+
+    /// Checks that psi acts on a point of G2 as multiplication by p, and that
+    /// multiplication and multiexp with 4-way decomposition match double-and-add
+    pub(crate) fn check_g2_endomorphism<'a, FE, F, GE, G, P, M, E>(q: &TwistPoint<'a, FE, F, GE, G>, psi: P, mul_g2: M, multiexp_g2: E)
+    where FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>,
+        P: Fn(&TwistPoint<'a, FE, F, GE, G>) -> TwistPoint<'a, FE, F, GE, G>,
+        M: Fn(&TwistPoint<'a, FE, F, GE, G>, Vec<u64>) -> TwistPoint<'a, FE, F, GE, G>,
+        E: Fn(&[TwistPoint<'a, FE, F, GE, G>], &[Vec<u64>]) -> Option<TwistPoint<'a, FE, F, GE, G>>
+    {
+        let p = biguint_from_limbs(q.curve.base_field.field.modulus().as_ref());
+        let r = biguint_from_limbs(q.curve.scalar_field.modulus().as_ref());
+        assert!(same_twist_point(&psi(q), &q.mul(biguint_to_u64_vec(&p % &r))));
+
+        let mut scalars: Vec<BigUint> = (1..6u64).map(|i| &r * BigUint::from(i) / BigUint::from(7u64) + BigUint::from(i)).collect();
+        scalars.extend_from_slice(&[
+            BigUint::zero(),
+            BigUint::from(1u64),
+            BigUint::from(0x1234567890abcdefu64),
+            &r - BigUint::from(1u64),
+            r.clone(),
+            &r + BigUint::from(5u64)
+        ]);
+        let scalars: Vec<Vec<u64>> = scalars.into_iter().map(biguint_to_u64_vec).collect();
+
+        for scalar in scalars.iter() {
+            assert!(same_twist_point(&mul_g2(q, scalar.clone()), &q.mul(scalar)));
+        }
+        assert!(mul_g2(&TwistPoint::zero(q.curve), scalars[0].clone()).is_zero());
+
+        let mut q2 = q.clone();
+        q2.double();
+        let points = [q.clone(), q2, psi(q), TwistPoint::zero(q.curve)];
+        let mut expected = TwistPoint::zero(q.curve);
+        for (point, scalar) in points.iter().zip(scalars.iter()) {
+            expected.add_assign(&point.mul(scalar));
+        }
+        assert!(same_twist_point(&multiexp_g2(&points, &scalars[..4]).unwrap(), &expected));
+        assert!(multiexp_g2(&[], &[]).unwrap().is_zero());
+        assert!(multiexp_g2(&points, &scalars[..3]).is_none());
+    }
+
+    // square root in Fp for p = 3 mod 4
+    fn sqrt_fp<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(field: &'a F, a: &Fp<'a, FE, F>) -> Option<Fp<'a, FE, F>> {
+        let p = biguint_from_limbs(field.modulus().as_ref());
+        let root = a.pow(biguint_to_u64_vec((p + BigUint::from(1u64)) / BigUint::from(4u64)));
+        let mut square = root.clone();
+        square.square();
+
+        if &square == a { Some(root) } else { None }
+    }
+
+    // square root in Fp2 = Fp[i] with i^2 = -1 for p = 3 mod 4, algorithm 9
+    // of https://eprint.iacr.org/2012/685.pdf
+    fn sqrt_fp2<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(extension: &'a Extension2<'a, FE, F>, a: &Fp2<'a, FE, F>) -> Option<Fp2<'a, FE, F>> {
+        let p = biguint_from_limbs(extension.field.modulus().as_ref());
+        let a1 = a.pow(biguint_to_u64_vec((&p - BigUint::from(3u64)) / BigUint::from(4u64)));
+        let mut alpha = a1.clone();
+        alpha.square();
+        alpha.mul_assign(a);
+        let mut x0 = a1;
+        x0.mul_assign(a);
+
+        let mut minus_one = Fp2::one(extension);
+        minus_one.negate();
+        let root = if alpha == minus_one {
+            let mut i = Fp2::zero(extension);
+            i.c1 = Fp::one(extension.field);
+            i.mul_assign(&x0);
+            i
+        } else {
+            alpha.add_assign(&Fp2::one(extension));
+            let mut b = alpha.pow(biguint_to_u64_vec((p - BigUint::from(1u64)) / BigUint::from(2u64)));
+            b.mul_assign(&x0);
+            b
+        };
+        let mut square = root.clone();
+        square.square();
+
+        if &square == a { Some(root) } else { None }
+    }
+
+    /// Checks fast subgroup membership tests against multiplication by the group order
+    /// on multiples of P and Q and on points of the curve and of the twist with small x,
+    /// that almost surely have a component outside of the subgroup. Needs p = 3 mod 4
+    /// and Fp2 non-residue -1. Returns the number of such points of the curve not in G1
+    pub(crate) fn check_subgroup_membership<'a, FE, F, GE, G, P, Q>(p: &CurvePoint<'a, FE, F, GE, G>, q: &TwistPoint<'a, FE, F, GE, G>, is_in_g1: P, is_in_g2: Q) -> usize
+    where FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>,
+        P: Fn(&CurvePoint<'a, FE, F, GE, G>) -> bool,
+        Q: Fn(&TwistPoint<'a, FE, F, GE, G>) -> bool
+    {
+        let order = p.curve.scalar_field.modulus();
+        let field = p.curve.base_field;
+        let extension = q.curve.base_field;
+
+        let mut curve_points = vec![];
+        let mut x = Fp::zero(field);
+        while curve_points.len() < 4 {
+            let mut rhs = x.clone();
+            rhs.square();
+            rhs.add_assign(&p.curve.a);
+            rhs.mul_assign(&x);
+            rhs.add_assign(&p.curve.b);
+            if let Some(y) = sqrt_fp(field, &rhs) {
+                let point = CurvePoint::point_from_xy(p.curve, x.clone(), y);
+                assert!(point.check_on_curve());
+                curve_points.push(point);
+            }
+            x.add_assign(&Fp::one(field));
+        }
+
+        let mut twist_points = vec![];
+        let mut x = Fp2::one(extension);
+        x.c1 = Fp::one(field);
+        while twist_points.len() < 4 {
+            let mut rhs = x.clone();
+            rhs.square();
+            rhs.add_assign(&q.curve.a);
+            rhs.mul_assign(&x);
+            rhs.add_assign(&q.curve.b);
+            if let Some(y) = sqrt_fp2(extension, &rhs) {
+                let point = TwistPoint::point_from_xy(q.curve, x.clone(), y);
+                assert!(point.check_on_curve());
+                twist_points.push(point);
+            }
+            x.c0.add_assign(&Fp::one(field));
+        }
+
+        // points of the subgroup, then points with small x shifted by P or Q
+        // and with the subgroup component cleared
+        for point in [p.clone(), p.mul([5u64]), CurvePoint::zero(p.curve)].iter() {
+            assert!(is_in_g1(point));
+            assert!(point.mul(order).is_zero());
+        }
+        let mut num_not_in_g1 = 0;
+        for point in curve_points.iter() {
+            let mut shifted = point.clone();
+            shifted.add_assign(p);
+            for point in [point.clone(), shifted, point.mul(order)].iter() {
+                let expected = point.mul(order).is_zero();
+                assert_eq!(is_in_g1(point), expected);
+                if !expected {
+                    num_not_in_g1 += 1;
+                }
+            }
+        }
+
+        for point in [q.clone(), q.mul([5u64]), TwistPoint::zero(q.curve)].iter() {
+            assert!(is_in_g2(point));
+            assert!(point.mul(order).is_zero());
+        }
+        let mut num_not_in_g2 = 0;
+        for point in twist_points.iter() {
+            let mut shifted = point.clone();
+            shifted.add_assign(q);
+            for point in [point.clone(), shifted, point.mul(order)].iter() {
+                let expected = point.mul(order).is_zero();
+                assert_eq!(is_in_g2(point), expected);
+                if !expected {
+                    num_not_in_g2 += 1;
+                }
+            }
+        }
+        assert!(num_not_in_g2 > 0);
+
+        num_not_in_g1
+    }
 }
@@ -0,0 +1,314 @@
+use crate::field::biguint_to_u64_vec;
+use crate::traits::{FieldElement, MsbBitIterator};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+use num_integer::Integer;
+
+// Slow but generic reduced Tate and Weil pairings that are used as an oracle to
+// cross-check optimized engines. Both points are given by the affine coordinates
+// in the full extension F_{q^k} (so points of the twist should be untwisted first),
+// Miller functions run over the full group order r and keep the vertical lines
+// as denominators, so no property of the embedding degree or a twist is used
+
+pub struct ReferencePairing<E: FieldElement> {
+    // curve coefficient a embedded into F_{q^k}
+    pub a: E,
+    pub one: E,
+    pub modulus: BigUint,
+    pub group_order: BigUint,
+    pub embedding_degree: usize,
+    group_order_repr: Vec<u64>,
+    // (q^k - 1)/r
+    final_exponent: Vec<u64>,
+}
+
+impl<E: FieldElement> ReferencePairing<E> {
+    pub fn new(
+        a: E,
+        one: E,
+        modulus: BigUint,
+        group_order: BigUint,
+        embedding_degree: usize
+    ) -> Result<Self, ()> {
+        if embedding_degree == 0 || embedding_degree > 24 {
+            return Err(());
+        }
+        if group_order.is_zero() || group_order.is_even() {
+            return Err(());
+        }
+
+        let mut q_k = BigUint::one();
+        for _ in 0..embedding_degree {
+            q_k *= &modulus;
+        }
+        let (final_exponent, rem) = (q_k - BigUint::one()).div_rem(&group_order);
+        if !rem.is_zero() {
+            return Err(());
+        }
+
+        Ok(Self {
+            a,
+            one,
+            modulus,
+            group_order_repr: biguint_to_u64_vec(group_order.clone()),
+            group_order,
+            embedding_degree,
+            final_exponent: biguint_to_u64_vec(final_exponent),
+        })
+    }
+
+    /// Evaluates the line through `t` and `s` (or the tangent at `t` if those are equal)
+    /// at `at`, returns the value and the sum `t + s` or `None` for a point at infinity
+    fn line_step(&self, t: &(E, E), s: &(E, E), at: &(E, E)) -> (E, Option<(E, E)>) {
+        let (tx, ty) = t;
+        let (sx, sy) = s;
+
+        let lambda = if tx == sx {
+            let mut y_sum = ty.clone();
+            y_sum.add_assign(sy);
+            if y_sum.is_zero() {
+                // vertical line x - tx
+                let mut value = at.0.clone();
+                value.sub_assign(tx);
+
+                return (value, None);
+            }
+
+            // (3*x^2 + a) / 2*y
+            let mut num = tx.clone();
+            num.square();
+            let mut t0 = num.clone();
+            t0.double();
+            num.add_assign(&t0);
+            num.add_assign(&self.a);
+
+            let mut den = ty.clone();
+            den.double();
+            num.mul_assign(&den.inverse().unwrap());
+
+            num
+        } else {
+            let mut num = sy.clone();
+            num.sub_assign(ty);
+            let mut den = sx.clone();
+            den.sub_assign(tx);
+            num.mul_assign(&den.inverse().unwrap());
+
+            num
+        };
+
+        // y_at - ty - lambda * (x_at - tx)
+        let mut value = at.1.clone();
+        value.sub_assign(ty);
+        let mut t0 = at.0.clone();
+        t0.sub_assign(tx);
+        t0.mul_assign(&lambda);
+        value.sub_assign(&t0);
+
+        let mut x = lambda.clone();
+        x.square();
+        x.sub_assign(tx);
+        x.sub_assign(sx);
+
+        let mut y = tx.clone();
+        y.sub_assign(&x);
+        y.mul_assign(&lambda);
+        y.sub_assign(ty);
+
+        (value, Some((x, y)))
+    }
+
+    /// Computes the Miller function f_{r, P}(Q) with the vertical lines included,
+    /// returns `None` if `Q` hits a zero or a pole of it
+    pub fn miller_function(&self, p: &(E, E), q: &(E, E)) -> Option<E> {
+        let mut numerator = self.one.clone();
+        let mut denominator = self.one.clone();
+
+        let mut t = Some(p.clone());
+        for bit in MsbBitIterator::new(&self.group_order_repr).skip(1) {
+            numerator.square();
+            denominator.square();
+
+            // for a prime r, T = [r]P only after the last step
+            let (line, sum) = self.line_step(t.as_ref()?, t.as_ref()?, q);
+            numerator.mul_assign(&line);
+            if let Some((x, _)) = &sum {
+                let mut vertical = q.0.clone();
+                vertical.sub_assign(x);
+                denominator.mul_assign(&vertical);
+            }
+            t = sum;
+
+            if bit {
+                let (line, sum) = self.line_step(t.as_ref()?, p, q);
+                numerator.mul_assign(&line);
+                if let Some((x, _)) = &sum {
+                    let mut vertical = q.0.clone();
+                    vertical.sub_assign(x);
+                    denominator.mul_assign(&vertical);
+                }
+                t = sum;
+            }
+        }
+
+        if t.is_some() || numerator.is_zero() {
+            return None;
+        }
+
+        numerator.mul_assign(&denominator.inverse()?);
+
+        Some(numerator)
+    }
+
+    /// Reduced Tate pairing f_{r, P}(Q)^((q^k - 1)/r)
+    pub fn tate(&self, p: &(E, E), q: &(E, E)) -> Option<E> {
+        let f = self.miller_function(p, q)?;
+
+        Some(f.pow(&self.final_exponent))
+    }
+
+    /// Weil pairing (-1)^r * f_{r, P}(Q) / f_{r, Q}(P)
+    pub fn weil(&self, p: &(E, E), q: &(E, E)) -> Option<E> {
+        let mut f = self.miller_function(p, q)?;
+        let g = self.miller_function(q, p)?;
+        f.mul_assign(&g.inverse()?);
+        // r is odd
+        f.negate();
+
+        Some(f)
+    }
+
+    /// For the ate pairing with a Miller loop over sum(c_i * q^i) = m * r (with the
+    /// Miller functions f_{c_i, Q}^(q^i) and the lines to sum up [c_i * q^i]Q)
+    /// returns the exponent e such that ate(Q, P) = tate(Q, P)^e. Follows from
+    /// f_{r, Q}^m = prod(f_{c_i, Q}^(q^i) * f_{q^i, Q}^(c_i)) * lines and
+    /// tate(Q, P)^((q^k - 1)/r) = f_{q, Q}(P)^(k * q^(k - 1) * (q^k - 1)/r)
+    pub fn ate_exponent(&self, coeffs: &[BigInt]) -> Option<BigUint> {
+        let modulus = BigInt::from_biguint(Sign::Plus, self.modulus.clone());
+        let group_order = BigInt::from_biguint(Sign::Plus, self.group_order.clone());
+
+        // lambda = sum(c_i * q^i), s = sum(i * c_i * q^(i - 1))
+        let mut lambda = BigInt::zero();
+        let mut s = BigInt::zero();
+        let mut q_power = BigInt::one();
+        for (i, c) in coeffs.iter().enumerate() {
+            lambda += c * &q_power;
+            if i != 0 {
+                s += c * BigInt::from(i as u64) * &q_power / &modulus;
+            }
+            q_power *= &modulus;
+        }
+
+        let (m, rem) = lambda.div_mod_floor(&group_order);
+        if !rem.is_zero() {
+            return None;
+        }
+
+        // l = (q^k - 1)/r, t = k * q^(k - 1) mod r
+        let mut q_k_minus_1 = BigUint::one();
+        for _ in 1..self.embedding_degree {
+            q_k_minus_1 *= &self.modulus;
+        }
+        let t = (q_k_minus_1 * BigUint::from(self.embedding_degree as u64)) % &self.group_order;
+        if t.is_zero() {
+            return None;
+        }
+        let two = BigUint::from(2u64);
+        let t_inv = t.modpow(&(&self.group_order - &two), &self.group_order);
+
+        let mut l = BigUint::one();
+        for _ in 0..self.embedding_degree {
+            l *= &self.modulus;
+        }
+        let l = (l - BigUint::one()) / &self.group_order;
+
+        // e = m - l * s / t
+        let correction = BigInt::from_biguint(Sign::Plus, (l * t_inv) % &self.group_order) * s;
+        let e = (m - correction).mod_floor(&group_order);
+
+        e.to_biguint()
+    }
+
+    pub fn final_exponent(&self) -> &[u64] {
+        &self.final_exponent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReferencePairing;
+    use crate::field::{U256Repr, PrimeField, new_field};
+    use crate::fp::Fp;
+    use crate::traits::FieldElement;
+    use crate::weierstrass::glv::biguint_from_limbs;
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::Num;
+
+    type Point<'a> = (Fp<'a, U256Repr, PrimeField<U256Repr>>, Fp<'a, U256Repr, PrimeField<U256Repr>>);
+
+    fn point<'a>(field: &'a PrimeField<U256Repr>, x: &str, y: &str) -> Point<'a> {
+        let x = BigUint::from_str_radix(x, 10).unwrap().to_bytes_be();
+        let y = BigUint::from_str_radix(y, 10).unwrap().to_bytes_be();
+
+        (Fp::from_be_bytes(field, &x, true).unwrap(), Fp::from_be_bytes(field, &y, true).unwrap())
+    }
+
+    // y^2 = x^3 + 16 over the BN254 base field has all of its 3-torsion defined over
+    // Fp: x = 0 and x^3 = -64 are roots of the 3-division polynomial, 16 and -48 are
+    // squares since p = 1 mod 3. So k = 1 and both pairings are non-degenerate on it.
+    // Values were computed independently with Python
+    #[test]
+    fn test_reference_pairing_with_embedding_degree_one() {
+        let modulus = "21888242871839275222246405745257275088696311157297823662689037894645226208583";
+        let field = new_field::<U256Repr>(modulus, 10).unwrap();
+        let modulus = BigUint::from_str_radix(modulus, 10).unwrap();
+        let zero = Fp::zero(&field);
+        let one = Fp::one(&field);
+        let order = BigUint::from(3u64);
+
+        assert!(ReferencePairing::new(zero.clone(), one.clone(), modulus.clone(), order.clone(), 0).is_err());
+        assert!(ReferencePairing::new(zero.clone(), one.clone(), modulus.clone(), BigUint::from(6u64), 1).is_err());
+        // p = 3 mod 5
+        assert!(ReferencePairing::new(zero.clone(), one.clone(), modulus.clone(), BigUint::from(5u64), 1).is_err());
+
+        let reference = ReferencePairing::new(zero, one.clone(), modulus.clone(), order.clone(), 1).unwrap();
+        let final_exponent = BigUint::from_str_radix("7296080957279758407415468581752425029565437052432607887563012631548408736194", 10).unwrap();
+        assert!(biguint_from_limbs(reference.final_exponent()) == final_exponent);
+
+        // P = (0, 4), Q = (-4, sqrt(-48)), R = P + Q and their doubles
+        let p = point(&field, "0", "4");
+        let p_2 = point(&field, "0", "21888242871839275222246405745257275088696311157297823662689037894645226208579");
+        let q = point(&field, "21888242871839275222246405745257275088696311157297823662689037894645226208579", "17631683881184975371348829942606096167794234071041645215732");
+        let q_2 = point(&field, "21888242871839275222246405745257275088696311157297823662689037894645226208579", "21888242871839275204614721864072299717347481214691727494894803823603580992851");
+        let r = point(&field, "21888242871839275213430563804664787403021896185994775578791920859124403600719", "17631683881184975371348829942606096167794234071041645215732");
+        let r_2 = point(&field, "21888242871839275213430563804664787403021896185994775578791920859124403600719", "21888242871839275204614721864072299717347481214691727494894803823603580992851");
+
+        // primitive cube root of unity
+        let root = Fp::from_be_bytes(&field, &BigUint::from_str_radix("21888242871839275220042445260109153167277707414472061641714758635765020556616", 10).unwrap().to_bytes_be(), true).unwrap();
+        let mut root_squared = root.clone();
+        root_squared.square();
+        assert!(root.pow([3u64]) == one);
+
+        // Tate pairing is bilinear, but is trivial on the pairs with P
+        let tate = reference.tate(&q, &r).unwrap();
+        assert!(tate == root);
+        assert!(reference.tate(&q, &r_2).unwrap() == root_squared);
+        assert!(reference.tate(&q_2, &r).unwrap() == root_squared);
+        assert!(reference.tate(&p, &q).unwrap() == one);
+
+        // Weil pairing is bilinear and alternating
+        let weil = reference.weil(&p, &q).unwrap();
+        assert!(weil == root);
+        assert!(reference.weil(&q, &p).unwrap() == root_squared);
+        assert!(reference.weil(&p_2, &q).unwrap() == root_squared);
+        assert!(reference.weil(&p, &q_2).unwrap() == root_squared);
+
+        // P is a zero of f_{r, P}
+        assert!(reference.miller_function(&p, &p).is_none());
+        assert!(reference.weil(&p, &p).is_none());
+
+        // Miller loop over r itself is the Tate pairing, a loop over 1 is not a multiple of r
+        assert!(reference.ate_exponent(&[BigInt::from(3)]) == Some(BigUint::from(1u64)));
+        assert!(reference.ate_exponent(&[BigInt::from(1)]).is_none());
+    }
+}
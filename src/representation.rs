@@ -105,6 +105,10 @@ pub trait ElementRepr:
     // but it's a necessary evil
     fn mont_mul_assign(&mut self, other: &Self, modulus: &Self, mont_inv: u64);
     fn mont_square(&mut self, modulus: &Self, mont_inv: u64);
+    /// Same as `mont_mul_assign` and `mont_square`, but the final reduction
+    /// doesn't branch on the value
+    fn mont_mul_assign_ct(&mut self, other: &Self, modulus: &Self, mont_inv: u64);
+    fn mont_square_ct(&mut self, modulus: &Self, mont_inv: u64);
     fn into_normal_repr(&self, modulus: &Self, mont_inv: u64) -> Self;
 }
 
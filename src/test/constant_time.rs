@@ -0,0 +1,163 @@
+// Timing leakage tests in the style of dudect (https://eprint.iacr.org/2016/1123.pdf):
+// measurements for a fixed scalar and for random scalars are interleaved in random
+// order, and Welch's t-test is run on the raw and cropped samples. |t| above 10 means
+// the timing depends on the scalar with overwhelming probability. Results depend on
+// the machine load, so tests are ignored by default, run them in release mode with
+// `cargo test --release -- --ignored constant_time`
+
+use crate::field::{U256Repr, PrimeField, SizedPrimeField, new_field};
+use crate::fp::Fp;
+use crate::traits::{FieldElement, ConstantTimeFieldElement};
+use crate::representation::ElementRepr;
+use crate::weierstrass::Group;
+use crate::weierstrass::curve::{WeierstrassCurve, CurvePoint};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use std::time::Instant;
+
+const NUM_MEASUREMENTS: usize = 20000;
+const T_THRESHOLD: f64 = 10.0;
+
+#[derive(Default)]
+struct WelchTest {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl WelchTest {
+    // online mean and variance
+    fn push(&mut self, class: usize, value: f64) {
+        self.n[class] += 1.0;
+        let delta = value - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (value - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        let var_0 = self.m2[0] / (self.n[0] - 1.0);
+        let var_1 = self.m2[1] / (self.n[1] - 1.0);
+
+        (self.mean[0] - self.mean[1]) / (var_0 / self.n[0] + var_1 / self.n[1]).sqrt()
+    }
+}
+
+/// Returns the largest |t| over the raw samples and samples cropped at a few
+/// percentiles, since the tail is dominated by interrupts and scheduling
+fn max_t_statistic(classes: &[usize], timings: &[u64]) -> f64 {
+    let mut sorted = timings.to_vec();
+    sorted.sort_unstable();
+
+    let mut max_t: f64 = 0.0;
+    for percentile in [100, 99, 95, 90, 75, 50].iter() {
+        let threshold = sorted[(sorted.len() - 1) * percentile / 100];
+        let mut test = WelchTest::default();
+        for (class, timing) in classes.iter().zip(timings.iter()) {
+            if *timing <= threshold {
+                test.push(*class, *timing as f64);
+            }
+        }
+        max_t = max_t.max(test.t().abs());
+    }
+
+    max_t
+}
+
+type Bn254Point<'a> = CurvePoint<'a, U256Repr, PrimeField<U256Repr>, U256Repr, PrimeField<U256Repr>>;
+
+fn measure_bn254_g1<M: Fn(&Bn254Point, U256Repr) -> bool>(mul: M) -> f64 {
+    let rng = &mut XorShiftRng::from_seed([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+    let group = new_field::<U256Repr>("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+    let one = Fp::one(&field);
+    let mut b_coeff = one.clone();
+    b_coeff.double();
+    b_coeff.add_assign(&one);
+    let curve = WeierstrassCurve::new(&group, Fp::zero(&field), b_coeff);
+    let mut two = one.clone();
+    two.double();
+    let point = CurvePoint::point_from_xy(&curve, one, two);
+
+    // class 0 is a fixed scalar of a small hamming weight, class 1 is random
+    let fixed_scalar = U256Repr::from(1);
+    let mut classes = Vec::with_capacity(NUM_MEASUREMENTS);
+    let mut scalars = Vec::with_capacity(NUM_MEASUREMENTS);
+    for _ in 0..NUM_MEASUREMENTS {
+        let class = rng.gen_range(0, 2);
+        let scalar = if class == 0 {
+            fixed_scalar
+        } else {
+            let mut scalar = U256Repr::default();
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes[1..]);
+            scalar.read_be(&bytes[..]).unwrap();
+            scalar
+        };
+        classes.push(class);
+        scalars.push(scalar);
+    }
+
+    let mut timings = Vec::with_capacity(NUM_MEASUREMENTS);
+    for scalar in scalars.into_iter() {
+        let start = Instant::now();
+        let res = mul(&point, scalar);
+        let elapsed = start.elapsed().as_nanos() as u64;
+        std::hint::black_box(res);
+        timings.push(elapsed);
+    }
+
+    max_t_statistic(&classes, &timings)
+}
+
+#[test]
+#[ignore]
+fn test_constant_time_harness_detects_leakage() {
+    // double-and-add skips leading zeroes, so it must be caught
+    let t = measure_bn254_g1(|point, scalar| point.mul(scalar).is_zero());
+    assert!(t > T_THRESHOLD, "double-and-add: max |t| = {}", t);
+}
+
+#[test]
+#[ignore]
+fn test_constant_time_mul_bn254() {
+    let t = measure_bn254_g1(|point, scalar| point.mul_constant_time(scalar).is_zero());
+    assert!(t < T_THRESHOLD, "montgomery ladder: max |t| = {}", t);
+}
+
+#[test]
+fn test_constant_time_field_arithmetic() {
+    // branchless reduction must agree with the usual one around the modulus
+    let field = new_field::<U256Repr>("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+    let one = Fp::one(&field);
+    let mut minus_one = one.clone();
+    minus_one.negate();
+    let mut minus_two = minus_one.clone();
+    minus_two.sub_assign(&one);
+    // largest element that doesn't overflow when doubled
+    let mut half = Fp::zero(&field);
+    half.repr = field.modulus();
+    half.repr.div2();
+    let elements = [Fp::zero(&field), one, minus_one, minus_two, half];
+
+    for a in elements.iter() {
+        let mut expected = a.clone();
+        expected.double();
+        let mut doubled = a.clone();
+        doubled.double_ct();
+        assert!(doubled == expected);
+
+        for b in elements.iter() {
+            let mut expected = a.clone();
+            expected.add_assign(b);
+            let mut sum = a.clone();
+            sum.add_assign_ct(b);
+            assert!(sum == expected);
+
+            let mut expected = a.clone();
+            expected.sub_assign(b);
+            let mut difference = a.clone();
+            difference.sub_assign_ct(b);
+            assert!(difference == expected);
+        }
+    }
+}
@@ -1 +1,2 @@
-mod no_compression;
\ No newline at end of file
+mod no_compression;
+mod constant_time;
\ No newline at end of file
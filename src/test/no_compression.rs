@@ -1,12 +1,12 @@
 extern crate hex;
 extern crate csv;
 
-use crate::{API, PrecompileAPI};
+use crate::{API, PrecompileAPI, CachedAPI, OperationType};
 use hex::{decode};
 use csv::{Reader};
 
-#[test]
-fn test_mul_from_csv() {
+fn read_mul_vectors() -> Vec<(bool, Vec<u8>, Vec<u8>)> {
+    let mut vectors = vec![];
     let mut reader = Reader::from_path("src/test/no_compression_mul.csv").expect("must open a test file");
     for line in reader.records() {
         let record = line.expect("line must decode");
@@ -78,6 +78,15 @@ fn test_mul_from_csv() {
 
         // println!("Result encoding = {}", encode(result.clone()));
 
+        vectors.push((err, encoding, result));
+    }
+
+    vectors
+}
+
+#[test]
+fn test_mul_from_csv() {
+    for (err, encoding, result) in read_mul_vectors() {
         let mul_result = API::mul_point(&encoding[..]).expect("must multiply");
 
         // println!("Multiplication result encoding = {}", encode(mul_result.clone()));
@@ -89,7 +98,222 @@ fn test_mul_from_csv() {
 
         // assert_eq!(result, mul_result);
     }
+}
 
+#[test]
+fn test_mul_from_csv_with_cache() {
+    let api = CachedAPI::new(4);
+    let vectors = read_mul_vectors();
+    // every vector twice, so the second pass runs on cached contexts
+    for (_, encoding, _) in vectors.iter().chain(vectors.iter()) {
+        let uncached = API::mul_point(&encoding[..]).expect("must multiply");
+        let cached = api.mul_point(&encoding[..]).expect("must multiply");
+        assert_eq!(uncached, cached);
+    }
+    assert!(api.cached_contexts() > 0);
+    assert!(api.cached_contexts() <= 4);
+}
+
+#[test]
+fn test_batch_from_csv() {
+    let vectors = read_mul_vectors();
+    let mut calls = vec![];
+    for (_, encoding, _) in vectors.iter() {
+        calls.push((OperationType::MulPoint, &encoding[..]));
+        // a single (point, scalar) pair is also a valid multiexp input
+        calls.push((OperationType::Multiexp, &encoding[..]));
+        // truncated input must fail without affecting other calls
+        calls.push((OperationType::MulPoint, &encoding[..(encoding.len() - 1)]));
+    }
+
+    let expected: Vec<_> = calls.iter().map(|(operation, bytes)| {
+        match operation {
+            OperationType::AddPoints => API::add_points(bytes),
+            OperationType::MulPoint => API::mul_point(bytes),
+            OperationType::Multiexp => API::multiexp(bytes),
+        }
+    }).collect();
+
+    for num_threads in [1, 4] {
+        let results = API::execute_batch(&calls[..], num_threads);
+        assert_eq!(results, expected);
+    }
+
+    assert!(API::execute_batch(&[], 4).is_empty());
+}
+
+#[test]
+fn test_unsupported_limbs_combination() {
+    // BN254 base field, but group order is one limb larger than the modulus
+    let modulus = decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+    let mut encoding = vec![modulus.len() as u8];
+    encoding.extend(modulus.into_iter());
+    encoding.extend(pad_for_len_be(vec![0], 32).into_iter());
+    encoding.extend(pad_for_len_be(vec![3], 32).into_iter());
+    encoding.push(40);
+    encoding.extend(vec![0xff; 40].into_iter());
+    encoding.extend(pad_for_len_be(vec![1], 32).into_iter());
+    encoding.extend(pad_for_len_be(vec![2], 32).into_iter());
+    encoding.extend(pad_for_len_be(vec![5], 40).into_iter());
+
+    assert!(API::mul_point(&encoding[..]).is_err());
+    assert!(CachedAPI::new(1).mul_point(&encoding[..]).is_err());
+}
+
+#[test]
+fn test_b_is_zero_rejects_two_torsion() {
+    // y^2 = x^3 + x over the BN254 base field, where (0, 0) is a point of order two
+    let encode_mul = |x: Vec<u8>, y: Vec<u8>| {
+        let modulus = decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        let mut encoding = vec![modulus.len() as u8];
+        encoding.extend(modulus);
+        encoding.extend(pad_for_len_be(vec![1], 32));
+        encoding.extend(pad_for_len_be(vec![0], 32));
+        encoding.push(order.len() as u8);
+        encoding.extend(order);
+        encoding.extend(pad_for_len_be(x, 32));
+        encoding.extend(pad_for_len_be(y, 32));
+        encoding.extend(pad_for_len_be(vec![2], 32));
+
+        encoding
+    };
+
+    let encoding = encode_mul(vec![0], vec![0]);
+    assert!(API::mul_point(&encoding[..]).is_err());
+    assert!(CachedAPI::new(1).mul_point(&encoding[..]).is_err());
+
+    let encoding = encode_mul(vec![3], decode("228aac9c1a871e92ed261943a31509ffe43913e8f5350b91fcc9692c5f710b6f").unwrap());
+    let mut expected = pad_for_len_be(decode(strip_0x_and_pad("ce78c62e6b7e671867bce52cd338f07e42282d1712f6936bab3588ea021549c")).unwrap(), 32);
+    expected.extend(decode("23f9aeedaf9387fea7c4b8a84fa5079c2d71a1c2bf0aee91f4cddbd7c8efe147").unwrap());
+    assert_eq!(API::mul_point(&encoding[..]).expect("must multiply"), expected);
+}
+
+#[test]
+fn test_mul_point_doesnt_depend_on_endomorphism() {
+    // curves y^2 = x^3 + b with the BN254 base field and group order, that only has
+    // this order for b = 3, and the generator (1, 2) that is multiplied with the
+    // endomorphism. Results are of plain double-and-add
+    let encode_mul = |b: u8, x: &str, y: &str| {
+        let modulus = decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        let mut encoding = vec![modulus.len() as u8];
+        encoding.extend(modulus);
+        encoding.extend(pad_for_len_be(vec![0], 32));
+        encoding.extend(pad_for_len_be(vec![b], 32));
+        encoding.push(order.len() as u8);
+        encoding.extend(order);
+        encoding.extend(pad_for_len_be(decode(strip_0x_and_pad(x)).unwrap(), 32));
+        encoding.extend(pad_for_len_be(decode(strip_0x_and_pad(y)).unwrap(), 32));
+        encoding.extend(decode("1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f809").unwrap());
+
+        encoding
+    };
+    let vectors = [
+        (2, "0x3", "0x2ffecaa516289797bda3a21257d498311fdc5aa385fe5343a52cd275f54c49aa",
+            "0xd60f80178f4c91ce64f0b6b6f2b1296784e64943ea4912d1efe45afd4a94b0c", "0x1c8130a28bff8136f74a48e1d166d6ec1f5b0cbe78565e487971e8057e840b33"),
+        (5, "0x2", "0x1c358800160da43b4a5d11dd7f22902e00d123051c13dd6b9293849d8f1d8634",
+            "0x190fd6f552ec4f0d0639239b64e17376d1e37975a97528dd3e358cbb12798a8e", "0x2c80115345d884479bdb074b28c6f34f3b9f01417404030c32ea9efa9991e6e9"),
+        (7, "0x1", "0x1ed6a916e1d82721466f07525097838fd187e5524cd1f233de2c483dbf4fb537",
+            "0xd4c4a2708a4234e9b3acb983e44e46f8f257dc847011167339479982ecafc54", "0x217a063483bcf6423b007aeb503a1318a340c1580105960e71cd693ebed17db0"),
+        (3, "0x1", "0x2",
+            "0xefe1347fd33f64819ae2b0c2ed0677fbe48798033d3afae9a4759e30f4e4837", "0x1cb3da1059d839009d2dc259b21ffbe7ec7366aabfd31681eb1c3b17d86923b"),
+    ];
+
+    for (b, x, y, result_x, result_y) in vectors.iter() {
+        let encoding = encode_mul(*b, x, y);
+        let mut expected = pad_for_len_be(decode(strip_0x_and_pad(result_x)).unwrap(), 32);
+        expected.extend(pad_for_len_be(decode(strip_0x_and_pad(result_y)).unwrap(), 32));
+        assert_eq!(API::mul_point(&encoding[..]).expect("must multiply"), expected);
+        assert_eq!(CachedAPI::new(1).mul_point(&encoding[..]).expect("must multiply"), expected);
+    }
+
+    // (1, 3) is not on the curve
+    let encoding = encode_mul(3, "0x1", "0x3");
+    assert!(API::mul_point(&encoding[..]).is_err());
+    assert!(CachedAPI::new(1).mul_point(&encoding[..]).is_err());
+}
+
+#[test]
+fn test_special_cases_of_addition() {
+    // y^2 = x^3 + 3 over the BN254 base field has prime order and goes through complete
+    // projective formulas, y^2 = x^3 - 1 has a point (1, 0) of order two and goes through
+    // Jacobian coordinates. (0, 0) is the point at infinity for both
+    let header = |b: &str| {
+        let modulus = decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47").unwrap();
+        let order = decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+        let mut encoding = vec![modulus.len() as u8];
+        encoding.extend(modulus);
+        encoding.extend(pad_for_len_be(vec![0], 32));
+        encoding.extend(pad_for_len_be(decode(strip_0x_and_pad(b)).unwrap(), 32));
+        encoding.push(order.len() as u8);
+        encoding.extend(order);
+
+        encoding
+    };
+    let point = |x: &str, y: &str| {
+        let mut encoding = pad_for_len_be(decode(strip_0x_and_pad(x)).unwrap(), 32);
+        encoding.extend(pad_for_len_be(decode(strip_0x_and_pad(y)).unwrap(), 32));
+
+        encoding
+    };
+    let scalar = |k: u8| pad_for_len_be(vec![k], 32);
+    let check = |operation: OperationType, parts: &[&Vec<u8>], expected: &Vec<u8>| {
+        let encoding: Vec<u8> = parts.iter().flat_map(|part| part.iter().cloned()).collect();
+        assert_eq!(&API::execute(operation, &encoding[..]).expect("must succeed"), expected);
+        assert_eq!(&CachedAPI::new(1).execute(operation, &encoding[..]).expect("must succeed"), expected);
+    };
+
+    let infinity = point("0x0", "0x0");
+    let curves = [
+        ("0x3", point("0x1", "0x2"), point("0x1", "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"),
+            point("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3", "0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4")),
+        ("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46",
+            point("0x2", "0x1dada9100531c64cbe18cee1c3fabfe5082f0ce8505483dc5b9d6fd2be57cae1"),
+            point("0x2", "0x12b6a562dbffd9dcfa3776d4bd8698788f525da9181d46b0e0831c441a253266"),
+            point("0x1ba70841a540edceb2770343b7b7a0357b256177a9659850b4a4e256329090bc", "0x268af498f5f89942ad60895e4300122cae7f531ed17b4bb57f68637abc7ff15c")),
+    ];
+    for (b, p, minus_p, two_p) in curves.iter() {
+        let header = header(b);
+
+        // P + P, P + (-P) and the point at infinity on either side
+        check(OperationType::AddPoints, &[&header, p, p], two_p);
+        check(OperationType::AddPoints, &[&header, p, minus_p], &infinity);
+        check(OperationType::AddPoints, &[&header, p, &infinity], p);
+        check(OperationType::AddPoints, &[&header, &infinity, p], p);
+        check(OperationType::AddPoints, &[&header, &infinity, &infinity], &infinity);
+
+        check(OperationType::MulPoint, &[&header, p, &scalar(2)], two_p);
+        check(OperationType::MulPoint, &[&header, p, &scalar(0)], &infinity);
+        check(OperationType::MulPoint, &[&header, &infinity, &scalar(2)], &infinity);
+
+        check(OperationType::Multiexp, &[&header, p, &scalar(1), p, &scalar(1)], two_p);
+        check(OperationType::Multiexp, &[&header, p, &scalar(1), minus_p, &scalar(1)], &infinity);
+        check(OperationType::Multiexp, &[&header, &infinity, &scalar(3), p, &scalar(2)], two_p);
+    }
+
+    // doubling of the point of order two
+    let header = header("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46");
+    let two_torsion = point("0x1", "0x0");
+    check(OperationType::AddPoints, &[&header, &two_torsion, &two_torsion], &infinity);
+    check(OperationType::MulPoint, &[&header, &two_torsion, &scalar(2)], &infinity);
+    check(OperationType::AddPoints, &[&header, &curves[1].1, &two_torsion], &point("0x4", "0x7bfa1b5b2cded6d36561ec7b712710c1675ae69dfe609856568c8b575f299eb"));
+}
+
+#[cfg(feature = "op_counter")]
+#[test]
+fn test_op_counts_from_csv() {
+    for (_, encoding, _) in read_mul_vectors() {
+        let (result, counts) = API::execute_with_op_counts(OperationType::MulPoint, &encoding[..]);
+        assert_eq!(result, API::mul_point(&encoding[..]));
+        assert!(counts.mul > 0);
+        assert!(counts.square > 0);
+        assert!(counts.add > 0);
+
+        // counts are deterministic for the same input
+        let (_, other_counts) = API::execute_with_op_counts(OperationType::MulPoint, &encoding[..]);
+        assert_eq!(counts, other_counts);
+    }
 }
 
 fn strip_0x_and_pad(string: &str) -> String {
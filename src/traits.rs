@@ -45,6 +45,46 @@ pub trait FieldElement:
     fn frobenius_map(&mut self, power: usize);
 }
 
+/// Selection without branches on the secret choice, for the code that should
+/// run in constant time, e.g. scalar multiplication by a secret key.
+pub trait ConditionallySelectable: Clone {
+    /// Replaces this element by `other` if `choice` is 1 and keeps it if `choice` is 0.
+    fn conditional_assign(&mut self, other: &Self, choice: u64);
+
+    /// Swaps two elements if `choice` is 1.
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u64) {
+        let t = a.clone();
+        a.conditional_assign(b, choice);
+        b.conditional_assign(&t, choice);
+    }
+}
+
+/// Field operations that don't branch on the values of elements. Only the
+/// constant-time code uses them, as they are slower than the usual ones
+pub trait ConstantTimeFieldElement: ConditionallySelectable {
+    /// Returns 1 if this element is zero and 0 otherwise.
+    fn is_zero_ct(&self) -> u64;
+
+    fn add_assign_ct(&mut self, other: &Self);
+
+    fn double_ct(&mut self);
+
+    fn sub_assign_ct(&mut self, other: &Self);
+
+    fn negate_ct(&mut self);
+
+    fn mul_assign_ct(&mut self, other: &Self);
+
+    fn square_ct(&mut self);
+}
+
+/// Expands a choice bit into an all-zeroes or all-ones mask. `black_box` keeps
+/// the compiler from turning the following masking back into a branch
+#[inline(always)]
+pub(crate) fn choice_mask(choice: u64) -> u64 {
+    std::hint::black_box(0u64.wrapping_sub(choice & 1))
+}
+
 pub trait FieldExtension {
     const EXTENSION_DEGREE: usize;
 
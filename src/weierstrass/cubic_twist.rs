@@ -20,10 +20,7 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         a: Fp3<'a, FE, F>, 
         b: Fp3<'a, FE, F>,
     ) -> Self {
-        let mut curve_type = CurveType::Generic;
-        if a.is_zero() {
-            curve_type = CurveType::AIsZero;
-        }
+        let curve_type = CurveType::from_coefficients(&a, &b, Fp3::one(extension_field));
 
         Self {
             base_field: extension_field,
@@ -586,25 +583,69 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         c.double();
         self.y.sub_assign(&c);
     }
+
+    fn double_a_is_minus_3_impl(&mut self) {
+        if self.is_zero() {
+            return;
+        }
+
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#doubling-dbl-2001-b
+
+        // delta = Z1^2
+        let mut delta = self.z.clone();
+        delta.square();
+
+        // gamma = Y1^2
+        let mut gamma = self.y.clone();
+        gamma.square();
+
+        // beta = X1*gamma
+        let mut beta = self.x.clone();
+        beta.mul_assign(&gamma);
+
+        // alpha = 3*(X1-delta)*(X1+delta)
+        let mut t0 = self.x.clone();
+        t0.sub_assign(&delta);
+        let mut alpha = self.x.clone();
+        alpha.add_assign(&delta);
+        alpha.mul_assign(&t0);
+        let t0 = alpha.clone();
+        alpha.double();
+        alpha.add_assign(&t0);
+
+        // X3 = alpha^2-8*beta
+        beta.double();
+        beta.double();
+        self.x = alpha.clone();
+        self.x.square();
+        self.x.sub_assign(&beta);
+        self.x.sub_assign(&beta);
+
+        // Z3 = (Y1+Z1)^2-gamma-delta
+        self.z.add_assign(&self.y);
+        self.z.square();
+        self.z.sub_assign(&gamma);
+        self.z.sub_assign(&delta);
+
+        // Y3 = alpha*(4*beta-X3)-8*gamma^2
+        self.y = beta;
+        self.y.sub_assign(&self.x);
+        self.y.mul_assign(&alpha);
+        gamma.square();
+        gamma.double();
+        gamma.double();
+        gamma.double();
+        self.y.sub_assign(&gamma);
+    }
 }
 
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Group for TwistPoint<'a, FE, F, GE, G> {
     fn add_assign(&mut self, other: &Self) {
-        match self.curve.curve_type {
-            _ => {
-                self.add_assign_generic_impl(&other);
-            },
-            _ => {unimplemented!()}
-        }
+        self.add_assign_generic_impl(other);
     }
 
     fn add_assign_mixed(&mut self, other: &Self) {
-        match self.curve.curve_type {
-            _ => {
-                self.add_assign_mixed_generic_impl(&other);
-            },
-            _ => {unimplemented!()}
-        }
+        self.add_assign_mixed_generic_impl(other);
     }
 
     fn sub_assign(&mut self, other: &Self) {
@@ -614,50 +655,32 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     }
 
     fn negate(&mut self) {
-        match self.curve.curve_type {
-            _ => {
-                self.negate_impl();
-            },
-            _ => {unimplemented!()}
-        }
+        self.negate_impl();
     }
 
     fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
-        match self.curve.curve_type {
-            _ => {
-                return self.mul_impl(exp);
-            },
-            _ => {unimplemented!()}
-        }
+        self.mul_impl(exp)
     }
 
     fn is_zero(&self) -> bool {
-        match self.curve.curve_type {
-            _ => {
-                return self.is_zero_generic_impl();
-            },
-            _ => {unimplemented!()}
-        }
+        self.is_zero_generic_impl()
     }
 
     fn double(&mut self) {
         match self.curve.curve_type {
-            CurveType::Generic => {
+            CurveType::Generic | CurveType::BIsZero => {
                 self.double_generic_impl();
             },
             CurveType::AIsZero => {
                 self.double_a_is_zero_impl();
+            },
+            CurveType::AIsMinus3 => {
+                self.double_a_is_minus_3_impl();
             }
-            _ => {unimplemented!()}
         }
     }
 
     fn wnaf_mul<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
-        match self.curve.curve_type {
-            _ => {
-                return self.wnaf_mul_impl(exp);
-            },
-            _ => {unimplemented!()}
-        }
+        self.wnaf_mul_impl(exp)
     }
 }
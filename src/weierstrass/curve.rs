@@ -1,15 +1,23 @@
 use crate::field::SizedPrimeField;
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator};
-use super::{CurveType, Group};
+use crate::traits::{FieldElement, BitIterator, ConditionallySelectable, ConstantTimeFieldElement};
+use super::{CurveType, Group, fixed_length_scalar};
+use super::projective::ConstantTimeFormulas;
+use super::glv::{GlvParameters, limbs_from_biguint};
+use crate::context::fp_from_mont_repr;
+use std::sync::OnceLock;
 
 pub struct WeierstrassCurve<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
     pub(crate) base_field: &'a F,
     pub(crate) scalar_field: &'a G,
     pub(crate) a: Fp<'a, FE, F>,
     pub(crate) b: Fp<'a, FE, F>,
-    pub(crate) curve_type: CurveType
+    pub(crate) curve_type: CurveType,
+    // derived on the first use, see `glv_parameters`
+    pub(crate) glv: OnceLock<Option<GlvParameters<FE>>>,
+    // derived on the first use, see `has_points_of_order_two`
+    pub(crate) two_torsion: OnceLock<bool>
 }
 
 
@@ -19,18 +27,182 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         a: Fp<'a, FE, F>, 
         b: Fp<'a, FE, F>,
     ) -> Self {
-        let mut curve_type = CurveType::Generic;
-        if a.is_zero() {
-            curve_type = CurveType::AIsZero;
-        }
+        let curve_type = CurveType::from_coefficients(&a, &b, Fp::one(a.field));
         Self {
             base_field: &a.field,
             scalar_field: scalar_field,
             a: a,
             b: b,
-            curve_type: curve_type
+            curve_type: curve_type,
+            glv: OnceLock::new(),
+            two_torsion: OnceLock::new()
+        }
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> WeierstrassCurve<'a, FE, F, GE, G> {
+    /// Parameters of the GLV endomorphism for a = 0 curves, derived on the first call
+    /// as it takes a few exponentiations and scalar multiplications
+    pub(crate) fn glv_parameters(&self) -> Option<&GlvParameters<FE>> {
+        self.glv.get_or_init(|| {
+            if self.curve_type == CurveType::AIsZero && !self.b.is_zero() {
+                GlvParameters::new(self.base_field, self.scalar_field).filter(|glv| self.glv_parameters_hold(glv))
+            } else {
+                None
+            }
+        }).as_ref()
+    }
+
+    /// Same as `glv_parameters` if the group order is r, so every point of the curve
+    /// is in the subgroup where the endomorphism acts as multiplication by lambda
+    pub(crate) fn glv_parameters_for_prime_order(&self) -> Option<&GlvParameters<FE>> {
+        self.glv_parameters().filter(|glv| glv.cofactor_is_one())
+    }
+
+    /// Checks if x^3 + a*x + b has a root x0, so (x0, 0) is a point of order two and
+    /// complete projective formulas don't apply. Roots of the cubic are the roots of
+    /// x^p - x, so there is one if their greatest common divisor is not a constant.
+    /// Derived on the first call as it takes an exponentiation of polynomials
+    pub(crate) fn has_points_of_order_two(&self) -> bool {
+        *self.two_torsion.get_or_init(|| {
+            if self.b.is_zero() {
+                return true;
+            }
+
+            let one = Fp::one(self.base_field);
+            let zero = Fp::zero(self.base_field);
+
+            // x^p modulo the cubic, coefficients are from the lowest
+            let x = vec![zero.clone(), one.clone()];
+            let mut power = vec![one.clone()];
+            for bit in BitIterator::new(self.base_field.modulus()) {
+                power = self.mul_modulo_cubic(&power, &power);
+                if bit {
+                    power = self.mul_modulo_cubic(&power, &x);
+                }
+            }
+            power[1].sub_assign(&one);
+            trim_polynomial(&mut power);
+
+            let mut divisor = power;
+            let mut dividend = vec![self.b.clone(), self.a.clone(), zero, one];
+            while !divisor.is_empty() {
+                let remainder = polynomial_remainder(&dividend, &divisor);
+                dividend = divisor;
+                divisor = remainder;
+            }
+
+            dividend.len() > 1
+        })
+    }
+
+    // u * v modulo x^3 + a*x + b, using x^3 = -a*x - b and x^4 = -a*x^2 - b*x
+    fn mul_modulo_cubic(&self, u: &[Fp<'a, FE, F>], v: &[Fp<'a, FE, F>]) -> Vec<Fp<'a, FE, F>> {
+        let mut product = vec![Fp::zero(self.base_field); 5];
+        for (i, u_i) in u.iter().enumerate() {
+            for (j, v_j) in v.iter().enumerate() {
+                let mut t = u_i.clone();
+                t.mul_assign(v_j);
+                product[i + j].add_assign(&t);
+            }
+        }
+
+        for (degree, coeff) in [(4, product[4].clone()), (3, product[3].clone())].iter() {
+            let mut t = self.a.clone();
+            t.mul_assign(coeff);
+            product[degree - 2].sub_assign(&t);
+            let mut t = self.b.clone();
+            t.mul_assign(coeff);
+            product[degree - 3].sub_assign(&t);
+        }
+        product.truncate(3);
+
+        product
+    }
+
+    /// `GlvParameters::new` only sees p and r, so it picks the one of six curves
+    /// y^2 = x^3 + b (sextic twists of each other) that has a subgroup of order r.
+    /// Checks on a point of this curve that it's the one: [h]P is not zero, has
+    /// order r and the endomorphism acts on it as multiplication by lambda
+    fn glv_parameters_hold(&self, glv: &GlvParameters<FE>) -> bool {
+        let one = Fp::one(self.base_field);
+        let mut legendre_exp = self.base_field.modulus();
+        legendre_exp.shr(1);
+        let order = self.scalar_field.modulus();
+        let cofactor = limbs_from_biguint(&glv.cofactor);
+        let lambda = limbs_from_biguint(&glv.lambda);
+
+        for x in 1u64..64 {
+            let x = match Fp::from_repr(self.base_field, FE::from(x)) {
+                Ok(x) => x,
+                Err(_) => return false
+            };
+            let mut s = x.clone();
+            s.square();
+            s.mul_assign(&x);
+            s.add_assign(&self.b);
+            if s.pow(legendre_exp) != one {
+                continue;
+            }
+
+            // Instead of taking a square root y of s = x^3 + b: the point (s*x, s^2) is on
+            // y^2 = x^3 + s^3 * b, that is isomorphic to this curve over Fp by
+            // (X, Y) -> (y^2 * X, y^3 * Y). The isomorphism keeps the group order and
+            // commutes with the endomorphism
+            let mut b = s.clone();
+            b.square();
+            b.mul_assign(&s);
+            b.mul_assign(&self.b);
+            let curve = WeierstrassCurve::new(self.scalar_field, self.a.clone(), b);
+            let mut point_x = s.clone();
+            point_x.mul_assign(&x);
+            let mut point_y = s.clone();
+            point_y.square();
+            let point = CurvePoint::point_from_xy(&curve, point_x, point_y).mul_impl(&cofactor[..]);
+            if point.is_zero() {
+                continue;
+            }
+            if !point.mul_impl(order).is_zero() {
+                return false;
+            }
+
+            let mut difference = point.endomorphism(glv);
+            difference.sub_assign(&point.mul_impl(lambda));
+
+            return difference.is_zero();
         }
+
+        false
+    }
+}
+
+fn trim_polynomial<FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(polynomial: &mut Vec<Fp<'_, FE, F>>) {
+    while polynomial.last().is_some_and(|coeff| coeff.is_zero()) {
+        polynomial.pop();
+    }
+}
+
+// remainder of the division by a polynomial with a nonzero leading coefficient
+fn polynomial_remainder<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(
+    dividend: &[Fp<'a, FE, F>],
+    divisor: &[Fp<'a, FE, F>]
+) -> Vec<Fp<'a, FE, F>> {
+    let leading_inverse = divisor[divisor.len() - 1].inverse().expect("leading coefficient is not zero");
+    let mut remainder = dividend.to_vec();
+    trim_polynomial(&mut remainder);
+    while remainder.len() >= divisor.len() {
+        let mut quotient = remainder[remainder.len() - 1].clone();
+        quotient.mul_assign(&leading_inverse);
+        let shift = remainder.len() - divisor.len();
+        for (i, coeff) in divisor.iter().enumerate() {
+            let mut t = coeff.clone();
+            t.mul_assign(&quotient);
+            remainder[shift + i].sub_assign(&t);
+        }
+        trim_polynomial(&mut remainder);
     }
+
+    remainder
 }
 
 pub struct CurvePoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
@@ -52,6 +224,15 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     }
 }
 
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> ConditionallySelectable for CurvePoint<'a, FE, F, GE, G> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.x.conditional_assign(&other.x, choice);
+        self.y.conditional_assign(&other.y, choice);
+        self.z.conditional_assign(&other.z, choice);
+    }
+}
+
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> CurvePoint<'a, FE, F, GE, G> {    
     pub fn zero(curve: &'a WeierstrassCurve<'a, FE, F, GE, G>) -> Self {
         Self {
@@ -353,6 +534,87 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         }
     }
 
+    /// Montgomery ladder for secret scalars. The point must be in the subgroup of
+    /// order r, and the scalar is reduced modulo r. Every scalar takes the same sequence
+    /// of conditional swaps and complete projective additions and doublings, and all
+    /// field operations are branchless. Only the point, that is public, is branched on
+    pub fn mul_constant_time<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        let (scalar, top_bit) = fixed_length_scalar(exp, self.curve.scalar_field);
+
+        // (X : Y : Z) in Jacobian coordinates is (X*Z : Y : Z^3) in homogeneous ones
+        let mut x = self.x.clone();
+        x.mul_assign(&self.z);
+        let mut z = self.z.clone();
+        z.square();
+        z.mul_assign(&self.z);
+
+        let formulas = ConstantTimeFormulas::new(&self.curve.a, &self.curve.b, self.curve.curve_type);
+        let [x, y, z] = formulas.ladder(&[x, self.y.clone(), z], &scalar, top_bit);
+
+        // back to (X*Z : Y*Z^2 : Z), the point at infinity (0 : Y : 0) becomes (0 : 0 : 0)
+        // and is replaced by the usual zero
+        let mut res_x = x;
+        res_x.mul_assign_ct(&z);
+        let mut res_y = z.clone();
+        res_y.square_ct();
+        res_y.mul_assign_ct(&y);
+        let is_zero = z.is_zero_ct();
+
+        let mut res = Self {
+            curve: self.curve,
+            x: res_x,
+            y: res_y,
+            z
+        };
+        res.conditional_assign(&Self::zero(self.curve), is_zero);
+
+        res
+    }
+
+    /// Multiplication with the GLV endomorphism when the curve has it, otherwise
+    /// falls back to double-and-add. The point must be in the subgroup of order r,
+    /// this is not checked, unlike in `mul`
+    pub fn mul_glv<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        match self.curve.glv_parameters() {
+            Some(glv) => self.mul_glv_impl(glv, exp),
+            None => self.mul_impl(exp)
+        }
+    }
+
+    /// Parameters of the endomorphism if it's known to act as multiplication by lambda
+    /// on this point: the point is affine and on the curve, and the cofactor is one, so
+    /// it's in the subgroup of order r. `point_from_xy` doesn't check the point, so
+    /// anything else goes through double-and-add
+    fn glv_parameters_for_mul(&self) -> Option<&GlvParameters<FE>> {
+        if self.z != Fp::one(self.curve.base_field) || !self.check_on_curve() {
+            return None;
+        }
+
+        self.curve.glv_parameters_for_prime_order()
+    }
+
+    /// (x, y) -> (beta * x, y), multiplication by lambda for points of the subgroup
+    fn endomorphism(&self, glv: &GlvParameters<FE>) -> Self {
+        self.sigma(&fp_from_mont_repr(self.curve.base_field, glv.beta))
+    }
+
+    /// (x, y) -> (beta * x, y) for a cube root of unity beta
+    pub(crate) fn sigma(&self, beta: &Fp<'a, FE, F>) -> Self {
+        let mut result = self.clone();
+        // x = X/Z^2, so it's enough to scale X
+        result.x.mul_assign(beta);
+
+        result
+    }
+
+    fn mul_glv_impl<S: AsRef<[u64]>>(&self, glv: &GlvParameters<FE>, exp: S) -> Self {
+        glv.mul(self, &self.endomorphism(glv), Self::zero(self.curve), exp.as_ref())
+    }
+
     fn mul_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
         let one = Fp::<'a, FE, F>::one(&self.curve.base_field);
         if self.z == one {
@@ -584,25 +846,69 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         c.double();
         self.y.sub_assign(&c);
     }
+
+    fn double_a_is_minus_3_impl(&mut self) {
+        if self.is_zero() {
+            return;
+        }
+
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#doubling-dbl-2001-b
+
+        // delta = Z1^2
+        let mut delta = self.z.clone();
+        delta.square();
+
+        // gamma = Y1^2
+        let mut gamma = self.y.clone();
+        gamma.square();
+
+        // beta = X1*gamma
+        let mut beta = self.x.clone();
+        beta.mul_assign(&gamma);
+
+        // alpha = 3*(X1-delta)*(X1+delta)
+        let mut t0 = self.x.clone();
+        t0.sub_assign(&delta);
+        let mut alpha = self.x.clone();
+        alpha.add_assign(&delta);
+        alpha.mul_assign(&t0);
+        let t0 = alpha.clone();
+        alpha.double();
+        alpha.add_assign(&t0);
+
+        // X3 = alpha^2-8*beta
+        beta.double();
+        beta.double();
+        self.x = alpha.clone();
+        self.x.square();
+        self.x.sub_assign(&beta);
+        self.x.sub_assign(&beta);
+
+        // Z3 = (Y1+Z1)^2-gamma-delta
+        self.z.add_assign(&self.y);
+        self.z.square();
+        self.z.sub_assign(&gamma);
+        self.z.sub_assign(&delta);
+
+        // Y3 = alpha*(4*beta-X3)-8*gamma^2
+        self.y = beta;
+        self.y.sub_assign(&self.x);
+        self.y.mul_assign(&alpha);
+        gamma.square();
+        gamma.double();
+        gamma.double();
+        gamma.double();
+        self.y.sub_assign(&gamma);
+    }
 }
 
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Group for CurvePoint<'a, FE, F, GE, G> {
     fn add_assign(&mut self, other: &Self) {
-        match self.curve.curve_type {
-            _ => {
-                self.add_assign_generic_impl(&other);
-            },
-            _ => {unimplemented!()}
-        }
+        self.add_assign_generic_impl(other);
     }
 
     fn add_assign_mixed(&mut self, other: &Self) {
-        match self.curve.curve_type {
-            _ => {
-                self.add_assign_mixed_generic_impl(&other);
-            },
-            _ => {unimplemented!()}
-        }
+        self.add_assign_mixed_generic_impl(other);
     }
 
     fn sub_assign(&mut self, other: &Self) {
@@ -612,50 +918,35 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     }
 
     fn negate(&mut self) {
-        match self.curve.curve_type {
-            _ => {
-                self.negate_impl();
-            },
-            _ => {unimplemented!()}
-        }
+        self.negate_impl();
     }
 
     fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
-        match self.curve.curve_type {
-            _ => {
-                return self.mul_impl(exp);
-            },
-            _ => {unimplemented!()}
+        match self.glv_parameters_for_mul() {
+            Some(glv) => self.mul_glv_impl(glv, exp),
+            None => self.mul_impl(exp)
         }
     }
 
     fn is_zero(&self) -> bool {
-        match self.curve.curve_type {
-            _ => {
-                return self.is_zero_generic_impl();
-            },
-            _ => {unimplemented!()}
-        }
+        self.is_zero_generic_impl()
     }
 
     fn double(&mut self) {
         match self.curve.curve_type {
-            CurveType::Generic => {
+            CurveType::Generic | CurveType::BIsZero => {
                 self.double_generic_impl();
             },
             CurveType::AIsZero => {
                 self.double_a_is_zero_impl();
+            },
+            CurveType::AIsMinus3 => {
+                self.double_a_is_minus_3_impl();
             }
-            _ => {unimplemented!()}
         }
     }
 
     fn wnaf_mul<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
-        match self.curve.curve_type {
-            _ => {
-                return self.wnaf_mul_impl(exp);
-            },
-            _ => {unimplemented!()}
-        }
+        self.wnaf_mul_impl(exp)
     }
 }
@@ -0,0 +1,165 @@
+use super::Group;
+use super::glv::{biguint_from_limbs, round_div, signed_limbs};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+
+// GLS endomorphism (https://eprint.iacr.org/2008/117.pdf) for G2 of BN and BLS12
+// curves: psi = untwist-Frobenius-twist acts on the points of order r as multiplication
+// by lambda = p mod r, that is 6u^2 for BN and x for BLS12 curves. A scalar is split as
+// k = k0 + k1 * lambda + k2 * lambda^2 + k3 * lambda^3 mod r with |k_i| about r^(1/4)
+// by Babai rounding against a short basis of {(k0, k1, k2, k3): sum k_i * lambda^i = 0 mod r}
+// that is known in terms of the curve parameter
+
+/// Signs (true for negative) and limbs of absolute values of four parts of a scalar
+pub(crate) type Decomposition = [(bool, Vec<u64>); 4];
+
+#[derive(Clone, Debug)]
+pub(crate) struct GlsParameters {
+    order: BigInt,
+    basis: [[BigInt; 4]; 4],
+    // first column of the adjugate of the basis, so (k, 0, 0, 0) is
+    // (k * adjugate / determinant) * basis
+    adjugate: [BigInt; 4],
+    determinant: BigInt,
+}
+
+pub(crate) fn signed_from_limbs(limbs: &[u64], is_negative: bool) -> BigInt {
+    let sign = if is_negative { Sign::Minus } else { Sign::Plus };
+
+    BigInt::from_biguint(sign, biguint_from_limbs(limbs))
+}
+
+fn determinant_3x3(m: [[&BigInt; 3]; 3]) -> BigInt {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+impl GlsParameters {
+    /// lambda = x, and x^4 - x^2 + 1 = r gives the basis
+    pub(crate) fn for_bls12(x: &[u64], x_is_negative: bool, order: &[u64]) -> Self {
+        let x = signed_from_limbs(x, x_is_negative);
+        let zero = BigInt::from(0);
+        let one = BigInt::from(1);
+
+        Self::from_basis([
+            [-&x, one.clone(), zero.clone(), zero.clone()],
+            [zero.clone(), -&x, one.clone(), zero.clone()],
+            [zero.clone(), zero.clone(), -&x, one.clone()],
+            [one, zero, BigInt::from(-1), x],
+        ], order)
+    }
+
+    /// lambda = 6u^2, basis from example 5 of https://eprint.iacr.org/2008/117.pdf.
+    /// It spans a sublattice of index 3, so the parts are a few bits longer
+    pub(crate) fn for_bn(u: &[u64], u_is_negative: bool, order: &[u64]) -> Self {
+        let u = signed_from_limbs(u, u_is_negative);
+        let one = BigInt::from(1);
+        let two_u = BigInt::from(2) * &u;
+
+        Self::from_basis([
+            [&u + &one, u.clone(), u.clone(), -&two_u],
+            [&two_u + &one, -&u, -(&u + &one), -&u],
+            [two_u.clone(), &two_u + &one, &two_u + &one, &two_u + &one],
+            [&u - &one, BigInt::from(4) * &u + BigInt::from(2), &one - &two_u, &u - &one],
+        ], order)
+    }
+
+    fn from_basis(basis: [[BigInt; 4]; 4], order: &[u64]) -> Self {
+        let mut adjugate = [BigInt::from(0), BigInt::from(0), BigInt::from(0), BigInt::from(0)];
+        let mut determinant = BigInt::from(0);
+        for j in 0..4 {
+            let mut rows = (0..4).filter(|i| *i != j).map(|i| [&basis[i][1], &basis[i][2], &basis[i][3]]);
+            let minor = determinant_3x3([rows.next().unwrap(), rows.next().unwrap(), rows.next().unwrap()]);
+            adjugate[j] = if j % 2 == 0 { minor } else { -minor };
+            determinant += &basis[j][0] * &adjugate[j];
+        }
+
+        Self {
+            order: signed_from_limbs(order, false),
+            basis,
+            adjugate,
+            determinant
+        }
+    }
+
+    /// Splits the scalar as k = k0 + k1 * lambda + k2 * lambda^2 + k3 * lambda^3 mod r
+    pub(crate) fn decompose(&self, scalar: &[u64]) -> Decomposition {
+        let k = signed_from_limbs(scalar, false).mod_floor(&self.order);
+        let c: Vec<BigInt> = self.adjugate.iter().map(|a| round_div(&(&k * a), &self.determinant)).collect();
+
+        let mut parts = [k, BigInt::from(0), BigInt::from(0), BigInt::from(0)];
+        for (c, vector) in c.iter().zip(self.basis.iter()) {
+            for (part, coordinate) in parts.iter_mut().zip(vector.iter()) {
+                *part -= c * coordinate;
+            }
+        }
+
+        let [k0, k1, k2, k3] = parts;
+
+        [signed_limbs(k0), signed_limbs(k1), signed_limbs(k2), signed_limbs(k3)]
+    }
+}
+
+/// Point and its three images under the endomorphism
+pub(crate) fn endomorphism_powers<G: Group + Clone, E: Fn(&G) -> G>(point: &G, endomorphism: E) -> [G; 4] {
+    let p1 = endomorphism(point);
+    let p2 = endomorphism(&p1);
+    let p3 = endomorphism(&p2);
+
+    [point.clone(), p1, p2, p3]
+}
+
+fn bit_length(limbs: &[u64]) -> usize {
+    match limbs.iter().rposition(|limb| *limb != 0) {
+        Some(top) => top * 64 + 64 - (limbs[top].leading_zeros() as usize),
+        None => 0
+    }
+}
+
+fn bit(limbs: &[u64], i: usize) -> usize {
+    limbs.get(i / 64).map(|limb| ((limb >> (i % 64)) & 1) as usize).unwrap_or(0)
+}
+
+/// Simultaneous multiplication (Straus) of groups of four points by four short
+/// scalars. Signs of the scalars are moved to the points and every group gets a
+/// table of all 16 subset sums. Doublings are shared between all groups, so this
+/// serves both a single multiplication and a multiexp
+pub(crate) fn mul_4_way<G: Group + Clone>(zero: &G, terms: Vec<([G; 4], Decomposition)>) -> G {
+    let mut tables = Vec::with_capacity(terms.len());
+    let mut num_bits = 0;
+    for (points, parts) in terms.into_iter() {
+        let mut table = vec![zero.clone(); 16];
+        let mut scalars = vec![];
+        for (i, (point, (is_negative, scalar))) in points.iter().zip(parts.iter()).enumerate() {
+            table[1 << i] = point.clone();
+            if *is_negative {
+                table[1 << i].negate();
+            }
+            num_bits = num_bits.max(bit_length(scalar));
+            scalars.push(scalar.clone());
+        }
+        for index in 3..16usize {
+            if !index.is_power_of_two() {
+                let lowest = index & index.wrapping_neg();
+                let mut sum = table[index - lowest].clone();
+                sum.add_assign(&table[lowest]);
+                table[index] = sum;
+            }
+        }
+        tables.push((table, scalars));
+    }
+
+    let mut acc = zero.clone();
+    for i in (0..num_bits).rev() {
+        acc.double();
+        for (table, scalars) in tables.iter() {
+            let index = scalars.iter().enumerate().fold(0, |index, (j, scalar)| index | (bit(scalar, i) << j));
+            if index != 0 {
+                acc.add_assign(&table[index]);
+            }
+        }
+    }
+
+    acc
+}
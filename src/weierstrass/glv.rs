@@ -0,0 +1,281 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::FieldElement;
+use super::Group;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+// GLV endomorphism (https://www.iacr.org/archive/crypto2001/21390189.pdf) for
+// y^2 = x^3 + b over Fp with p = 1 mod 3: phi(x, y) = (beta * x, y) for a cube root
+// of unity beta acts on the points of order r as multiplication by lambda with
+// lambda^2 + lambda + 1 = 0 mod r.
+//
+// Matching of beta and lambda (out of two roots each) doesn't need a point. phi acts
+// on the invariant differential dx/y as beta and Frobenius acts as zero, so Frobenius
+// is c + d * phi with c + d * beta = 0 mod p and c^2 - c*d + d^2 = p, i.e. the
+// shortest vector of the lattice {(c, d): c + d * beta = 0 mod p}, up to one of six
+// units. The unit is picked by r | #E = p + 1 - (2c - d), and as Frobenius fixes the
+// points of E(Fp), c + d * lambda = 1 mod r. This also gives the cofactor #E / r
+
+#[derive(Clone, Debug)]
+pub struct GlvParameters<FE: ElementRepr> {
+    // in Montgomery form
+    pub(crate) beta: FE,
+    pub(crate) lambda: BigUint,
+    pub(crate) cofactor: BigUint,
+    // short basis of {(k1, k2): k1 + k2 * lambda = 0 mod r} and its determinant
+    basis: [(BigInt, BigInt); 2],
+    determinant: BigInt,
+}
+
+pub(crate) fn biguint_from_limbs(limbs: &[u64]) -> BigUint {
+    limbs.iter().rev().fold(BigUint::zero(), |acc, limb| (acc << 64) + BigUint::from(*limb))
+}
+
+pub(crate) fn limbs_from_biguint(value: &BigUint) -> Vec<u64> {
+    value.to_bytes_le().chunks(8).map(|chunk| {
+        chunk.iter().rev().fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte))
+    }).collect()
+}
+
+// norm form c^2 - c*d + d^2 of c + d * omega and twice its bilinear form, to stay in integers
+fn norm(u: &(BigInt, BigInt)) -> BigInt {
+    &u.0 * &u.0 - &u.0 * &u.1 + &u.1 * &u.1
+}
+
+fn double_bilinear(u: &(BigInt, BigInt), v: &(BigInt, BigInt)) -> BigInt {
+    BigInt::from(2) * &u.0 * &v.0 - &u.0 * &v.1 - &u.1 * &v.0 + BigInt::from(2) * &u.1 * &v.1
+}
+
+// rounded n / d
+pub(crate) fn round_div(n: &BigInt, d: &BigInt) -> BigInt {
+    let (n, d) = if d.sign() == Sign::Minus { (-n, -d) } else { (n.clone(), d.clone()) };
+
+    (BigInt::from(2) * n + &d).div_floor(&(BigInt::from(2) * d))
+}
+
+/// Value modulo p as an element of the field if it's a primitive cube root of unity,
+/// for beta given by a polynomial in a parameter of the curve family
+pub(crate) fn cube_root_of_unity<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>>(field: &'a F, value: &BigInt) -> Option<Fp<'a, FE, F>> {
+    let p = BigInt::from_biguint(Sign::Plus, biguint_from_limbs(field.modulus().as_ref()));
+    let beta = Fp::from_be_bytes(field, &value.mod_floor(&p).to_biguint()?.to_bytes_be(), true).ok()?;
+
+    // beta^2 + beta + 1 = 0
+    let mut t = beta.clone();
+    t.square();
+    t.add_assign(&beta);
+    t.add_assign(&Fp::one(field));
+
+    if t.is_zero() { Some(beta) } else { None }
+}
+
+/// Lagrange-Gauss reduction of a two dimensional lattice with respect to the norm
+/// form, returns the basis with the shortest vector first
+fn reduce_basis(u: (BigInt, BigInt), v: (BigInt, BigInt)) -> [(BigInt, BigInt); 2] {
+    let (mut u, mut v) = if norm(&u) < norm(&v) { (v, u) } else { (u, v) };
+    loop {
+        let q = round_div(&double_bilinear(&u, &v), &(BigInt::from(2) * norm(&v)));
+        u = (&u.0 - &q * &v.0, &u.1 - &q * &v.1);
+        if norm(&u) >= norm(&v) {
+            return [v, u];
+        }
+        std::mem::swap(&mut u, &mut v);
+    }
+}
+
+impl<FE: ElementRepr> GlvParameters<FE> {
+    /// Derives parameters for y^2 = x^3 + b over the `field` with a prime subgroup
+    /// of the `order`. Returns `None` if there is no such endomorphism or if the
+    /// order doesn't divide the number of points on the curve
+    pub(crate) fn new<F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>>(field: &F, scalar_field: &G) -> Option<Self> {
+        let p = biguint_from_limbs(field.modulus().as_ref());
+        let r = biguint_from_limbs(scalar_field.modulus().as_ref());
+        let three = BigUint::from(3u64);
+        if (&p % &three) != BigUint::one() || (&r % &three) != BigUint::one() {
+            return None;
+        }
+
+        // beta = g^((p - 1)/3) for any g that is not a cube
+        let exp = limbs_from_biguint(&((&p - BigUint::one()) / &three));
+        let mut beta = None;
+        for g in 2u64..64 {
+            let candidate = Fp::from_repr(field, FE::from(g)).ok()?.pow(&exp);
+            if candidate != Fp::one(field) {
+                beta = Some(candidate);
+                break;
+            }
+        }
+        let beta = beta?;
+        let beta_int = biguint_from_limbs(beta.into_repr().as_ref());
+
+        let p_int = BigInt::from_biguint(Sign::Plus, p.clone());
+        let r_int = BigInt::from_biguint(Sign::Plus, r.clone());
+        let [(c, d), _] = reduce_basis(
+            (p_int.clone(), BigInt::zero()),
+            (-BigInt::from_biguint(Sign::Plus, beta_int), BigInt::one())
+        );
+        if norm(&(c.clone(), d.clone())) != p_int {
+            return None;
+        }
+
+        // multiplication by the unit omega is (c, d) -> (-d, c - d)
+        let mut frobenius = None;
+        let (mut c, mut d) = (c, d);
+        for _ in 0..3 {
+            for (c, d) in [(c.clone(), d.clone()), (-&c, -&d)].iter() {
+                let num_points = &p_int + BigInt::one() - BigInt::from(2) * c + d;
+                if num_points.sign() == Sign::Plus && num_points.is_multiple_of(&r_int) {
+                    frobenius = Some((c.clone(), d.clone(), num_points / &r_int));
+                }
+            }
+            let next_c = -&d;
+            d = &c - &d;
+            c = next_c;
+        }
+        let (c, d, cofactor) = frobenius?;
+
+        // lambda = (1 - c) / d mod r
+        let to_scalar = |value: BigInt| {
+            Fp::from_be_bytes(scalar_field, &value.mod_floor(&r_int).to_biguint()?.to_bytes_be(), true).ok()
+        };
+        let mut lambda = to_scalar(d)?.inverse()?;
+        lambda.mul_assign(&to_scalar(BigInt::one() - c)?);
+        let mut t = lambda.clone();
+        t.square();
+        t.add_assign(&lambda);
+        t.add_assign(&Fp::one(scalar_field));
+        if !t.is_zero() {
+            return None;
+        }
+        let lambda = biguint_from_limbs(lambda.into_repr().as_ref());
+
+        let basis = reduce_basis(
+            (r_int, BigInt::zero()),
+            (-BigInt::from_biguint(Sign::Plus, lambda.clone()), BigInt::one())
+        );
+        let determinant = &basis[0].0 * &basis[1].1 - &basis[1].0 * &basis[0].1;
+
+        Some(Self {
+            beta: beta.repr,
+            lambda,
+            cofactor: cofactor.to_biguint()?,
+            basis,
+            determinant
+        })
+    }
+
+    /// True if every point of the curve is in the subgroup
+    pub(crate) fn cofactor_is_one(&self) -> bool {
+        self.cofactor.is_one()
+    }
+
+    /// Splits the scalar as k = k1 + k2 * lambda mod r with |k1|, |k2| about sqrt(r).
+    /// Returns signs (true for negative) and limbs of absolute values
+    pub(crate) fn decompose(&self, scalar: &[u64]) -> ((bool, Vec<u64>), (bool, Vec<u64>)) {
+        let k = BigInt::from_biguint(Sign::Plus, biguint_from_limbs(scalar));
+        let [(a1, b1), (a2, b2)] = &self.basis;
+
+        // (k, 0) = c1 * (a1, b1) + c2 * (a2, b2) over rationals, rounded
+        let c1 = round_div(&(&k * b2), &self.determinant);
+        let c2 = round_div(&(-&k * b1), &self.determinant);
+
+        let k1 = k - &c1 * a1 - &c2 * a2;
+        let k2 = -(&c1 * b1) - &c2 * b2;
+
+        (signed_limbs(k1), signed_limbs(k2))
+    }
+
+    /// Multiplication of a point of the subgroup of order r by decomposing the scalar
+    /// and running over the joint sparse form of both halves. `image` is the endomorphism
+    /// applied to the point, same for any representation of points
+    pub(crate) fn mul<P: Group + Clone>(&self, point: &P, image: &P, zero: P, exp: &[u64]) -> P {
+        let ((k1_is_negative, k1), (k2_is_negative, k2)) = self.decompose(exp);
+
+        let mut p1 = point.clone();
+        if k1_is_negative {
+            p1.negate();
+        }
+        let mut p2 = image.clone();
+        if k2_is_negative {
+            p2.negate();
+        }
+        let mut sum = p1.clone();
+        sum.add_assign(&p2);
+        let mut difference = p1.clone();
+        difference.sub_assign(&p2);
+
+        let mut res = zero;
+        for digits in joint_sparse_form(&k1, &k2).into_iter().rev() {
+            res.double();
+            match digits {
+                (0, 0) => {},
+                (1, 0) => res.add_assign(&p1),
+                (-1, 0) => res.sub_assign(&p1),
+                (0, 1) => res.add_assign(&p2),
+                (0, -1) => res.sub_assign(&p2),
+                (1, 1) => res.add_assign(&sum),
+                (-1, -1) => res.sub_assign(&sum),
+                (1, -1) => res.add_assign(&difference),
+                (-1, 1) => res.sub_assign(&difference),
+                _ => unreachable!("digits of the joint sparse form are -1, 0 or 1")
+            }
+        }
+
+        res
+    }
+}
+
+/// Sign (true for negative) and limbs of the absolute value
+pub(crate) fn signed_limbs(k: BigInt) -> (bool, Vec<u64>) {
+    let is_negative = k.sign() == Sign::Minus;
+    let magnitude = if is_negative { -k } else { k };
+
+    // can not fail for a non-negative number
+    (is_negative, limbs_from_biguint(&magnitude.to_biguint().unwrap()))
+}
+
+fn low_bits(limbs: &[u64]) -> i64 {
+    (limbs.first().cloned().unwrap_or(0) & 7) as i64
+}
+
+fn shift_right(limbs: &mut [u64]) {
+    let mut carry = 0;
+    for limb in limbs.iter_mut().rev() {
+        let next_carry = *limb << 63;
+        *limb = (*limb >> 1) | carry;
+        carry = next_carry;
+    }
+}
+
+/// Joint sparse form of two non-negative scalars (Solinas, Algorithm 3.50 in Guide
+/// to Elliptic Curve Cryptography), digits are in {-1, 0, 1} and least significant
+/// first. On average half of the columns are nonzero
+pub(crate) fn joint_sparse_form(k0: &[u64], k1: &[u64]) -> Vec<(i8, i8)> {
+    let mut k = [k0.to_vec(), k1.to_vec()];
+    let mut d = [0i64; 2];
+    let mut digits = vec![];
+
+    while k.iter().zip(d.iter()).any(|(k, d)| *d != 0 || k.iter().any(|limb| *limb != 0)) {
+        let l = [(d[0] + low_bits(&k[0])) & 7, (d[1] + low_bits(&k[1])) & 7];
+        let mut u = [0i64; 2];
+        for i in 0..2 {
+            if l[i] & 1 == 1 {
+                u[i] = if l[i] & 3 == 1 { 1 } else { -1 };
+                if (l[i] == 3 || l[i] == 5) && (l[1 - i] & 3) == 2 {
+                    u[i] = -u[i];
+                }
+            }
+        }
+        for i in 0..2 {
+            if 2 * d[i] == 1 + u[i] {
+                d[i] = 1 - d[i];
+            }
+            shift_right(&mut k[i]);
+        }
+        digits.push((u[0] as i8, u[1] as i8));
+    }
+
+    digits
+}
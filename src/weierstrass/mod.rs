@@ -1,3 +1,8 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{ConditionallySelectable, ConstantTimeFieldElement};
+
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum CurveType {
     Generic,
@@ -6,6 +11,82 @@ pub enum CurveType {
     BIsZero,
 }
 
+impl CurveType {
+    /// Picks the formulas for y^2 = x^3 + a*x + b. Singular a = b = 0 is left as
+    /// `AIsZero`, and for b = 0 there is a point (0, 0) of order two
+    pub(crate) fn from_coefficients<E: crate::traits::FieldElement>(a: &E, b: &E, one: E) -> Self {
+        if a.is_zero() {
+            return CurveType::AIsZero;
+        }
+        if b.is_zero() {
+            return CurveType::BIsZero;
+        }
+
+        let mut minus_three = one.clone();
+        minus_three.double();
+        minus_three.add_assign(&one);
+        minus_three.negate();
+        if *a == minus_three {
+            return CurveType::AIsMinus3;
+        }
+
+        CurveType::Generic
+    }
+}
+
+/// Reduces a scalar of any length modulo r and recodes the result k as k + r or k + 2r,
+/// whichever has the top bit at position bits(r). Both give the same multiple of a point
+/// of order r, but the ladder then runs the same number of steps for every scalar.
+/// Returns limbs and the top bit position
+pub(crate) fn fixed_length_scalar<S: AsRef<[u64]>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>>(
+    scalar: S,
+    scalar_field: &G
+) -> (Vec<u64>, usize) {
+    let scalar = scalar.as_ref();
+    let order = scalar_field.modulus();
+    let order = order.as_ref();
+
+    // Horner's rule over the bits of the scalar. Addition modulo r doesn't depend on
+    // the Montgomery form, so the plain value of k mod r is kept in the representation
+    let mut reduced = Fp::zero(scalar_field);
+    let one = Fp {
+        field: scalar_field,
+        repr: GE::from(1)
+    };
+    for i in (0..scalar.len() * 64).rev() {
+        reduced.double_ct();
+        let mut plus_one = reduced.clone();
+        plus_one.add_assign_ct(&one);
+        reduced.conditional_assign(&plus_one, (scalar[i / 64] >> (i % 64)) & 1);
+    }
+
+    // one limb on top is enough for k + 2r < 3r
+    let num_limbs = order.len() + 1;
+
+    let top_limb = order.iter().rposition(|limb| *limb != 0).expect("order is not zero");
+    let top_bit = top_limb * 64 + 64 - (order[top_limb].leading_zeros() as usize);
+
+    let add_order = |value: &[u64]| -> Vec<u64> {
+        let mut result = vec![0u64; num_limbs];
+        let mut carry = 0u64;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let a = value.get(i).cloned().unwrap_or(0);
+            let b = order.get(i).cloned().unwrap_or(0);
+            *limb = crate::arithmetics::adc(a, b, &mut carry);
+        }
+
+        result
+    };
+
+    let k_plus_r = add_order(reduced.repr.as_ref());
+    let k_plus_2r = add_order(&k_plus_r);
+
+    let mask = crate::traits::choice_mask(k_plus_r[top_bit / 64] >> (top_bit % 64));
+    let result = k_plus_r.iter().zip(k_plus_2r.iter()).map(|(a, b)| (a & mask) | (b & !mask)).collect();
+
+    (result, top_bit)
+}
+
 pub trait Group: Sized {
     fn add_assign(&mut self, other: &Self);
     fn add_assign_mixed(&mut self, other: &Self);
@@ -18,5 +99,9 @@ pub trait Group: Sized {
 }
 
 pub mod curve;
+pub mod projective;
+pub mod glv;
+pub mod gls;
 pub mod twist;
-pub mod cubic_twist;
\ No newline at end of file
+pub mod cubic_twist;
+pub mod quartic_twist;
\ No newline at end of file
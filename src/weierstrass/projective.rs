@@ -0,0 +1,1017 @@
+use crate::field::SizedPrimeField;
+use crate::fp::Fp;
+use crate::representation::ElementRepr;
+use crate::traits::{FieldElement, BitIterator, ConstantTimeFieldElement};
+use super::{CurveType, Group};
+use super::curve::{WeierstrassCurve, CurvePoint};
+use crate::context::fp_from_mont_repr;
+
+// Points in homogeneous projective coordinates (x = X/Z, y = Y/Z) with complete
+// addition formulas from https://eprint.iacr.org/2015/1060.pdf. The same formula
+// covers P + P, P + (-P) and the point at infinity (0 : 1 : 0), so there are no
+// branches on the values of coordinates. Formulas are complete for curves without
+// points of order two, e.g. any curve of odd (prime) order
+
+pub struct ProjectivePoint<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
+    pub(crate) curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+    pub(crate) x: Fp<'a, FE, F>,
+    pub(crate) y: Fp<'a, FE, F>,
+    pub(crate) z: Fp<'a, FE, F>,
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Clone for ProjectivePoint<'a, FE, F, GE, G> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            curve: self.curve,
+            x: self.x.clone(),
+            y: self.y.clone(),
+            z: self.z.clone()
+        }
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> ProjectivePoint<'a, FE, F, GE, G> {
+    pub fn zero(curve: &'a WeierstrassCurve<'a, FE, F, GE, G>) -> Self {
+        Self {
+            curve,
+            x: Fp::zero(curve.base_field),
+            y: Fp::one(curve.base_field),
+            z: Fp::zero(curve.base_field),
+        }
+    }
+
+    pub fn point_from_xy(
+        curve: &'a WeierstrassCurve<'a, FE, F, GE, G>,
+        x: Fp<'a, FE, F>,
+        y: Fp<'a, FE, F>
+    ) -> Self {
+        Self {
+            curve,
+            x,
+            y,
+            z: Fp::one(curve.base_field)
+        }
+    }
+
+    /// Converts from Jacobian coordinates, (X : Y : Z) -> (X*Z : Y : Z^3)
+    pub fn from_jacobian(point: &CurvePoint<'a, FE, F, GE, G>) -> Self {
+        if point.is_zero() {
+            return Self::zero(point.curve);
+        }
+
+        let mut x = point.x.clone();
+        x.mul_assign(&point.z);
+
+        let mut z = point.z.clone();
+        z.square();
+        z.mul_assign(&point.z);
+
+        Self {
+            curve: point.curve,
+            x,
+            y: point.y.clone(),
+            z
+        }
+    }
+
+    /// Converts into Jacobian coordinates, (X : Y : Z) -> (X*Z : Y*Z^2 : Z)
+    pub fn to_jacobian(&self) -> CurvePoint<'a, FE, F, GE, G> {
+        if self.is_zero() {
+            return CurvePoint::zero(self.curve);
+        }
+
+        let mut x = self.x.clone();
+        x.mul_assign(&self.z);
+
+        let mut y = self.z.clone();
+        y.square();
+        y.mul_assign(&self.y);
+
+        CurvePoint {
+            curve: self.curve,
+            x,
+            y,
+            z: self.z.clone()
+        }
+    }
+
+    pub fn check_on_curve(&self) -> bool {
+        // Y^2*Z = X^3 + a*X*Z^2 + b*Z^3
+        let mut lhs = self.y.clone();
+        lhs.square();
+        lhs.mul_assign(&self.z);
+
+        let mut z_2 = self.z.clone();
+        z_2.square();
+
+        let mut rhs = self.x.clone();
+        rhs.square();
+        let mut t0 = self.curve.a.clone();
+        t0.mul_assign(&z_2);
+        rhs.add_assign(&t0);
+        rhs.mul_assign(&self.x);
+        let mut t0 = self.curve.b.clone();
+        t0.mul_assign(&z_2);
+        t0.mul_assign(&self.z);
+        rhs.add_assign(&t0);
+
+        lhs == rhs
+    }
+
+    pub fn is_normalized(&self) -> bool {
+        if self.is_zero() {
+            return true;
+        }
+
+        self.z == Fp::one(self.curve.base_field)
+    }
+
+    pub fn normalize(&mut self) {
+        if self.is_normalized() {
+            return;
+        }
+
+        let z_inv = self.z.inverse().unwrap();
+        self.x.mul_assign(&z_inv);
+        self.y.mul_assign(&z_inv);
+        self.z = Fp::one(self.curve.base_field);
+    }
+
+    pub fn into_xy(&self) -> (Fp<'a, FE, F>, Fp<'a, FE, F>) {
+        if self.is_zero() {
+            return (Fp::zero(self.curve.base_field), Fp::zero(self.curve.base_field));
+        }
+
+        let mut point = self.clone();
+        point.normalize();
+
+        (point.x, point.y)
+    }
+
+    // 3*b
+    fn b3(&self) -> Fp<'a, FE, F> {
+        let mut b3 = self.curve.b.clone();
+        b3.double();
+        b3.add_assign(&self.curve.b);
+
+        b3
+    }
+
+    fn add_assign_generic_impl(&mut self, other: &Self) {
+        // Algorithm 1 of https://eprint.iacr.org/2015/1060.pdf
+        let a = &self.curve.a;
+        let b3 = self.b3();
+
+        // t0 = X1*X2, t1 = Y1*Y2, t2 = Z1*Z2
+        let mut t0 = self.x.clone();
+        t0.mul_assign(&other.x);
+        let mut t1 = self.y.clone();
+        t1.mul_assign(&other.y);
+        let mut t2 = self.z.clone();
+        t2.mul_assign(&other.z);
+
+        // t3 = (X1+Y1)*(X2+Y2) - t0 - t1
+        let mut t3 = self.x.clone();
+        t3.add_assign(&self.y);
+        let mut t4 = other.x.clone();
+        t4.add_assign(&other.y);
+        t3.mul_assign(&t4);
+        t3.sub_assign(&t0);
+        t3.sub_assign(&t1);
+
+        // t4 = (X1+Z1)*(X2+Z2) - t0 - t2
+        let mut t4 = self.x.clone();
+        t4.add_assign(&self.z);
+        let mut t5 = other.x.clone();
+        t5.add_assign(&other.z);
+        t4.mul_assign(&t5);
+        t4.sub_assign(&t0);
+        t4.sub_assign(&t2);
+
+        // t5 = (Y1+Z1)*(Y2+Z2) - t1 - t2
+        let mut t5 = self.y.clone();
+        t5.add_assign(&self.z);
+        let mut x3 = other.y.clone();
+        x3.add_assign(&other.z);
+        t5.mul_assign(&x3);
+        t5.sub_assign(&t1);
+        t5.sub_assign(&t2);
+
+        self.add_assign_generic_finish(a, &b3, t0, t1, t2, t3, t4, t5);
+    }
+
+    fn add_assign_mixed_generic_impl(&mut self, other: &Self) {
+        // Algorithm 2 of https://eprint.iacr.org/2015/1060.pdf, Z2 = 1
+        let a = &self.curve.a;
+        let b3 = self.b3();
+
+        // t0 = X1*X2, t1 = Y1*Y2
+        let mut t0 = self.x.clone();
+        t0.mul_assign(&other.x);
+        let mut t1 = self.y.clone();
+        t1.mul_assign(&other.y);
+
+        // t3 = (X1+Y1)*(X2+Y2) - t0 - t1
+        let mut t3 = other.x.clone();
+        t3.add_assign(&other.y);
+        let mut t4 = self.x.clone();
+        t4.add_assign(&self.y);
+        t3.mul_assign(&t4);
+        t3.sub_assign(&t0);
+        t3.sub_assign(&t1);
+
+        // t4 = X2*Z1 + X1
+        let mut t4 = other.x.clone();
+        t4.mul_assign(&self.z);
+        t4.add_assign(&self.x);
+
+        // t5 = Y2*Z1 + Y1
+        let mut t5 = other.y.clone();
+        t5.mul_assign(&self.z);
+        t5.add_assign(&self.y);
+
+        let t2 = self.z.clone();
+
+        self.add_assign_generic_finish(a, &b3, t0, t1, t2, t3, t4, t5);
+    }
+
+    // common tail of the Algorithms 1 and 2 for t0 = X1*X2, t1 = Y1*Y2, t2 = Z1*Z2,
+    // t3 = X1*Y2 + X2*Y1, t4 = X1*Z2 + X2*Z1, t5 = Y1*Z2 + Y2*Z1
+    #[allow(clippy::too_many_arguments)]
+    fn add_assign_generic_finish(
+        &mut self,
+        a: &Fp<'a, FE, F>,
+        b3: &Fp<'a, FE, F>,
+        mut t0: Fp<'a, FE, F>,
+        mut t1: Fp<'a, FE, F>,
+        mut t2: Fp<'a, FE, F>,
+        t3: Fp<'a, FE, F>,
+        mut t4: Fp<'a, FE, F>,
+        t5: Fp<'a, FE, F>
+    ) {
+        // Z3 = a*t4 + b3*t2
+        let mut z3 = a.clone();
+        z3.mul_assign(&t4);
+        let mut x3 = b3.clone();
+        x3.mul_assign(&t2);
+        z3.add_assign(&x3);
+
+        // X3 = t1 - Z3, Z3 = t1 + Z3, Y3 = X3*Z3
+        let mut x3 = t1.clone();
+        x3.sub_assign(&z3);
+        z3.add_assign(&t1);
+        let mut y3 = x3.clone();
+        y3.mul_assign(&z3);
+
+        // t1 = 3*t0 + a*t2
+        t1 = t0.clone();
+        t1.double();
+        t1.add_assign(&t0);
+        t2.mul_assign(a);
+        t1.add_assign(&t2);
+
+        // t4 = b3*t4 + a*(t0 - a*t2)
+        t4.mul_assign(b3);
+        let mut t6 = t0.clone();
+        t6.sub_assign(&t2);
+        t6.mul_assign(a);
+        t4.add_assign(&t6);
+
+        // Y3 = Y3 + t1*t4
+        t0 = t1.clone();
+        t0.mul_assign(&t4);
+        y3.add_assign(&t0);
+
+        // X3 = t3*X3 - t5*t4
+        t4.mul_assign(&t5);
+        x3.mul_assign(&t3);
+        x3.sub_assign(&t4);
+
+        // Z3 = t5*Z3 + t3*t1
+        z3.mul_assign(&t5);
+        t1.mul_assign(&t3);
+        z3.add_assign(&t1);
+
+        self.x = x3;
+        self.y = y3;
+        self.z = z3;
+    }
+
+    fn double_generic_impl(&mut self) {
+        // Algorithm 3 of https://eprint.iacr.org/2015/1060.pdf
+        let a = &self.curve.a;
+        let b3 = self.b3();
+
+        // t0 = X^2, t1 = Y^2, t2 = Z^2
+        let mut t0 = self.x.clone();
+        t0.square();
+        let mut t1 = self.y.clone();
+        t1.square();
+        let mut t2 = self.z.clone();
+        t2.square();
+
+        // t3 = 2*X*Y, Z3 = 2*X*Z
+        let mut t3 = self.x.clone();
+        t3.mul_assign(&self.y);
+        t3.double();
+        let mut z3 = self.x.clone();
+        z3.mul_assign(&self.z);
+        z3.double();
+
+        // Y3 = a*Z3 + b3*t2
+        let mut x3 = a.clone();
+        x3.mul_assign(&z3);
+        let mut y3 = b3.clone();
+        y3.mul_assign(&t2);
+        y3.add_assign(&x3);
+
+        // X3 = t1 - Y3, Y3 = (t1 - Y3)*(t1 + Y3), X3 = t3*X3
+        let mut x3 = t1.clone();
+        x3.sub_assign(&y3);
+        y3.add_assign(&t1);
+        y3.mul_assign(&x3);
+        x3.mul_assign(&t3);
+
+        // t3 = a*(t0 - a*t2) + b3*Z3
+        z3.mul_assign(&b3);
+        t2.mul_assign(a);
+        let mut t3 = t0.clone();
+        t3.sub_assign(&t2);
+        t3.mul_assign(a);
+        t3.add_assign(&z3);
+
+        // Y3 = Y3 + (3*t0 + t2)*t3
+        let mut t4 = t0.clone();
+        t4.double();
+        t4.add_assign(&t0);
+        t4.add_assign(&t2);
+        t4.mul_assign(&t3);
+        y3.add_assign(&t4);
+
+        // t2 = 2*Y*Z, X3 = X3 - t2*t3, Z3 = 4*t2*t1
+        let mut t2 = self.y.clone();
+        t2.mul_assign(&self.z);
+        t2.double();
+        t3.mul_assign(&t2);
+        x3.sub_assign(&t3);
+        let mut z3 = t2;
+        z3.mul_assign(&t1);
+        z3.double();
+        z3.double();
+
+        self.x = x3;
+        self.y = y3;
+        self.z = z3;
+    }
+
+    fn add_assign_a_is_zero_impl(&mut self, other: &Self) {
+        // Algorithm 7 of https://eprint.iacr.org/2015/1060.pdf
+        let b3 = self.b3();
+
+        // t0 = X1*X2, t1 = Y1*Y2, t2 = Z1*Z2
+        let mut t0 = self.x.clone();
+        t0.mul_assign(&other.x);
+        let mut t1 = self.y.clone();
+        t1.mul_assign(&other.y);
+        let mut t2 = self.z.clone();
+        t2.mul_assign(&other.z);
+
+        // t3 = (X1+Y1)*(X2+Y2) - t0 - t1
+        let mut t3 = self.x.clone();
+        t3.add_assign(&self.y);
+        let mut t4 = other.x.clone();
+        t4.add_assign(&other.y);
+        t3.mul_assign(&t4);
+        t3.sub_assign(&t0);
+        t3.sub_assign(&t1);
+
+        // t4 = (Y1+Z1)*(Y2+Z2) - t1 - t2
+        let mut t4 = self.y.clone();
+        t4.add_assign(&self.z);
+        let mut x3 = other.y.clone();
+        x3.add_assign(&other.z);
+        t4.mul_assign(&x3);
+        t4.sub_assign(&t1);
+        t4.sub_assign(&t2);
+
+        // Y3 = (X1+Z1)*(X2+Z2) - t0 - t2
+        let mut y3 = self.x.clone();
+        y3.add_assign(&self.z);
+        let mut x3 = other.x.clone();
+        x3.add_assign(&other.z);
+        y3.mul_assign(&x3);
+        y3.sub_assign(&t0);
+        y3.sub_assign(&t2);
+
+        self.add_assign_a_is_zero_finish(&b3, t0, t1, t2, t3, t4, y3);
+    }
+
+    fn add_assign_mixed_a_is_zero_impl(&mut self, other: &Self) {
+        // Algorithm 8 of https://eprint.iacr.org/2015/1060.pdf, Z2 = 1
+        let b3 = self.b3();
+
+        // t0 = X1*X2, t1 = Y1*Y2
+        let mut t0 = self.x.clone();
+        t0.mul_assign(&other.x);
+        let mut t1 = self.y.clone();
+        t1.mul_assign(&other.y);
+
+        // t3 = (X1+Y1)*(X2+Y2) - t0 - t1
+        let mut t3 = other.x.clone();
+        t3.add_assign(&other.y);
+        let mut t4 = self.x.clone();
+        t4.add_assign(&self.y);
+        t3.mul_assign(&t4);
+        t3.sub_assign(&t0);
+        t3.sub_assign(&t1);
+
+        // t4 = Y2*Z1 + Y1
+        let mut t4 = other.y.clone();
+        t4.mul_assign(&self.z);
+        t4.add_assign(&self.y);
+
+        // Y3 = X2*Z1 + X1
+        let mut y3 = other.x.clone();
+        y3.mul_assign(&self.z);
+        y3.add_assign(&self.x);
+
+        let t2 = self.z.clone();
+
+        self.add_assign_a_is_zero_finish(&b3, t0, t1, t2, t3, t4, y3);
+    }
+
+    // common tail of the Algorithms 7 and 8 for t0 = X1*X2, t1 = Y1*Y2, t2 = Z1*Z2,
+    // t3 = X1*Y2 + X2*Y1, t4 = Y1*Z2 + Y2*Z1, y3 = X1*Z2 + X2*Z1
+    #[allow(clippy::too_many_arguments)]
+    fn add_assign_a_is_zero_finish(
+        &mut self,
+        b3: &Fp<'a, FE, F>,
+        mut t0: Fp<'a, FE, F>,
+        mut t1: Fp<'a, FE, F>,
+        mut t2: Fp<'a, FE, F>,
+        mut t3: Fp<'a, FE, F>,
+        t4: Fp<'a, FE, F>,
+        mut y3: Fp<'a, FE, F>
+    ) {
+        // t0 = 3*t0
+        let mut x3 = t0.clone();
+        x3.double();
+        t0.add_assign(&x3);
+
+        // Z3 = t1 + b3*t2, t1 = t1 - b3*t2
+        t2.mul_assign(b3);
+        let mut z3 = t1.clone();
+        z3.add_assign(&t2);
+        t1.sub_assign(&t2);
+
+        // X3 = t3*t1 - t4*b3*Y3
+        y3.mul_assign(b3);
+        let mut x3 = t4.clone();
+        x3.mul_assign(&y3);
+        let mut t2 = t3.clone();
+        t2.mul_assign(&t1);
+        t2.sub_assign(&x3);
+        let x3 = t2;
+
+        // Y3 = t1*Z3 + Y3*t0
+        y3.mul_assign(&t0);
+        t1.mul_assign(&z3);
+        y3.add_assign(&t1);
+
+        // Z3 = Z3*t4 + t0*t3
+        t3.mul_assign(&t0);
+        z3.mul_assign(&t4);
+        z3.add_assign(&t3);
+
+        self.x = x3;
+        self.y = y3;
+        self.z = z3;
+    }
+
+    fn double_a_is_zero_impl(&mut self) {
+        // Algorithm 9 of https://eprint.iacr.org/2015/1060.pdf
+        let b3 = self.b3();
+
+        // t0 = Y^2, Z3 = 8*t0
+        let mut t0 = self.y.clone();
+        t0.square();
+        let mut z3 = t0.clone();
+        z3.double();
+        z3.double();
+        z3.double();
+
+        // t1 = Y*Z, t2 = b3*Z^2
+        let mut t1 = self.y.clone();
+        t1.mul_assign(&self.z);
+        let mut t2 = self.z.clone();
+        t2.square();
+        t2.mul_assign(&b3);
+
+        // X3 = t2*Z3, Y3 = t0 + t2, Z3 = t1*Z3
+        let mut x3 = t2.clone();
+        x3.mul_assign(&z3);
+        let mut y3 = t0.clone();
+        y3.add_assign(&t2);
+        z3.mul_assign(&t1);
+
+        // t0 = t0 - 3*t2
+        let mut t1 = t2.clone();
+        t1.double();
+        t1.add_assign(&t2);
+        t0.sub_assign(&t1);
+
+        // Y3 = X3 + t0*Y3
+        y3.mul_assign(&t0);
+        y3.add_assign(&x3);
+
+        // X3 = 2*t0*X*Y
+        let mut x3 = self.x.clone();
+        x3.mul_assign(&self.y);
+        x3.mul_assign(&t0);
+        x3.double();
+
+        self.x = x3;
+        self.y = y3;
+        self.z = z3;
+    }
+
+    fn mul_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::zero(self.curve);
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp)
+        {
+            if found_one {
+                res.double();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.add_assign(self);
+            }
+        }
+
+        res
+    }
+
+    fn wnaf_mul_impl<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
+        const WINDOW_SIZE: u32 = 3;
+
+        // odd multiples of the point from -(2^(w-1) - 1) to 2^(w-1) - 1
+        let index_for_positive = (1 << (WINDOW_SIZE-2)) as usize;
+        let mut precomp_table = vec![Self::zero(self.curve); index_for_positive * 2];
+
+        let mut two_self = self.clone();
+        two_self.double();
+
+        let mut precomp = self.clone();
+        for i in 0..index_for_positive {
+            if i != 0 {
+                precomp.add_assign(&two_self);
+            }
+            let mut neg_precomp = precomp.clone();
+            neg_precomp.negate();
+            precomp_table[index_for_positive+i] = precomp.clone();
+            precomp_table[index_for_positive-1-i] = neg_precomp;
+        }
+
+        let wnaf = exp.wnaf(WINDOW_SIZE);
+
+        let mut res = Self::zero(self.curve);
+        let mut found_nonzero = false;
+
+        for w in wnaf.into_iter().rev() {
+            if found_nonzero {
+                res.double();
+            }
+            if w != 0 {
+                found_nonzero = true;
+                if w > 0 {
+                    let idx = (w >> 1) as usize;
+                    res.add_assign(&precomp_table[index_for_positive + idx]);
+                } else {
+                    let idx = ((-w) >> 1) as usize;
+                    res.add_assign(&precomp_table[index_for_positive - 1 - idx]);
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Group for ProjectivePoint<'a, FE, F, GE, G> {
+    fn add_assign(&mut self, other: &Self) {
+        match self.curve.curve_type {
+            CurveType::AIsZero => {
+                self.add_assign_a_is_zero_impl(other);
+            },
+            _ => {
+                self.add_assign_generic_impl(other);
+            }
+        }
+    }
+
+    /// Mixed addition requires `other` to be normalized, point at infinity has
+    /// no affine form so it's the only case that is handled separately
+    fn add_assign_mixed(&mut self, other: &Self) {
+        if other.is_zero() {
+            return;
+        }
+        debug_assert!(other.is_normalized());
+
+        match self.curve.curve_type {
+            CurveType::AIsZero => {
+                self.add_assign_mixed_a_is_zero_impl(other);
+            },
+            _ => {
+                self.add_assign_mixed_generic_impl(other);
+            }
+        }
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        let mut other_neg = other.clone();
+        other_neg.negate();
+        self.add_assign(&other_neg);
+    }
+
+    fn negate(&mut self) {
+        // (0 : -1 : 0) is the same point at infinity
+        self.y.negate();
+    }
+
+    fn double(&mut self) {
+        match self.curve.curve_type {
+            CurveType::AIsZero => {
+                self.double_a_is_zero_impl();
+            },
+            _ => {
+                self.double_generic_impl();
+            }
+        }
+    }
+
+    /// Uses the GLV endomorphism for points on curves of prime order r, same as
+    /// for Jacobian coordinates
+    fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        if self.check_on_curve() {
+            if let Some(glv) = self.curve.glv_parameters_for_prime_order() {
+                // x = X/Z, so it's enough to scale X
+                let mut image = self.clone();
+                image.x.mul_assign(&fp_from_mont_repr(self.curve.base_field, glv.beta));
+
+                return glv.mul(self, &image, Self::zero(self.curve), exp.as_ref());
+            }
+        }
+
+        self.mul_impl(exp)
+    }
+
+    fn wnaf_mul<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
+        self.wnaf_mul_impl(exp)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.z.is_zero()
+    }
+}
+
+/// The same complete formulas over any field, written with operations that don't
+/// branch on the values of elements. Used by the Montgomery ladder of `CurvePoint`
+/// and `TwistPoint` for secret scalars, coordinates are (X, Y, Z) as above
+pub(crate) struct ConstantTimeFormulas<T: ConstantTimeFieldElement> {
+    a: T,
+    b3: T,
+    a_is_zero: bool,
+}
+
+impl<T: ConstantTimeFieldElement> ConstantTimeFormulas<T> {
+    pub(crate) fn new(a: &T, b: &T, curve_type: CurveType) -> Self {
+        let mut b3 = b.clone();
+        b3.double_ct();
+        b3.add_assign_ct(b);
+
+        Self {
+            a: a.clone(),
+            b3,
+            a_is_zero: curve_type == CurveType::AIsZero,
+        }
+    }
+
+    /// Montgomery ladder over the bits of the scalar below `top_bit`, that must be set.
+    /// Every step is a conditional swap, one addition, one doubling and a swap back,
+    /// and the difference of two accumulators is always the point itself
+    pub(crate) fn ladder(&self, point: &[T; 3], scalar: &[u64], top_bit: usize) -> [T; 3] {
+        let mut r0 = point.clone();
+        let mut r1 = point.clone();
+        self.double(&mut r1);
+
+        for i in (0..top_bit).rev() {
+            let bit = (scalar[i / 64] >> (i % 64)) & 1;
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+            self.add(&mut r1, &r0);
+            self.double(&mut r0);
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        r0
+    }
+
+    fn conditional_swap(p: &mut [T; 3], q: &mut [T; 3], choice: u64) {
+        for (a, b) in p.iter_mut().zip(q.iter_mut()) {
+            T::conditional_swap(a, b, choice);
+        }
+    }
+
+    // the choice of formulas depends on the curve only
+    fn add(&self, p: &mut [T; 3], q: &[T; 3]) {
+        if self.a_is_zero {
+            self.add_a_is_zero(p, q);
+        } else {
+            self.add_generic(p, q);
+        }
+    }
+
+    fn double(&self, p: &mut [T; 3]) {
+        if self.a_is_zero {
+            self.double_a_is_zero(p);
+        } else {
+            self.double_generic(p);
+        }
+    }
+
+    fn add_generic(&self, p: &mut [T; 3], q: &[T; 3]) {
+        // Algorithm 1 of https://eprint.iacr.org/2015/1060.pdf
+        let [x1, y1, z1] = &*p;
+        let [x2, y2, z2] = q;
+        let (a, b3) = (&self.a, &self.b3);
+
+        // t0 = X1*X2, t1 = Y1*Y2, t2 = Z1*Z2
+        let mut t0 = x1.clone();
+        t0.mul_assign_ct(x2);
+        let mut t1 = y1.clone();
+        t1.mul_assign_ct(y2);
+        let mut t2 = z1.clone();
+        t2.mul_assign_ct(z2);
+
+        // t3 = (X1+Y1)*(X2+Y2) - t0 - t1
+        let mut t3 = x1.clone();
+        t3.add_assign_ct(y1);
+        let mut t = x2.clone();
+        t.add_assign_ct(y2);
+        t3.mul_assign_ct(&t);
+        t3.sub_assign_ct(&t0);
+        t3.sub_assign_ct(&t1);
+
+        // t4 = (X1+Z1)*(X2+Z2) - t0 - t2
+        let mut t4 = x1.clone();
+        t4.add_assign_ct(z1);
+        let mut t = x2.clone();
+        t.add_assign_ct(z2);
+        t4.mul_assign_ct(&t);
+        t4.sub_assign_ct(&t0);
+        t4.sub_assign_ct(&t2);
+
+        // t5 = (Y1+Z1)*(Y2+Z2) - t1 - t2
+        let mut t5 = y1.clone();
+        t5.add_assign_ct(z1);
+        let mut t = y2.clone();
+        t.add_assign_ct(z2);
+        t5.mul_assign_ct(&t);
+        t5.sub_assign_ct(&t1);
+        t5.sub_assign_ct(&t2);
+
+        // Z3 = a*t4 + b3*t2
+        let mut z3 = a.clone();
+        z3.mul_assign_ct(&t4);
+        let mut t = b3.clone();
+        t.mul_assign_ct(&t2);
+        z3.add_assign_ct(&t);
+
+        // X3 = t1 - Z3, Z3 = t1 + Z3, Y3 = X3*Z3
+        let mut x3 = t1.clone();
+        x3.sub_assign_ct(&z3);
+        z3.add_assign_ct(&t1);
+        let mut y3 = x3.clone();
+        y3.mul_assign_ct(&z3);
+
+        // t1 = 3*t0 + a*t2
+        let mut t1 = t0.clone();
+        t1.double_ct();
+        t1.add_assign_ct(&t0);
+        t2.mul_assign_ct(a);
+        t1.add_assign_ct(&t2);
+
+        // t4 = b3*t4 + a*(t0 - a*t2)
+        t4.mul_assign_ct(b3);
+        let mut t = t0;
+        t.sub_assign_ct(&t2);
+        t.mul_assign_ct(a);
+        t4.add_assign_ct(&t);
+
+        // Y3 = Y3 + t1*t4
+        let mut t = t1.clone();
+        t.mul_assign_ct(&t4);
+        y3.add_assign_ct(&t);
+
+        // X3 = t3*X3 - t5*t4
+        t4.mul_assign_ct(&t5);
+        x3.mul_assign_ct(&t3);
+        x3.sub_assign_ct(&t4);
+
+        // Z3 = t5*Z3 + t3*t1
+        z3.mul_assign_ct(&t5);
+        t1.mul_assign_ct(&t3);
+        z3.add_assign_ct(&t1);
+
+        *p = [x3, y3, z3];
+    }
+
+    fn double_generic(&self, p: &mut [T; 3]) {
+        // Algorithm 3 of https://eprint.iacr.org/2015/1060.pdf
+        let [x, y, z] = &*p;
+        let (a, b3) = (&self.a, &self.b3);
+
+        // t0 = X^2, t1 = Y^2, t2 = Z^2
+        let mut t0 = x.clone();
+        t0.square_ct();
+        let mut t1 = y.clone();
+        t1.square_ct();
+        let mut t2 = z.clone();
+        t2.square_ct();
+
+        // t3 = 2*X*Y, Z3 = 2*X*Z
+        let mut t3 = x.clone();
+        t3.mul_assign_ct(y);
+        t3.double_ct();
+        let mut z3 = x.clone();
+        z3.mul_assign_ct(z);
+        z3.double_ct();
+
+        // Y3 = a*Z3 + b3*t2
+        let mut t = a.clone();
+        t.mul_assign_ct(&z3);
+        let mut y3 = b3.clone();
+        y3.mul_assign_ct(&t2);
+        y3.add_assign_ct(&t);
+
+        // X3 = t1 - Y3, Y3 = (t1 - Y3)*(t1 + Y3), X3 = t3*X3
+        let mut x3 = t1.clone();
+        x3.sub_assign_ct(&y3);
+        y3.add_assign_ct(&t1);
+        y3.mul_assign_ct(&x3);
+        x3.mul_assign_ct(&t3);
+
+        // t3 = a*(t0 - a*t2) + b3*Z3
+        z3.mul_assign_ct(b3);
+        t2.mul_assign_ct(a);
+        let mut t3 = t0.clone();
+        t3.sub_assign_ct(&t2);
+        t3.mul_assign_ct(a);
+        t3.add_assign_ct(&z3);
+
+        // Y3 = Y3 + (3*t0 + t2)*t3
+        let mut t4 = t0.clone();
+        t4.double_ct();
+        t4.add_assign_ct(&t0);
+        t4.add_assign_ct(&t2);
+        t4.mul_assign_ct(&t3);
+        y3.add_assign_ct(&t4);
+
+        // t2 = 2*Y*Z, X3 = X3 - t2*t3, Z3 = 4*t2*t1
+        let mut t2 = y.clone();
+        t2.mul_assign_ct(z);
+        t2.double_ct();
+        t3.mul_assign_ct(&t2);
+        x3.sub_assign_ct(&t3);
+        let mut z3 = t2;
+        z3.mul_assign_ct(&t1);
+        z3.double_ct();
+        z3.double_ct();
+
+        *p = [x3, y3, z3];
+    }
+
+    fn add_a_is_zero(&self, p: &mut [T; 3], q: &[T; 3]) {
+        // Algorithm 7 of https://eprint.iacr.org/2015/1060.pdf
+        let [x1, y1, z1] = &*p;
+        let [x2, y2, z2] = q;
+        let b3 = &self.b3;
+
+        // t0 = X1*X2, t1 = Y1*Y2, t2 = Z1*Z2
+        let mut t0 = x1.clone();
+        t0.mul_assign_ct(x2);
+        let mut t1 = y1.clone();
+        t1.mul_assign_ct(y2);
+        let mut t2 = z1.clone();
+        t2.mul_assign_ct(z2);
+
+        // t3 = (X1+Y1)*(X2+Y2) - t0 - t1
+        let mut t3 = x1.clone();
+        t3.add_assign_ct(y1);
+        let mut t = x2.clone();
+        t.add_assign_ct(y2);
+        t3.mul_assign_ct(&t);
+        t3.sub_assign_ct(&t0);
+        t3.sub_assign_ct(&t1);
+
+        // t4 = (Y1+Z1)*(Y2+Z2) - t1 - t2
+        let mut t4 = y1.clone();
+        t4.add_assign_ct(z1);
+        let mut t = y2.clone();
+        t.add_assign_ct(z2);
+        t4.mul_assign_ct(&t);
+        t4.sub_assign_ct(&t1);
+        t4.sub_assign_ct(&t2);
+
+        // Y3 = (X1+Z1)*(X2+Z2) - t0 - t2
+        let mut y3 = x1.clone();
+        y3.add_assign_ct(z1);
+        let mut t = x2.clone();
+        t.add_assign_ct(z2);
+        y3.mul_assign_ct(&t);
+        y3.sub_assign_ct(&t0);
+        y3.sub_assign_ct(&t2);
+
+        // t0 = 3*t0
+        let mut t = t0.clone();
+        t.double_ct();
+        t0.add_assign_ct(&t);
+
+        // Z3 = t1 + b3*t2, t1 = t1 - b3*t2
+        t2.mul_assign_ct(b3);
+        let mut z3 = t1.clone();
+        z3.add_assign_ct(&t2);
+        t1.sub_assign_ct(&t2);
+
+        // X3 = t3*t1 - t4*b3*Y3
+        y3.mul_assign_ct(b3);
+        let mut t = t4.clone();
+        t.mul_assign_ct(&y3);
+        let mut x3 = t3.clone();
+        x3.mul_assign_ct(&t1);
+        x3.sub_assign_ct(&t);
+
+        // Y3 = t1*Z3 + Y3*t0
+        y3.mul_assign_ct(&t0);
+        t1.mul_assign_ct(&z3);
+        y3.add_assign_ct(&t1);
+
+        // Z3 = Z3*t4 + t0*t3
+        t3.mul_assign_ct(&t0);
+        z3.mul_assign_ct(&t4);
+        z3.add_assign_ct(&t3);
+
+        *p = [x3, y3, z3];
+    }
+
+    fn double_a_is_zero(&self, p: &mut [T; 3]) {
+        // Algorithm 9 of https://eprint.iacr.org/2015/1060.pdf
+        let [x, y, z] = &*p;
+        let b3 = &self.b3;
+
+        // t0 = Y^2, Z3 = 8*t0
+        let mut t0 = y.clone();
+        t0.square_ct();
+        let mut z3 = t0.clone();
+        z3.double_ct();
+        z3.double_ct();
+        z3.double_ct();
+
+        // t1 = Y*Z, t2 = b3*Z^2
+        let mut t1 = y.clone();
+        t1.mul_assign_ct(z);
+        let mut t2 = z.clone();
+        t2.square_ct();
+        t2.mul_assign_ct(b3);
+
+        // X3 = t2*Z3, Y3 = t0 + t2, Z3 = t1*Z3
+        let mut x3 = t2.clone();
+        x3.mul_assign_ct(&z3);
+        let mut y3 = t0.clone();
+        y3.add_assign_ct(&t2);
+        z3.mul_assign_ct(&t1);
+
+        // t0 = t0 - 3*t2
+        let mut t = t2.clone();
+        t.double_ct();
+        t.add_assign_ct(&t2);
+        t0.sub_assign_ct(&t);
+
+        // Y3 = X3 + t0*Y3
+        y3.mul_assign_ct(&t0);
+        y3.add_assign_ct(&x3);
+
+        // X3 = 2*t0*X*Y
+        let mut x3 = x.clone();
+        x3.mul_assign_ct(y);
+        x3.mul_assign_ct(&t0);
+        x3.double_ct();
+
+        *p = [x3, y3, z3];
+    }
+}
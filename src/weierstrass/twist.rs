@@ -1,8 +1,9 @@
 use crate::field::SizedPrimeField;
 use crate::fp::Fp;
 use crate::representation::ElementRepr;
-use crate::traits::{FieldElement, BitIterator};
-use super::{CurveType, Group};
+use crate::traits::{FieldElement, BitIterator, ConditionallySelectable, ConstantTimeFieldElement};
+use super::{CurveType, Group, fixed_length_scalar};
+use super::projective::ConstantTimeFormulas;
 use crate::extension_towers::fp2::{Fp2, Extension2};
 
 pub struct WeierstrassCurveTwist<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> {
@@ -20,10 +21,7 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         a: Fp2<'a, FE, F>, 
         b: Fp2<'a, FE, F>,
     ) -> Self {
-        let mut curve_type = CurveType::Generic;
-        if a.is_zero() {
-            curve_type = CurveType::AIsZero;
-        }
+        let curve_type = CurveType::from_coefficients(&a, &b, Fp2::one(extension_field));
 
         Self {
             base_field: extension_field,
@@ -54,6 +52,15 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     }
 }
 
+impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> ConditionallySelectable for TwistPoint<'a, FE, F, GE, G> {
+    #[inline(always)]
+    fn conditional_assign(&mut self, other: &Self, choice: u64) {
+        self.x.conditional_assign(&other.x, choice);
+        self.y.conditional_assign(&other.y, choice);
+        self.z.conditional_assign(&other.z, choice);
+    }
+}
+
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> TwistPoint<'a, FE, F, GE, G> {    
     pub fn zero(curve: &'a WeierstrassCurveTwist<'a, FE, F, GE, G>) -> Self {
         Self {
@@ -356,6 +363,64 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         }
     }
 
+    /// Untwist-Frobenius-twist endomorphism (x^p * x_coeff, y^p * y_coeff) with
+    /// coefficients that depend on the twist. Applies in Jacobian coordinates as
+    /// is, since Frobenius is a field automorphism
+    pub(crate) fn psi(&self, x_coeff: &Fp2<'a, FE, F>, y_coeff: &Fp2<'a, FE, F>) -> Self {
+        let mut res = self.clone();
+
+        res.x.c1.negate();
+        res.x.mul_assign(x_coeff);
+
+        res.y.c1.negate();
+        res.y.mul_assign(y_coeff);
+
+        res.z.c1.negate();
+
+        res
+    }
+
+    /// Montgomery ladder for secret scalars. The point must be in the subgroup of
+    /// order r, and the scalar is reduced modulo r. Every scalar takes the same sequence
+    /// of conditional swaps and complete projective additions and doublings, and all
+    /// field operations are branchless. Only the point, that is public, is branched on
+    pub fn mul_constant_time<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        let (scalar, top_bit) = fixed_length_scalar(exp, self.curve.scalar_field);
+
+        // (X : Y : Z) in Jacobian coordinates is (X*Z : Y : Z^3) in homogeneous ones
+        let mut x = self.x.clone();
+        x.mul_assign(&self.z);
+        let mut z = self.z.clone();
+        z.square();
+        z.mul_assign(&self.z);
+
+        let formulas = ConstantTimeFormulas::new(&self.curve.a, &self.curve.b, self.curve.curve_type);
+        let [x, y, z] = formulas.ladder(&[x, self.y.clone(), z], &scalar, top_bit);
+
+        // back to (X*Z : Y*Z^2 : Z), the point at infinity (0 : Y : 0) becomes (0 : 0 : 0)
+        // and is replaced by the usual zero
+        let mut res_x = x;
+        res_x.mul_assign_ct(&z);
+        let mut res_y = z.clone();
+        res_y.square_ct();
+        res_y.mul_assign_ct(&y);
+        let is_zero = z.is_zero_ct();
+
+        let mut res = Self {
+            curve: self.curve,
+            x: res_x,
+            y: res_y,
+            z
+        };
+        res.conditional_assign(&Self::zero(self.curve), is_zero);
+
+        res
+    }
+
     fn mul_impl<S: AsRef<[u64]>>(&self, exp: S) -> Self {
         let one = Fp2::<'a, FE, F>::one(&self.curve.base_field);
         if self.z == one {
@@ -587,25 +652,69 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
         c.double();
         self.y.sub_assign(&c);
     }
+
+    fn double_a_is_minus_3_impl(&mut self) {
+        if self.is_zero() {
+            return;
+        }
+
+        // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#doubling-dbl-2001-b
+
+        // delta = Z1^2
+        let mut delta = self.z.clone();
+        delta.square();
+
+        // gamma = Y1^2
+        let mut gamma = self.y.clone();
+        gamma.square();
+
+        // beta = X1*gamma
+        let mut beta = self.x.clone();
+        beta.mul_assign(&gamma);
+
+        // alpha = 3*(X1-delta)*(X1+delta)
+        let mut t0 = self.x.clone();
+        t0.sub_assign(&delta);
+        let mut alpha = self.x.clone();
+        alpha.add_assign(&delta);
+        alpha.mul_assign(&t0);
+        let t0 = alpha.clone();
+        alpha.double();
+        alpha.add_assign(&t0);
+
+        // X3 = alpha^2-8*beta
+        beta.double();
+        beta.double();
+        self.x = alpha.clone();
+        self.x.square();
+        self.x.sub_assign(&beta);
+        self.x.sub_assign(&beta);
+
+        // Z3 = (Y1+Z1)^2-gamma-delta
+        self.z.add_assign(&self.y);
+        self.z.square();
+        self.z.sub_assign(&gamma);
+        self.z.sub_assign(&delta);
+
+        // Y3 = alpha*(4*beta-X3)-8*gamma^2
+        self.y = beta;
+        self.y.sub_assign(&self.x);
+        self.y.mul_assign(&alpha);
+        gamma.square();
+        gamma.double();
+        gamma.double();
+        gamma.double();
+        self.y.sub_assign(&gamma);
+    }
 }
 
 impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: SizedPrimeField<Repr = GE>> Group for TwistPoint<'a, FE, F, GE, G> {
     fn add_assign(&mut self, other: &Self) {
-        match self.curve.curve_type {
-            _ => {
-                self.add_assign_generic_impl(&other);
-            },
-            _ => {unimplemented!()}
-        }
+        self.add_assign_generic_impl(other);
     }
 
     fn add_assign_mixed(&mut self, other: &Self) {
-        match self.curve.curve_type {
-            _ => {
-                self.add_assign_mixed_generic_impl(&other);
-            },
-            _ => {unimplemented!()}
-        }
+        self.add_assign_mixed_generic_impl(other);
     }
 
     fn sub_assign(&mut self, other: &Self) {
@@ -615,50 +724,32 @@ impl<'a, FE: ElementRepr, F: SizedPrimeField<Repr = FE>, GE: ElementRepr, G: Siz
     }
 
     fn negate(&mut self) {
-        match self.curve.curve_type {
-            _ => {
-                self.negate_impl();
-            },
-            _ => {unimplemented!()}
-        }
+        self.negate_impl();
     }
 
     fn mul<S: AsRef<[u64]>>(&self, exp: S) -> Self {
-        match self.curve.curve_type {
-            _ => {
-                return self.mul_impl(exp);
-            },
-            _ => {unimplemented!()}
-        }
+        self.mul_impl(exp)
     }
 
     fn is_zero(&self) -> bool {
-        match self.curve.curve_type {
-            _ => {
-                return self.is_zero_generic_impl();
-            },
-            _ => {unimplemented!()}
-        }
+        self.is_zero_generic_impl()
     }
 
     fn double(&mut self) {
         match self.curve.curve_type {
-            CurveType::Generic => {
+            CurveType::Generic | CurveType::BIsZero => {
                 self.double_generic_impl();
             },
             CurveType::AIsZero => {
                 self.double_a_is_zero_impl();
+            },
+            CurveType::AIsMinus3 => {
+                self.double_a_is_minus_3_impl();
             }
-            _ => {unimplemented!()}
         }
     }
 
     fn wnaf_mul<S: crate::representation::IntoWnaf>(&self, exp: S) -> Self {
-        match self.curve.curve_type {
-            _ => {
-                return self.wnaf_mul_impl(exp);
-            },
-            _ => {unimplemented!()}
-        }
+        self.wnaf_mul_impl(exp)
     }
 }
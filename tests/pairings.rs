@@ -0,0 +1,162 @@
+extern crate eip1829;
+extern crate num_bigint;
+extern crate num_traits;
+
+use eip1829::CurveContext;
+use eip1829::field::{U256Repr, U384Repr, PrimeField};
+use eip1829::fp::Fp;
+use eip1829::representation::ElementRepr;
+use eip1829::extension_towers::fp2::{Fp2, Extension2};
+use eip1829::weierstrass::Group;
+use eip1829::weierstrass::curve::CurvePoint;
+use eip1829::weierstrass::twist::{TwistPoint, WeierstrassCurveTwist};
+use eip1829::pairings::PairingEngine;
+use eip1829::pairings::bn::{self, BnContext};
+use eip1829::pairings::bls12::{self, Bls12Context};
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use std::slice;
+use std::sync::Arc;
+
+fn fp<'a, FE: ElementRepr>(field: &'a PrimeField<FE>, decimal: &str) -> Fp<'a, FE, PrimeField<FE>> {
+    Fp::from_be_bytes(field, &BigUint::from_str_radix(decimal, 10).unwrap().to_bytes_be(), true).unwrap()
+}
+
+fn fp2<'a, FE: ElementRepr>(extension: &'a Extension2<'a, FE, PrimeField<FE>>, c0: &str, c1: &str) -> Fp2<'a, FE, PrimeField<FE>> {
+    let mut el = Fp2::zero(extension);
+    el.c0 = fp(extension.field, c0);
+    el.c1 = fp(extension.field, c1);
+
+    el
+}
+
+fn twist_point<'a, FE: ElementRepr, GE: ElementRepr>(
+    twist: &'a WeierstrassCurveTwist<'a, FE, PrimeField<FE>, GE, PrimeField<GE>>,
+    extension: &'a Extension2<'a, FE, PrimeField<FE>>,
+    coordinates: [&str; 4]
+) -> TwistPoint<'a, FE, PrimeField<FE>, GE, PrimeField<GE>> {
+    let x = fp2(extension, coordinates[0], coordinates[1]);
+    let y = fp2(extension, coordinates[2], coordinates[3]);
+
+    TwistPoint::point_from_xy(twist, x, y)
+}
+
+fn bn254() -> BnContext<U256Repr, U256Repr> {
+    let modulus = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208583", 10).unwrap();
+    let order = BigUint::from_str_radix("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+    let minus_one = &modulus - 1u64;
+    let curve = CurveContext::new(modulus, order, BigUint::from(0u64), BigUint::from(3u64)).unwrap();
+
+    // Fp2 = Fp[u]/(u^2 + 1), Fp6 = Fp2[v]/(v^3 - (u + 9))
+    BnContext::new(curve, &BigUint::from(4965661367192848881u64), false, &minus_one, (&BigUint::from(9u64), &BigUint::from(1u64)), bn::TwistType::D).unwrap()
+}
+
+fn bls12_381() -> Bls12Context<U384Repr, U256Repr> {
+    let modulus = BigUint::from_str_radix("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", 10).unwrap();
+    let order = BigUint::from_str_radix("52435875175126190479447740508185965837690552500527637822603658699938581184513", 10).unwrap();
+    let minus_one = &modulus - 1u64;
+    let curve = CurveContext::new(modulus, order, BigUint::from(0u64), BigUint::from(4u64)).unwrap();
+
+    // Fp2 = Fp[u]/(u^2 + 1), Fp6 = Fp2[v]/(v^3 - (u + 1))
+    Bls12Context::new(curve, &BigUint::from(0xd201000000010000u64), true, &minus_one, (&BigUint::from(1u64), &BigUint::from(1u64)), bls12::TwistType::M).unwrap()
+}
+
+#[test]
+fn test_bn254_context() {
+    let context = Arc::new(bn254());
+
+    // wrong sign of u
+    let curve = context.curve().clone();
+    let minus_one = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208582", 10).unwrap();
+    assert!(BnContext::new(curve, &BigUint::from(4965661367192848881u64), true, &minus_one, (&BigUint::from(9u64), &BigUint::from(1u64)), bn::TwistType::D).is_err());
+
+    let handle = {
+        let context = context.clone();
+        std::thread::spawn(move || {
+            context.with_engine(|engine| {
+                let p = CurvePoint::point_from_xy(engine.curve, fp(engine.base_field, "1"), fp(engine.base_field, "2"));
+                let q = twist_point(engine.curve_twist, engine.fp2_extension, [
+                    "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+                    "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+                    "4082367875863433681332203403145435568316851327593401208105741076214120093531"
+                ]);
+                assert!(engine.is_in_g1(&p));
+                assert!(engine.is_in_g2(&q));
+
+                // prepared points survive a round trip through bytes
+                let prepared = engine.prepare(&q);
+                let prepared = bn::PreparedTwistPoint::from_bytes(engine.fp2_extension, &prepared.to_bytes()).unwrap();
+                let expected = engine.pair(slice::from_ref(&p), slice::from_ref(&q)).unwrap();
+                assert!(engine.pair_prepared(slice::from_ref(&p), &[prepared]).unwrap() == expected);
+
+                // e([k]P, Q) = e(P, Q)^k
+                let k = Fp::from_be_bytes(context.curve().scalar_field(), &[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0], true).unwrap();
+                let gt = engine.pair_gt(slice::from_ref(&p), slice::from_ref(&q)).unwrap();
+                let mut p_k = p.mul(k.into_repr());
+                p_k.normalize();
+                assert!(engine.pair_gt(slice::from_ref(&p_k), slice::from_ref(&q)).unwrap() == gt.pow(&k));
+                assert!(engine.gt_from_bytes(&gt.to_bytes()).unwrap() == gt);
+
+                // e([k]P, Q) * e(-P, [k]Q) = 1
+                let mut q_k = q.mul_constant_time(k.into_repr());
+                q_k.normalize();
+                let mut minus_p = p.clone();
+                minus_p.negate();
+                assert!(engine.pairing_check(&[(p_k, q), (minus_p, q_k)]).unwrap());
+
+                // secret scalars
+                assert!(p.mul_constant_time(k.into_repr()).into_xy() == p.mul(k.into_repr()).into_xy());
+
+                // T6 torus compression of the target group element
+                let (m1, m2) = gt.as_fp12().torus_t6_compress().unwrap();
+                let decompressed = eip1829::extension_towers::fp12_as_2_over3_over_2::Fp12::torus_t6_decompress(engine.fp12_extension, &m1, &m2).unwrap();
+                assert!(&decompressed == gt.as_fp12());
+            })
+        })
+    };
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_bls12_381_context() {
+    let context = bls12_381();
+
+    // BN254 modulus and order don't match x
+    let curve = bn254().curve().clone();
+    let minus_one = BigUint::from_str_radix("21888242871839275222246405745257275088696311157297823662689037894645226208582", 10).unwrap();
+    assert!(Bls12Context::new(curve, &BigUint::from(0xd201000000010000u64), true, &minus_one, (&BigUint::from(1u64), &BigUint::from(1u64)), bls12::TwistType::M).is_err());
+
+    context.with_engine(|engine| {
+        let p = CurvePoint::point_from_xy(
+            engine.curve,
+            fp(engine.base_field, "3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507"),
+            fp(engine.base_field, "1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569")
+        );
+        let q = twist_point(engine.curve_twist, engine.fp2_extension, [
+            "352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160",
+            "3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758",
+            "1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905",
+            "927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582"
+        ]);
+        assert!(engine.is_in_g1(&p));
+        assert!(engine.is_in_g2(&q));
+
+        let prepared = engine.prepare(&q);
+        let prepared = bls12::PreparedTwistPoint::from_bytes(engine.fp2_extension, &prepared.to_bytes()).unwrap();
+        let gt = engine.pair_gt(slice::from_ref(&p), slice::from_ref(&q)).unwrap();
+        assert!(engine.pair_prepared(slice::from_ref(&p), &[prepared]).unwrap() == *gt.as_fp12());
+        assert!(!gt.is_one());
+
+        // e([2]P, Q) = e(P, [2]Q) = e(P, Q)^2
+        let mut two_p = p.mul([2u64]);
+        two_p.normalize();
+        let mut two_q = engine.mul_g2(&q, [2u64]);
+        two_q.normalize();
+        let mut squared = gt.clone();
+        squared.square();
+        assert!(engine.pair_gt(&[two_p], slice::from_ref(&q)).unwrap() == squared);
+        assert!(engine.pair_gt(&[p], &[two_q]).unwrap() == squared);
+    });
+}